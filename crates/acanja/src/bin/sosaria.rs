@@ -2,33 +2,36 @@ use acanja::effect::{parse_gremlins, parse_mark, parse_moongate_travel, parse_wi
 use acanja::loader::{GameConfigLoader, StartMap};
 use acanja::map::prefab::Prefabs;
 use acanja::tasks::AnchorPos;
+use gw_app::screen::BoxedScreen;
 use gw_app::*;
 use gw_ecs::{Builder, Entity, Join, World};
-use gw_util::grid::{dump_grid, random_point_with};
 use gw_util::point::Point;
-use gw_util::rng::RandomNumberGenerator;
 use gw_world::action::idle::IdleAction;
 use gw_world::action::move_step::MoveStepAction;
-use gw_world::being::{spawn_being, Being, BeingFlags, BeingKinds, Stats};
+use gw_world::action::search::SearchAction;
+use gw_world::being::{
+    register_level_up_screen, spawn_being, Being, BeingFlags, BeingKinds, Stats,
+};
 use gw_world::camera::{update_camera_follows, Camera};
 use gw_world::combat::Melee;
 use gw_world::effect::{register_effect_parser, BoxedEffect};
 use gw_world::fov::update_fov;
 use gw_world::hero::Hero;
-use gw_world::horde::{pick_random_horde, spawn_horde, HordeRef, HordeSpawner, SpawnRef};
-use gw_world::map::{ensure_area_grid, AreaGrid, Cell, Map};
+use gw_world::horde::spawn_periodic_horde;
+use gw_world::level::travel;
+use gw_world::map::{Cell, Map};
 use gw_world::position::Position;
 use gw_world::task::{do_next_task, DoNextTaskResult, Executor, Task, UserAction};
-use gw_world::task::{get_hero_entity, register_task};
+use gw_world::task::{get_hero_entity, register_task, start_explore, start_travel};
 use gw_world::tile::Tiles;
-use gw_world::widget::Viewport;
-use std::ops::DerefMut;
+use gw_world::widget::{LevelUpScreen, LookScreen, MessageLogScreen, MessagePanel, Viewport};
 
 // const CAMERA_WIDTH: u32 = 1024 / 32;
 // const CAMERA_HEIGHT: u32 = 768 / 32;
 
 struct MainScreen {
     viewport: Viewport,
+    messages: MessagePanel,
 }
 
 impl MainScreen {
@@ -36,11 +39,17 @@ impl MainScreen {
         let viewport = Viewport::builder("VIEWPORT")
             // .size(11, 11)
             .font("assets/font_32x58.png")
-            // .extents(0.0, 0.0, 0.85, 0.85)
+            .extents(0.0, 0.0, 1.0, 0.85)
             // .wrap(Wrap::XY)
             .build();
 
-        Box::new(MainScreen { viewport })
+        let messages = MessagePanel::builder()
+            .font("assets/font_32x58.png")
+            .size(64, 4)
+            .extents(0.0, 0.85, 1.0, 1.0)
+            .build();
+
+        Box::new(MainScreen { viewport, messages })
     }
 
     fn pre_update(&mut self, ecs: &mut Ecs) {
@@ -113,6 +122,9 @@ impl Screen for MainScreen {
     }
 
     fn input(&mut self, ecs: &mut Ecs, ev: &AppEvent) -> ScreenResult {
+        if let Some(result) = self.messages.input(ecs.current_world_mut(), ev) {
+            return result;
+        }
         if let Some(result) = self.viewport.input(ecs.current_world_mut(), ev) {
             return result;
         }
@@ -154,6 +166,26 @@ impl Screen for MainScreen {
                 '.' | ' ' => {
                     hero_idle(ecs.current_world_mut());
                 }
+                's' => {
+                    hero_search(ecs.current_world_mut());
+                }
+                'x' => {
+                    start_explore(ecs.current_world_mut());
+                }
+                'l' => {
+                    let look = LookScreen::builder("LOOK")
+                        .font("assets/font_32x58.png")
+                        .extents(0.0, 0.0, 1.0, 0.85)
+                        .build();
+                    return ScreenResult::Push(look);
+                }
+                'p' => {
+                    let history = MessageLogScreen::builder()
+                        .font("assets/font_32x58.png")
+                        .page_size(64, 24)
+                        .build();
+                    return ScreenResult::Push(history);
+                }
                 't' => {
                     let executor = ecs.read_resource::<Executor>();
                     println!("TASKS = {:?}", *executor);
@@ -218,6 +250,9 @@ impl Screen for MainScreen {
                         log(format!("STATS - {:?}", *stats));
                     }
                 }
+                drop(map);
+
+                start_travel(ecs.current_world_mut(), pt);
             }
             _ => {}
         }
@@ -230,6 +265,11 @@ impl Screen for MainScreen {
         // spawn things?
         self.pre_update(ecs);
 
+        // Wait for the player to read the messages
+        if self.messages.has_more(ecs.current_world()) {
+            return ScreenResult::Continue;
+        }
+
         // let mut levels = ecs.resources.get_mut::<Levels>().unwrap();
         // let level = levels.current_mut();
 
@@ -244,6 +284,9 @@ impl Screen for MainScreen {
             // let res = executor.do_next_action(&mut *level);
             let res = do_next_task(ecs.current_world_mut());
             self.post_action(ecs);
+            if travel(ecs) {
+                return ScreenResult::Continue;
+            }
             match res {
                 DoNextTaskResult::Done => {
                     return ScreenResult::Continue;
@@ -262,7 +305,9 @@ impl Screen for MainScreen {
 
     fn render(&mut self, app: &mut Ecs) {
         self.viewport.draw_level(app.current_world_mut());
+        self.messages.draw(app.current_world());
         self.viewport.render(app);
+        self.messages.render(app);
     }
 }
 
@@ -272,6 +317,7 @@ fn main() {
     register_effect_parser("mark", parse_mark);
     register_effect_parser("moongate_travel", parse_moongate_travel);
     register_effect_parser("moongate", parse_moongate_travel);
+    register_level_up_screen(level_up_screen);
 
     let app = AppBuilder::new(1024, 768)
         .title("Acanja - World Viewer")
@@ -294,6 +340,13 @@ fn main() {
     app.run(MainScreen::new());
 }
 
+fn level_up_screen(world: &mut World, entity: Entity, level: u32) -> BoxedScreen {
+    LevelUpScreen::builder()
+        .font("assets/font_32x58.png")
+        .size(32, 12)
+        .build(world, entity, level)
+}
+
 fn move_hero(level: &mut World, dx: i32, dy: i32) {
     let hero_entity = get_hero_entity(level);
     let mut user_action = level.write_resource::<UserAction>();
@@ -316,6 +369,18 @@ fn hero_idle(level: &mut World) {
     user_action.set(Box::new(IdleAction::new(hero_entity, act_time)));
 }
 
+fn hero_search(level: &mut World) {
+    let hero_entity = get_hero_entity(level);
+    let act_time = level
+        .read_component::<Being>()
+        .get(hero_entity)
+        .unwrap()
+        .act_time;
+
+    let mut user_action = level.write_resource::<UserAction>();
+    user_action.set(Box::new(SearchAction::new(hero_entity, act_time)));
+}
+
 fn get_hero_action_effects(
     world: &mut World,
     action: &str,
@@ -353,82 +418,10 @@ fn try_fire_hero_action(world: &mut World, action: &str) -> bool {
 }
 
 fn spawn_hordes(ecs: &mut Ecs) {
-    let current_time = ecs.read_resource::<Executor>().time();
     let depth = 1;
 
-    let (max_alive, spawn_id) = {
-        let level = ecs.current_world_mut();
-        let mut info = match level.try_write_resource::<HordeSpawner>() {
-            None => {
-                return;
-            }
-            Some(info) => info,
-        };
-        if info.next_time > current_time {
-            return;
-        }
-        info.next_time += info.check_delay;
-        (info.max_alive, info.id.clone())
-    };
-
-    {
-        let beings = ecs.read_component::<SpawnRef>();
-
-        let count = beings.join().filter(|b| b.is(&spawn_id)).count() as u32;
-        if count >= max_alive {
-            log(format!(
-                "spawn_hordes - too many alive => count:{} >= max_alive:{}",
-                count, max_alive
-            ));
-            return;
-        }
-        log(format!("alive count = {} / {}", count, max_alive));
-    }
-
-    // We got to here so we need to spawn a horde...
-    if let Some(horde) = pick_random_horde(ecs.current_world_mut(), depth) {
-        log(format!("SPAWN TEST - {:?}", horde));
-
-        // need spawn point...
-
-        ensure_area_grid(ecs.current_world_mut());
-        let mut level = ecs.current_world_mut();
-        let spawn_point = {
-            let area_grid = level.read_resource::<AreaGrid>();
-            let mut rng = level.write_resource::<RandomNumberGenerator>();
-
-            let spawn_point = match random_point_with(&area_grid.grid(), |v, _, _| *v > 0, &mut rng)
-            {
-                None => {
-                    log(format!("Failed to find point to spawn horde"));
-                    dump_grid(area_grid.grid());
-                    return;
-                }
-                Some(point) => point,
-            };
-
-            let map = level.read_resource::<Map>();
-            let map_idx = map.get_index(spawn_point.x, spawn_point.y).unwrap();
-            if map.is_blocked(map_idx) {
-                log(format!(
-                    "Not spawning - location blocked - {:?}",
-                    spawn_point
-                ));
-                return;
-            }
-            spawn_point
-        };
-
-        let leader_entity = spawn_horde(&horde, level.deref_mut(), spawn_point);
-
-        let _ = level
-            .write_component()
-            .insert(leader_entity, SpawnRef::new(spawn_id));
-
-        log(format!(
-            "Spawned horde leader = {:?} @ {:?}",
-            leader_entity, spawn_point
-        ));
+    if let Some(leader_entity) = spawn_periodic_horde(ecs.current_world_mut(), depth) {
+        log(format!("Spawned horde leader = {:?}", leader_entity));
     }
 }
 
@@ -1,7 +1,13 @@
 use super::LevelLoader;
 use gw_app::loader::{LoadError, LoadHandler, Loader};
 use gw_ecs::{atomize::Atom, Ecs};
-use gw_world::{being::BeingKindsLoader, horde::HordesLoader, tile::TilesLoader};
+use gw_world::{
+    being::{BeingKindsLoader, ProgressionLoader},
+    blueprint::BlueprintsLoader,
+    horde::HordesLoader,
+    item::ItemKindsLoader,
+    tile::TilesLoader,
+};
 
 #[derive(Clone, Debug)]
 pub struct StartMap {
@@ -89,6 +95,26 @@ impl LoadHandler for GameConfigLoader {
                 }
             }
 
+            // Load ITEMS
+            if let Some(item_value) = table.get(&"items".into()) {
+                if item_value.is_string() {
+                    let filename = item_value.to_string();
+                    loader
+                        .load_file(&filename, Box::new(ItemKindsLoader::new()))
+                        .expect("Failed to load items file!");
+                }
+            }
+
+            // Load PROGRESSION (xp levels)
+            if let Some(progression_value) = table.get(&"progression".into()) {
+                if progression_value.is_string() {
+                    let filename = progression_value.to_string();
+                    loader
+                        .load_file(&filename, Box::new(ProgressionLoader::new()))
+                        .expect("Failed to load progression file!");
+                }
+            }
+
             // Load HORDES
             if let Some(horde_value) = table.get(&"hordes".into()) {
                 if horde_value.is_string() {
@@ -98,6 +124,16 @@ impl LoadHandler for GameConfigLoader {
                         .expect("Failed to load hordes file!");
                 }
             }
+
+            // Load BLUEPRINTS
+            if let Some(blueprint_value) = table.get(&"blueprints".into()) {
+                if blueprint_value.is_string() {
+                    let filename = blueprint_value.to_string();
+                    loader
+                        .load_file(&filename, Box::new(BlueprintsLoader::new()))
+                        .expect("Failed to load blueprints file!");
+                }
+            }
         }
 
         ecs.insert_global(StartMap::new(start_map.as_str(), "START"));
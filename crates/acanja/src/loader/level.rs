@@ -1,7 +1,8 @@
 use super::{load_level_data_file, LevelDataLoader};
 use gw_app::{
+    color::get_color,
     loader::{LoadError, LoadHandler, Loader},
-    log,
+    log, RGBA,
 };
 use gw_ecs::{Ecs, ReadGlobal, SystemData, World, WriteGlobal};
 use gw_util::{
@@ -18,6 +19,7 @@ use gw_world::{
     fov::FOV,
     horde::{parse_spawn, HordeSpawner},
     level::NeedsDraw,
+    light::LightMap,
     log::Logger,
     map::Map,
     task::UserAction,
@@ -65,6 +67,7 @@ pub struct LevelData {
     pub map_size: (u32, u32),
     pub map_wrap: bool,
     pub welcome: Option<String>,
+    pub ambient: Option<RGBA>,
     pub camera_size: (u32, u32),
     pub region: Option<Rect>,
     pub fov: Option<u32>,
@@ -82,6 +85,7 @@ impl LevelData {
             map_size: (0, 0),
             map_wrap: false,
             welcome: None,
+            ambient: None,
             camera_size: (11, 11),
             region: None,
             fov: None,
@@ -530,6 +534,14 @@ pub fn load_level_data(ecs: &Ecs, json: Value) -> LevelData {
         }
     };
 
+    level_data.ambient = match root.get(&"ambient".into()) {
+        None => None,
+        Some(val) => match get_color(&val.to_string()) {
+            Err(_) => panic!("Invalid ambient light color - {}", val),
+            Ok(color) => Some(color),
+        },
+    };
+
     level_data.fov = {
         match root.get(&"fov".into()) {
             None => None,
@@ -724,6 +736,10 @@ pub fn make_level<'a>(ecs: &'a mut Ecs, mut level_data: LevelData) -> &'a World
 
     world.insert_resource(map);
 
+    if let Some(ambient) = level_data.ambient {
+        world.write_resource::<LightMap>().set_ambient(ambient);
+    }
+
     for (y, line) in data.iter().enumerate() {
         let y = y as i32;
         if y >= height as i32 {
@@ -45,10 +45,10 @@ fn compute_system(entities: Entities, mut computes: WriteComp<Compute>, mut comm
     }
 }
 
-fn cleanup_system(mut removed_computes: Removed<Compute>, mut commands: Commands) {
+fn cleanup_system(removed_computes: RemovedComponents<Compute>, mut commands: Commands) {
     for entity in removed_computes.iter() {
         println!("!! CLEANUP compute - {}", entity.id());
-        commands.entity(*entity).despawn();
+        commands.entity(entity).despawn();
     }
 }
 
@@ -0,0 +1,197 @@
+use super::Component;
+use crate::entity::Entity;
+use crate::system::Commands;
+
+/// A callback that runs when a component is added, inserted or removed.
+/// The hook gets the value of the component and can queue changes to the world with the `Commands`.
+pub type ComponentHook<T> = fn(&mut Commands, Entity, &T);
+
+/// The lifecycle hooks of a component type.
+///
+/// * `on_add` - the entity did not have the component before the insert
+/// * `on_insert` - every insert, after `on_add`
+/// * `on_remove` - the component was removed, the entity was deleted or moved to another world
+///   or the value was replaced by an insert (with the old value)
+///
+/// Changes made through `get_mut` do not run any hooks.
+///
+/// The commands are applied by [`World::apply_hooks`](crate::world::World::apply_hooks), which happens
+/// after `EntityBuilder` inserts, entity deletes, at the end of `Schedule::run` and on `World::maintain`.
+///
+/// ```
+/// # use gw_ecs::prelude::*;
+/// # use gw_ecs::components::ComponentHooks;
+/// # #[derive(Component, Default)]
+/// # struct Position(i32);
+/// #[derive(Default)]
+/// struct Placed(Vec<Entity>);
+///
+/// let mut world = World::default();
+/// world.insert_resource(Placed::default());
+/// world.register_with_hooks(ComponentHooks::<Position>::new().on_add(|commands, entity, _pos| {
+///     commands.add(move |world: &mut World| world.write_resource::<Placed>().0.push(entity));
+/// }));
+///
+/// let entity = world.spawn((Position(3),));
+/// assert_eq!(world.read_resource::<Placed>().0, vec![entity]);
+/// ```
+pub struct ComponentHooks<T: Component> {
+    pub on_add: Option<ComponentHook<T>>,
+    pub on_insert: Option<ComponentHook<T>>,
+    pub on_remove: Option<ComponentHook<T>>,
+}
+
+impl<T: Component> ComponentHooks<T> {
+    pub fn new() -> Self {
+        ComponentHooks {
+            on_add: None,
+            on_insert: None,
+            on_remove: None,
+        }
+    }
+
+    pub fn on_add(mut self, hook: ComponentHook<T>) -> Self {
+        self.on_add = Some(hook);
+        self
+    }
+
+    pub fn on_insert(mut self, hook: ComponentHook<T>) -> Self {
+        self.on_insert = Some(hook);
+        self
+    }
+
+    pub fn on_remove(mut self, hook: ComponentHook<T>) -> Self {
+        self.on_remove = Some(hook);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.on_add.is_none() && self.on_insert.is_none() && self.on_remove.is_none()
+    }
+}
+
+impl<T: Component> Default for ComponentHooks<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Component> Clone for ComponentHooks<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Component> Copy for ComponentHooks<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate as gw_ecs;
+    use crate::prelude::*;
+
+    #[derive(Component, Default, Debug)]
+    struct Pos(u32);
+
+    #[derive(Default)]
+    struct Calls(Vec<String>);
+
+    fn log(commands: &mut Commands, text: String) {
+        commands.add(move |world: &mut World| world.write_resource::<Calls>().0.push(text));
+    }
+
+    fn pos_hooks() -> ComponentHooks<Pos> {
+        ComponentHooks::<Pos>::new()
+            .on_add(|commands, _, pos| log(commands, format!("add {}", pos.0)))
+            .on_insert(|commands, _, pos| log(commands, format!("insert {}", pos.0)))
+            .on_remove(|commands, _, pos| log(commands, format!("remove {}", pos.0)))
+    }
+
+    fn calls(world: &World) -> Vec<String> {
+        std::mem::take(&mut world.write_resource::<Calls>().0)
+    }
+
+    #[test]
+    fn hooks_run() {
+        let mut world = World::default();
+        world.insert_resource(Calls::default());
+        world.register_with_hooks(pos_hooks());
+
+        let entity = world.spawn((Pos(1),));
+        assert_eq!(calls(&world), vec!["add 1", "insert 1"]);
+
+        world
+            .write_component::<Pos>()
+            .insert(entity, Pos(2))
+            .unwrap();
+        assert!(calls(&world).is_empty()); // Not applied yet
+        world.apply_hooks();
+        assert_eq!(calls(&world), vec!["remove 1", "insert 2"]);
+
+        world.write_component::<Pos>().remove(entity);
+        world.maintain();
+        assert_eq!(calls(&world), vec!["remove 2"]);
+
+        world.create_entity().with(Pos(3)).id();
+        let entity = world.spawn((Pos(4),));
+        world.delete_entity(entity);
+        assert_eq!(
+            calls(&world),
+            vec!["add 3", "insert 3", "add 4", "insert 4", "remove 4"]
+        );
+    }
+
+    #[test]
+    fn hooks_in_systems() {
+        fn add_pos(mut commands: Commands) {
+            commands.spawn((Pos(5),));
+        }
+
+        fn remove_pos(entities: Entities, mut positions: WriteComp<Pos>) {
+            let all: Vec<Entity> = (&entities, &positions).join().map(|(e, _)| e).collect();
+            for entity in all {
+                positions.remove(entity);
+            }
+        }
+
+        let mut world = World::default();
+        world.insert_resource(Calls::default());
+        world.register_with_hooks(pos_hooks());
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(add_pos);
+        schedule.run(&mut world);
+        assert_eq!(calls(&world), vec!["add 5", "insert 5"]);
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(remove_pos);
+        schedule.run(&mut world);
+        assert_eq!(calls(&world), vec!["remove 5"]);
+    }
+
+    #[test]
+    fn hooks_in_all_worlds() {
+        fn global_log(commands: &mut Commands, text: String) {
+            commands.add(move |world: &mut World| world.write_global::<Calls>().0.push(text));
+        }
+
+        let mut ecs = Ecs::default();
+        ecs.insert_global(Calls::default());
+        ecs.register_with_hooks(
+            ComponentHooks::<Pos>::new()
+                .on_add(|commands, _, pos| global_log(commands, format!("add {}", pos.0)))
+                .on_remove(|commands, _, pos| global_log(commands, format!("remove {}", pos.0))),
+        );
+
+        let first = ecs.current_world_mut().spawn((Pos(1),));
+        ecs.create_world("OTHER").spawn((Pos(2),));
+
+        let source = ecs.current_world().id();
+        ecs.move_entity(first, source, "OTHER");
+
+        assert_eq!(
+            ecs.read_global::<Calls>().0,
+            vec!["add 1", "add 2", "add 1", "remove 1"]
+        );
+    }
+}
@@ -1,11 +1,13 @@
 mod component;
 mod component_set;
 mod data;
+mod hooks;
 mod meta;
 mod system_param;
 
 pub use component::*;
 pub use component_set::*;
 pub use data::*;
+pub use hooks::*;
 pub use meta::*;
 pub use system_param::*;
@@ -1,4 +1,4 @@
-use crate::components::Component;
+use crate::components::{Component, ComponentHooks};
 use crate::entity::Entity;
 use crate::event::Event;
 use crate::globals::{GlobalMut, GlobalRef, Globals};
@@ -253,6 +253,19 @@ impl Ecs {
         }));
     }
 
+    /// Registers the component with its lifecycle hooks in all of the worlds (current and future)
+    pub fn register_with_hooks<T: Component>(&mut self, hooks: ComponentHooks<T>)
+    where
+        T::Storage: Default,
+    {
+        for world in self.worlds.iter_mut() {
+            world.register_with_hooks(hooks);
+        }
+        self.registry.push(Box::new(move |w| {
+            w.register_with_hooks(hooks);
+        }));
+    }
+
     pub fn register_event<E: Event>(&mut self) {
         self.registry.push(Box::new(|w| {
             w.register_event::<E>();
@@ -177,6 +177,7 @@ impl<'a> EntityBuilder<'a> {
         // // in the EntityBuilder.
         // storage.insert(self.entity, c).unwrap();
         let _ = self.world.write_component::<T>().insert(self.entity, c);
+        self.world.apply_hooks();
     }
 
     /// Inserts a component into the correct storage
@@ -187,6 +188,7 @@ impl<'a> EntityBuilder<'a> {
             // // in the EntityBuilder.
             // storage.insert(self.entity, value).unwrap();
             let _ = self.world.write_component::<T>().insert(self.entity, value);
+            self.world.apply_hooks();
         }
     }
 }
@@ -204,6 +206,7 @@ impl<'a> Builder for EntityBuilder<'a> {
 
     fn spawn<C: ComponentSet>(self, c: C) -> Self {
         c.insert(self.world, self.entity);
+        self.world.apply_hooks();
         self
     }
 
@@ -1,6 +1,9 @@
 use std::fmt;
 use std::num::NonZeroI32;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Index generation. When a new entity is placed at an old index,
 /// it bumps the `Generation` by 1. This allows to avoid using components
 /// from the entities that were deleted.
@@ -20,11 +23,12 @@ impl Generation {
         Generation(unsafe { NonZeroI32::new_unchecked(1) })
     }
 
+    /// A generation that is never alive (placeholder for dead entities).
+    /// `NonZeroI32` can not hold 0, so the most negative value is used.
     pub(crate) fn zero() -> Self {
-        Generation(unsafe { NonZeroI32::new_unchecked(0) })
+        Generation(NonZeroI32::new(i32::MIN).unwrap())
     }
 
-    #[cfg(test)]
     pub fn new(v: i32) -> Self {
         Generation(NonZeroI32::new(v).expect("generation id must be non-zero"))
     }
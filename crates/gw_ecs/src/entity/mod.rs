@@ -240,7 +240,6 @@ pub struct Entity(Index, Generation);
 
 impl Entity {
     /// Creates a new entity (externally from ECS).
-    #[cfg(test)]
     pub fn new(index: Index, gen: Generation) -> Self {
         Self(index, gen)
     }
@@ -0,0 +1,93 @@
+use crate::entity::Entity;
+use crate::system::EntityCommands;
+use crate::world::World;
+
+impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
+    /// Makes `child` the last child of this entity - see [`World::add_child`]
+    pub fn add_child(&mut self, child: Entity) -> &mut Self {
+        let parent = self.id();
+        self.commands()
+            .add(move |world: &mut World| world.add_child(parent, child));
+        self
+    }
+
+    /// Adds all of the children (in order) to this entity
+    pub fn push_children(&mut self, children: &[Entity]) -> &mut Self {
+        let parent = self.id();
+        let children = children.to_vec();
+        self.commands().add(move |world: &mut World| {
+            for child in children {
+                world.add_child(parent, child);
+            }
+        });
+        self
+    }
+
+    /// Takes `child` away from this entity - see [`World::remove_child`]
+    pub fn remove_child(&mut self, child: Entity) -> &mut Self {
+        let parent = self.id();
+        self.commands()
+            .add(move |world: &mut World| world.remove_child(parent, child));
+        self
+    }
+
+    /// Makes this entity the last child of `parent`
+    pub fn set_parent(&mut self, parent: Entity) -> &mut Self {
+        let child = self.id();
+        self.commands()
+            .add(move |world: &mut World| world.add_child(parent, child));
+        self
+    }
+
+    /// Takes this entity away from its parent (if any)
+    pub fn remove_parent(&mut self) -> &mut Self {
+        let child = self.id();
+        self.commands()
+            .add(move |world: &mut World| world.remove_parent(child));
+        self
+    }
+
+    /// Deletes this entity and all of its descendants - see [`World::despawn_recursive`]
+    pub fn despawn_recursive(&mut self) {
+        let entity = self.id();
+        self.commands()
+            .add(move |world: &mut World| world.despawn_recursive(entity));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::system::CommandQueue;
+
+    #[test]
+    fn hierarchy_commands() {
+        let mut world = World::default();
+        let parent = world.create_entity().id();
+        let a = world.create_entity().id();
+        let b = world.create_entity().id();
+
+        let mut queue = CommandQueue::default();
+        let c = {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(parent).push_children(&[a, b]);
+            commands.spawn_empty().set_parent(a).id()
+        };
+        queue.apply(&mut world);
+
+        assert_eq!(world.children(parent), vec![a, b]);
+        assert_eq!(world.children(a), vec![c]);
+
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(parent).remove_child(b);
+            commands.entity(a).despawn_recursive();
+        }
+        queue.apply(&mut world);
+
+        assert!(world.children(parent).is_empty());
+        assert_eq!(world.parent(b), None);
+        assert!(!world.entities().is_alive(a));
+        assert!(!world.entities().is_alive(c));
+    }
+}
@@ -0,0 +1,61 @@
+use crate as gw_ecs;
+use crate::components::Component;
+use crate::entity::{Entity, EntityMap, MapEntities, MapEntitiesError};
+use std::ops::Deref;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The parent of the entity.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent(pub(crate) Entity);
+
+impl Parent {
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+impl Deref for Parent {
+    type Target = Entity;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl MapEntities for Parent {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        self.0 = entity_map.get(self.0)?;
+        Ok(())
+    }
+}
+
+/// The children of the entity - in the order they were added.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Children(pub(crate) Vec<Entity>);
+
+impl Children {
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl Deref for Children {
+    type Target = [Entity];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl MapEntities for Children {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for entity in self.0.iter_mut() {
+            *entity = entity_map.get(*entity)?;
+        }
+        Ok(())
+    }
+}
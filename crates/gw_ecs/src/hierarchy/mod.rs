@@ -0,0 +1,14 @@
+//! Parent/child relationships between entities.
+//!
+//! The [`Parent`] and [`Children`] components are kept in sync by the [`World`](crate::world::World) methods
+//! (`add_child`, `remove_child`, `despawn_recursive`, ...) and the matching
+//! [`EntityCommands`](crate::system::EntityCommands) methods.  Deleting an entity (or removing either component)
+//! is cleaned up by their hooks, but inserting them directly will get them out of sync.
+
+mod commands;
+mod components;
+mod query;
+mod world;
+
+pub use components::*;
+pub(crate) use world::{children_hooks, parent_hooks};
@@ -0,0 +1,48 @@
+use super::{Children, Parent};
+use crate::entity::Entity;
+use crate::storage::{MaskedStorage, Storage};
+use std::ops::Deref;
+
+impl<'e, D> Storage<'e, Parent, D>
+where
+    D: Deref<Target = MaskedStorage<Parent>>,
+{
+    /// The parent of the entity, its parent, ... up to the root
+    pub fn iter_ancestors(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        let mut next = self.get(entity).map(|p| p.get());
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = self.get(current).map(|p| p.get());
+            Some(current)
+        })
+    }
+
+    /// The top of the hierarchy that the entity is in (the entity itself if it has no parent)
+    pub fn root(&self, entity: Entity) -> Entity {
+        self.iter_ancestors(entity).last().unwrap_or(entity)
+    }
+}
+
+impl<'e, D> Storage<'e, Children, D>
+where
+    D: Deref<Target = MaskedStorage<Children>>,
+{
+    /// All of the children, their children, ... of the entity - breadth first
+    pub fn iter_descendants(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        let mut todo: Vec<Entity> = self.children_of(entity);
+        let mut next = 0;
+        std::iter::from_fn(move || {
+            let current = *todo.get(next)?;
+            next += 1;
+            todo.extend(self.children_of(current));
+            Some(current)
+        })
+    }
+
+    fn children_of(&self, entity: Entity) -> Vec<Entity> {
+        match self.get(entity) {
+            None => Vec::new(),
+            Some(children) => children.0.clone(),
+        }
+    }
+}
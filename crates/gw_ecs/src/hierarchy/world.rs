@@ -0,0 +1,281 @@
+use super::{Children, Parent};
+use crate::components::ComponentHooks;
+use crate::entity::{Entity, EntityMap, MapEntities};
+use crate::world::World;
+
+/// Takes the child out of its old parent's `Children` when the `Parent` is removed (or the child deleted)
+pub(crate) fn parent_hooks() -> ComponentHooks<Parent> {
+    ComponentHooks::<Parent>::new().on_remove(|commands, child, parent| {
+        let parent = parent.get();
+        commands.add(move |world: &mut World| {
+            if world.parent(child) == Some(parent) {
+                return;
+            }
+            let mut children = world.write_component::<Children>();
+            let now_empty = match children.get_mut(parent) {
+                None => false,
+                Some(mut children) => {
+                    children.0.retain(|e| *e != child);
+                    children.0.is_empty()
+                }
+            };
+            if now_empty {
+                children.remove(parent);
+            }
+        });
+    })
+}
+
+/// Removes the `Parent` of the children when the `Children` are removed (or the parent deleted)
+pub(crate) fn children_hooks() -> ComponentHooks<Children> {
+    ComponentHooks::<Children>::new().on_remove(|commands, parent, children| {
+        let children = children.0.clone();
+        commands.add(move |world: &mut World| {
+            let still_children = world.children(parent);
+            let mut parents = world.write_component::<Parent>();
+            for child in children {
+                if still_children.contains(&child) {
+                    continue;
+                }
+                if parents.get(child).map(|p| p.get()) == Some(parent) {
+                    parents.remove(child);
+                }
+            }
+        });
+    })
+}
+
+impl World {
+    /// The parent of the entity (if any)
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.read_component::<Parent>().get(entity).map(|p| p.get())
+    }
+
+    /// The children of the entity (empty if none)
+    pub fn children(&self, entity: Entity) -> Vec<Entity> {
+        self.read_component::<Children>()
+            .get(entity)
+            .map(|c| c.0.clone())
+            .unwrap_or_default()
+    }
+
+    /// Makes `child` the last child of `parent` - taking it away from its current parent (if any)
+    pub fn add_child(&mut self, parent: Entity, child: Entity) {
+        assert!(parent != child, "Cannot add entity as a child of itself.");
+        assert!(
+            !self
+                .read_component::<Parent>()
+                .iter_ancestors(parent)
+                .any(|e| e == child),
+            "Cannot add an ancestor of the entity as its child."
+        );
+        if self.parent(child) == Some(parent) {
+            return;
+        }
+        self.remove_parent(child);
+
+        let _ = self
+            .write_component::<Parent>()
+            .insert(child, Parent(parent));
+        let mut children = self.write_component::<Children>();
+        match children.get_mut(parent) {
+            Some(mut children) => children.0.push(child),
+            None => {
+                let _ = children.insert(parent, Children(vec![child]));
+            }
+        }
+    }
+
+    /// Takes the child away from the parent - the child becomes a root
+    pub fn remove_child(&mut self, parent: Entity, child: Entity) {
+        if self.parent(child) != Some(parent) {
+            return;
+        }
+        self.write_component::<Parent>().remove(child);
+
+        let mut children = self.write_component::<Children>();
+        let now_empty = match children.get_mut(parent) {
+            None => false,
+            Some(mut children) => {
+                children.0.retain(|e| *e != child);
+                children.0.is_empty()
+            }
+        };
+        if now_empty {
+            children.remove(parent);
+        }
+    }
+
+    /// Takes the entity away from its parent (if any)
+    pub fn remove_parent(&mut self, child: Entity) {
+        if let Some(parent) = self.parent(child) {
+            self.remove_child(parent, child);
+        }
+    }
+
+    /// Deletes the entity and all of its descendants
+    pub fn despawn_recursive(&mut self, entity: Entity) {
+        self.remove_parent(entity);
+        let mut delete = vec![entity];
+        delete.extend(self.read_component::<Children>().iter_descendants(entity));
+        self.delete_entities(&delete);
+    }
+
+    /// Points the `Parent` and `Children` of the mapped (new) entities at the other new entities
+    pub(crate) fn map_hierarchy(&mut self, entity_map: &EntityMap) {
+        let mut parents = self.write_component::<Parent>();
+        let mut children = self.write_component::<Children>();
+        for entity in entity_map.values() {
+            if let Some(mut parent) = parents.get_mut(entity) {
+                let _ = parent.map_entities(entity_map);
+            }
+            if let Some(mut children) = children.get_mut(entity) {
+                let _ = children.map_entities(entity_map);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate as gw_ecs;
+    use crate::prelude::*;
+
+    #[derive(Component, Default, Debug)]
+    struct Name(&'static str);
+
+    #[test]
+    fn add_and_remove_children() {
+        let mut world = World::default();
+        let a = world.create_entity().id();
+        let b = world.create_entity().id();
+        let c = world.create_entity().id();
+
+        world.add_child(a, b);
+        world.add_child(a, c);
+        assert_eq!(world.children(a), vec![b, c]);
+        assert_eq!(world.parent(b), Some(a));
+
+        // moves c from a to b
+        world.add_child(b, c);
+        assert_eq!(world.children(a), vec![b]);
+        assert_eq!(world.children(b), vec![c]);
+        assert_eq!(
+            world
+                .read_component::<Parent>()
+                .iter_ancestors(c)
+                .collect::<Vec<_>>(),
+            vec![b, a]
+        );
+        assert_eq!(world.read_component::<Parent>().root(c), a);
+        assert_eq!(
+            world
+                .read_component::<Children>()
+                .iter_descendants(a)
+                .collect::<Vec<_>>(),
+            vec![b, c]
+        );
+
+        world.remove_child(a, b);
+        assert!(world.children(a).is_empty());
+        assert!(!world.read_component::<Children>().contains(a));
+        assert_eq!(world.parent(b), None);
+        assert_eq!(world.parent(c), Some(b));
+    }
+
+    #[test]
+    fn despawn_recursive() {
+        let mut world = World::default();
+        let root = world.create_entity().id();
+        let a = world.create_entity().id();
+        let b = world.create_entity().id();
+        let c = world.create_entity().id();
+        world.add_child(root, a);
+        world.add_child(a, b);
+        world.add_child(b, c);
+
+        world.despawn_recursive(a);
+        assert!(world.entities().is_alive(root));
+        assert!(!world.entities().is_alive(a));
+        assert!(!world.entities().is_alive(b));
+        assert!(!world.entities().is_alive(c));
+        assert!(world.children(root).is_empty());
+    }
+
+    #[test]
+    fn move_hierarchy() {
+        let mut ecs = Ecs::default();
+        ecs.register::<Name>();
+        let source = ecs.current_world().id();
+        let world = ecs.current_world_mut();
+        world.register::<Name>();
+        let root = world.spawn((Name("root"),));
+        let bag = world.spawn((Name("bag"),));
+        let coin = world.spawn((Name("coin"),));
+        world.add_child(root, bag);
+        world.add_child(bag, coin);
+
+        ecs.create_world("OTHER");
+        let new_bag = ecs.move_entity(bag, source, "OTHER");
+
+        let world = ecs.current_world();
+        assert!(world.children(root).is_empty());
+        assert!(!world.entities().is_alive(bag));
+        assert!(!world.entities().is_alive(coin));
+
+        let other = ecs.get_world("OTHER").unwrap();
+        assert_eq!(other.parent(new_bag), None);
+        let children = other.children(new_bag);
+        assert_eq!(children.len(), 1);
+        let new_coin = children[0];
+        assert_eq!(other.parent(new_coin), Some(new_bag));
+        assert_eq!(
+            other.read_component::<Name>().get(new_coin).unwrap().0,
+            "coin"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_cycles() {
+        let mut world = World::default();
+        let a = world.create_entity().id();
+        let b = world.create_entity().id();
+        let c = world.create_entity().id();
+        world.add_child(a, b);
+        world.add_child(b, c);
+        world.add_child(c, a);
+    }
+
+    #[test]
+    fn delete_keeps_hierarchy() {
+        let mut world = World::default();
+        let root = world.create_entity().id();
+        let a = world.create_entity().id();
+        let b = world.create_entity().id();
+        world.add_child(root, a);
+        world.add_child(root, b);
+
+        // Deleting a child takes it out of the parent's children
+        world.delete_entity(a);
+        assert_eq!(world.children(root), vec![b]);
+
+        world.delete_entity(b);
+        assert!(!world.read_component::<Children>().contains(root));
+
+        // Deleting a parent leaves the children as roots
+        let c = world.create_entity().id();
+        let d = world.create_entity().id();
+        world.add_child(root, c);
+        world.add_child(c, d);
+        world.delete_entity(root);
+        assert_eq!(world.parent(c), None);
+        assert_eq!(world.children(c), vec![d]);
+        assert_eq!(world.parent(d), Some(c));
+
+        // Removing the components directly is handled the same way
+        world.write_component::<Parent>().remove(d);
+        world.apply_hooks();
+        assert!(world.children(c).is_empty());
+    }
+}
@@ -13,11 +13,15 @@ pub mod entity;
 pub mod error;
 pub mod event;
 pub mod globals;
+pub mod hierarchy;
 pub mod join;
 #[cfg(feature = "bevy_reflect")]
 pub mod reflect;
+pub mod registry;
 pub mod removal_detection;
 pub mod resources;
+#[cfg(feature = "serde")]
+pub mod saveload;
 pub mod schedule;
 pub mod storage;
 pub mod system;
@@ -38,12 +42,14 @@ pub mod prelude {
     pub use crate::{
         // bundle::Bundle,
         change_detection::{DetectChanges, DetectChangesMut},
-        components::{CompMut, CompRef, Component, ReadComp, Removed, WriteComp},
+        components::{CompMut, CompRef, Component, ReadComp, WriteComp},
         ecs::Ecs,
         entity::{Builder, Entities, Entity},
         event::{Event, EventReader, EventWriter, Events},
         globals::{GlobalMut, GlobalRef, ReadNonSendGlobal, WriteNonSendGlobal},
+        hierarchy::{Children, Parent},
         join::{Join, JoinExt, ParJoin},
+        removal_detection::RemovedComponents,
         resources::{ResMut, ResRef, Resource},
         schedule::{
             apply_state_transition, apply_system_buffers, common_conditions::*, Condition,
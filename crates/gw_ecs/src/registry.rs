@@ -0,0 +1,625 @@
+//! A registry of component and resource types keyed by a name.
+//!
+//! Each type registers a string id together with functions to build it from a value and to turn it back
+//! into one.  `V` is the value type used - e.g. `gw_util::value::Value` - so that data files, save files and
+//! inspectors can work with any registered type without knowing about it.
+
+use crate::{
+    components::Component,
+    entity::{Builder, Entity, EntityMap, MapEntities},
+    resources::Resource,
+    storage::MaskedStorage,
+    world::World,
+};
+use bevy_utils::HashMap;
+use std::any::TypeId;
+use std::sync::Arc;
+
+/// Builds a `T` from a value
+pub type FromValueFn<T, V> = fn(&V) -> Result<T, String>;
+/// Turns a `T` into a value
+pub type ToValueFn<T, V> = fn(&T) -> Result<V, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisteredKind {
+    Component,
+    Resource,
+}
+
+type InsertFn<V> = Box<dyn Fn(&mut World, Entity, &V) -> Result<(), String> + Send + Sync>;
+type DumpFn<V> = Box<dyn Fn(&World, Entity) -> Option<Result<V, String>> + Send + Sync>;
+type CloneFn = Box<dyn Fn(&World, Entity, &mut World, Entity) -> bool + Send + Sync>;
+type RemoveFn = Box<dyn Fn(&mut World, Entity) -> bool + Send + Sync>;
+type MapFn = Box<dyn Fn(&mut World, Entity, &EntityMap) -> Result<(), String> + Send + Sync>;
+
+/// The functions that work with one registered type.
+/// The entity is ignored for resources.
+pub struct TypeRegistration<V> {
+    id: String,
+    type_name: &'static str,
+    kind: RegisteredKind,
+    insert: InsertFn<V>,
+    dump: DumpFn<V>,
+    clone: CloneFn,
+    remove: RemoveFn,
+    map: Option<MapFn>,
+}
+
+impl<V: 'static> TypeRegistration<V> {
+    fn component<T>(id: &str, from: FromValueFn<T, V>, to: ToValueFn<T, V>) -> Self
+    where
+        T: Component + Clone,
+        T::Storage: Default,
+    {
+        TypeRegistration {
+            id: id.to_string(),
+            type_name: std::any::type_name::<T>(),
+            kind: RegisteredKind::Component,
+            insert: Box::new(move |world, entity, value| {
+                let comp = from(value)?;
+                world.register::<T>();
+                world
+                    .write_component::<T>()
+                    .insert(entity, comp)
+                    .map_err(|e| e.to_string())?;
+                world.apply_hooks();
+                Ok(())
+            }),
+            dump: Box::new(move |world, entity| {
+                if !world.has_resource::<MaskedStorage<T>>() {
+                    return None;
+                }
+                let comps = world.read_component::<T>();
+                comps.get(entity).map(|comp| to(&comp))
+            }),
+            clone: Box::new(|source, entity, dest, dest_entity| {
+                if !source.has_resource::<MaskedStorage<T>>() {
+                    return false;
+                }
+                let comp = match source.read_component::<T>().get(entity) {
+                    None => return false,
+                    Some(comp) => comp.clone(),
+                };
+                dest.register::<T>();
+                let done = dest
+                    .write_component::<T>()
+                    .insert(dest_entity, comp)
+                    .is_ok();
+                dest.apply_hooks();
+                done
+            }),
+            remove: Box::new(|world, entity| {
+                if !world.has_resource::<MaskedStorage<T>>() {
+                    return false;
+                }
+                let done = world.write_component::<T>().remove(entity).is_some();
+                world.apply_hooks();
+                done
+            }),
+            map: None,
+        }
+    }
+
+    fn mapped_component<T>(id: &str, from: FromValueFn<T, V>, to: ToValueFn<T, V>) -> Self
+    where
+        T: Component + Clone + MapEntities,
+        T::Storage: Default,
+    {
+        let mut registration = Self::component::<T>(id, from, to);
+        registration.map = Some(Box::new(|world, entity, entity_map| {
+            if !world.has_resource::<MaskedStorage<T>>() {
+                return Ok(());
+            }
+            let mut comps = world.write_component::<T>();
+            match comps.get_mut(entity) {
+                None => Ok(()),
+                Some(mut comp) => comp.map_entities(entity_map).map_err(|e| e.to_string()),
+            }
+        }));
+        registration
+    }
+
+    fn resource<R>(id: &str, from: FromValueFn<R, V>, to: ToValueFn<R, V>) -> Self
+    where
+        R: Resource + Clone + Send + Sync,
+    {
+        TypeRegistration {
+            id: id.to_string(),
+            type_name: std::any::type_name::<R>(),
+            kind: RegisteredKind::Resource,
+            insert: Box::new(move |world, _, value| {
+                world.insert_resource(from(value)?);
+                Ok(())
+            }),
+            dump: Box::new(move |world, _| world.try_read_resource::<R>().map(|res| to(&res))),
+            clone: Box::new(|source, _, dest, _| {
+                let res = match source.try_read_resource::<R>() {
+                    None => return false,
+                    Some(res) => R::clone(&res),
+                };
+                dest.insert_resource(res);
+                true
+            }),
+            remove: Box::new(|world, _| world.remove_resource::<R>().is_some()),
+            map: None,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The rust name of the type
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn kind(&self) -> RegisteredKind {
+        self.kind
+    }
+
+    pub fn is_component(&self) -> bool {
+        self.kind == RegisteredKind::Component
+    }
+
+    pub fn is_resource(&self) -> bool {
+        self.kind == RegisteredKind::Resource
+    }
+
+    /// Does the type hold entity references that need to be mapped when it moves between worlds?
+    pub fn has_entities(&self) -> bool {
+        self.map.is_some()
+    }
+
+    /// Builds the component from the value and inserts it on the entity (replacing any that is there)
+    pub fn insert_component(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        value: &V,
+    ) -> Result<(), String> {
+        if !self.is_component() {
+            return Err(format!("{} is not a component", self.id));
+        }
+        (self.insert)(world, entity, value)
+    }
+
+    /// The value of the entity's component - `None` if it does not have one
+    pub fn component_value(&self, world: &World, entity: Entity) -> Option<Result<V, String>> {
+        match self.is_component() {
+            true => (self.dump)(world, entity),
+            false => None,
+        }
+    }
+
+    /// Copies the component of the entity to the entity in the other world.
+    /// Returns false if there was nothing to copy.
+    pub fn clone_component(
+        &self,
+        source: &World,
+        entity: Entity,
+        dest: &mut World,
+        dest_entity: Entity,
+    ) -> bool {
+        self.is_component() && (self.clone)(source, entity, dest, dest_entity)
+    }
+
+    pub fn remove_component(&self, world: &mut World, entity: Entity) -> bool {
+        self.is_component() && (self.remove)(world, entity)
+    }
+
+    /// Updates the entity references in the entity's component (if the type has any)
+    pub fn map_entities(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        entity_map: &EntityMap,
+    ) -> Result<(), String> {
+        match self.map.as_ref() {
+            None => Ok(()),
+            Some(map) => map(world, entity, entity_map),
+        }
+    }
+
+    /// Builds the resource from the value and inserts it (replacing any that is there)
+    pub fn insert_resource(&self, world: &mut World, value: &V) -> Result<(), String> {
+        if !self.is_resource() {
+            return Err(format!("{} is not a resource", self.id));
+        }
+        (self.insert)(world, Entity::dead(), value)
+    }
+
+    /// The value of the resource - `None` if the world does not have it
+    pub fn resource_value(&self, world: &World) -> Option<Result<V, String>> {
+        match self.is_resource() {
+            true => (self.dump)(world, Entity::dead()),
+            false => None,
+        }
+    }
+
+    /// Copies the resource to the other world.
+    /// Returns false if the source did not have it.
+    pub fn clone_resource(&self, source: &World, dest: &mut World) -> bool {
+        self.is_resource() && (self.clone)(source, Entity::dead(), dest, Entity::dead())
+    }
+
+    pub fn remove_resource(&self, world: &mut World) -> bool {
+        self.is_resource() && (self.remove)(world, Entity::dead())
+    }
+}
+
+/// The registered component and resource types - by id.
+///
+/// Usually kept as a global so that every world can use it.
+/// Cloning is cheap - the registrations are shared.
+///
+/// ```
+/// # use gw_ecs::prelude::*;
+/// # use gw_ecs::registry::TypeRegistry;
+/// #[derive(Component, Default, Clone, Debug, PartialEq)]
+/// struct Health(i32);
+///
+/// let mut registry = TypeRegistry::<i64>::new();
+/// registry.register_component_with::<Health>("health", |v| Ok(Health(*v as i32)), |h| Ok(h.0 as i64));
+///
+/// let mut world = World::default();
+/// let entity = registry.spawn(&mut world, [("health", &10)]).unwrap();
+/// assert_eq!(*world.read_component::<Health>().get(entity).unwrap(), Health(10));
+/// assert_eq!(registry.dump_entity(&world, entity), vec![("health", Ok(10))]);
+/// ```
+pub struct TypeRegistry<V> {
+    types: HashMap<String, Arc<TypeRegistration<V>>>,
+    ids: HashMap<TypeId, String>,
+}
+
+impl<V> Clone for TypeRegistry<V> {
+    fn clone(&self) -> Self {
+        TypeRegistry {
+            types: self.types.clone(),
+            ids: self.ids.clone(),
+        }
+    }
+}
+
+impl<V: 'static> TypeRegistry<V> {
+    pub fn new() -> Self {
+        TypeRegistry {
+            types: HashMap::default(),
+            ids: HashMap::default(),
+        }
+    }
+
+    /// Registers a component type - replacing any registration with the same id
+    pub fn register_component_with<T>(
+        &mut self,
+        id: &str,
+        from: FromValueFn<T, V>,
+        to: ToValueFn<T, V>,
+    ) where
+        T: Component + Clone,
+        T::Storage: Default,
+    {
+        self.add(
+            TypeId::of::<T>(),
+            TypeRegistration::component::<T>(id, from, to),
+        );
+    }
+
+    /// Registers a component that holds entity references - see `TypeRegistration::map_entities`
+    pub fn register_mapped_component_with<T>(
+        &mut self,
+        id: &str,
+        from: FromValueFn<T, V>,
+        to: ToValueFn<T, V>,
+    ) where
+        T: Component + Clone + MapEntities,
+        T::Storage: Default,
+    {
+        self.add(
+            TypeId::of::<T>(),
+            TypeRegistration::mapped_component::<T>(id, from, to),
+        );
+    }
+
+    /// Registers a resource type - replacing any registration with the same id
+    pub fn register_resource_with<R>(
+        &mut self,
+        id: &str,
+        from: FromValueFn<R, V>,
+        to: ToValueFn<R, V>,
+    ) where
+        R: Resource + Clone + Send + Sync,
+    {
+        self.add(
+            TypeId::of::<R>(),
+            TypeRegistration::resource::<R>(id, from, to),
+        );
+    }
+
+    fn add(&mut self, type_id: TypeId, registration: TypeRegistration<V>) {
+        if let Some(old) = self.ids.insert(type_id, registration.id.clone()) {
+            self.types.remove(&old);
+        }
+        self.types
+            .insert(registration.id.clone(), Arc::new(registration));
+    }
+
+    pub fn get(&self, id: &str) -> Option<&TypeRegistration<V>> {
+        self.types.get(id).map(|reg| reg.as_ref())
+    }
+
+    /// The registration of the type
+    pub fn get_type<T: 'static>(&self) -> Option<&TypeRegistration<V>> {
+        self.ids.get(&TypeId::of::<T>()).and_then(|id| self.get(id))
+    }
+
+    /// The id the type was registered with
+    pub fn id_of<T: 'static>(&self) -> Option<&str> {
+        self.ids.get(&TypeId::of::<T>()).map(|id| id.as_str())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.types.contains_key(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TypeRegistration<V>> {
+        self.types.values().map(|reg| reg.as_ref())
+    }
+
+    pub fn components(&self) -> impl Iterator<Item = &TypeRegistration<V>> {
+        self.iter().filter(|reg| reg.is_component())
+    }
+
+    pub fn resources(&self) -> impl Iterator<Item = &TypeRegistration<V>> {
+        self.iter().filter(|reg| reg.is_resource())
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Inserts the components built from the (id, value) pairs on the entity.
+    /// Stops at the first error.
+    pub fn insert_components<'v, I>(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        comps: I,
+    ) -> Result<(), String>
+    where
+        I: IntoIterator<Item = (&'v str, &'v V)>,
+    {
+        for (id, value) in comps {
+            match self.get(id) {
+                None => return Err(format!("Unknown component: {}", id)),
+                Some(reg) => reg
+                    .insert_component(world, entity, value)
+                    .map_err(|e| format!("{} - {}", id, e))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates an entity with the components built from the (id, value) pairs.
+    /// The entity is deleted if any of them fail.
+    pub fn spawn<'v, I>(&self, world: &mut World, comps: I) -> Result<Entity, String>
+    where
+        I: IntoIterator<Item = (&'v str, &'v V)>,
+    {
+        let entity = world.create_entity().id();
+        if let Err(e) = self.insert_components(world, entity, comps) {
+            world.delete_entity(entity);
+            return Err(e);
+        }
+        Ok(entity)
+    }
+
+    /// The values of all of the registered components that the entity has - sorted by id
+    pub fn dump_entity(&self, world: &World, entity: Entity) -> Vec<(&str, Result<V, String>)> {
+        let mut out: Vec<(&str, Result<V, String>)> = self
+            .components()
+            .filter_map(|reg| reg.component_value(world, entity).map(|v| (reg.id(), v)))
+            .collect();
+        out.sort_by(|a, b| a.0.cmp(b.0));
+        out
+    }
+
+    /// The values of all of the registered resources that the world has - sorted by id
+    pub fn dump_resources(&self, world: &World) -> Vec<(&str, Result<V, String>)> {
+        let mut out: Vec<(&str, Result<V, String>)> = self
+            .resources()
+            .filter_map(|reg| reg.resource_value(world).map(|v| (reg.id(), v)))
+            .collect();
+        out.sort_by(|a, b| a.0.cmp(b.0));
+        out
+    }
+
+    /// Creates a new entity in the destination world with copies of all of the registered components
+    pub fn clone_entity(&self, source: &World, entity: Entity, dest: &mut World) -> Entity {
+        let dest_entity = dest.create_entity().id();
+        for reg in self.components() {
+            reg.clone_component(source, entity, dest, dest_entity);
+        }
+        dest_entity
+    }
+}
+
+impl<V: 'static> Default for TypeRegistry<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate as gw_ecs;
+    use crate::prelude::*;
+
+    #[derive(Component, Default, Clone, Debug, PartialEq)]
+    struct Health(i32);
+
+    #[derive(Component, Default, Clone, Debug, PartialEq)]
+    struct Name(String);
+
+    #[derive(Default, Clone, Debug, PartialEq)]
+    struct Turn(u32);
+
+    fn registry() -> TypeRegistry<String> {
+        let mut registry = TypeRegistry::<String>::new();
+        registry.register_component_with::<Health>(
+            "health",
+            |v| v.parse().map(Health).map_err(|e| format!("{}", e)),
+            |h| Ok(h.0.to_string()),
+        );
+        registry.register_component_with::<Name>(
+            "name",
+            |v| Ok(Name(v.clone())),
+            |n| Ok(n.0.clone()),
+        );
+        registry.register_resource_with::<Turn>(
+            "turn",
+            |v| v.parse().map(Turn).map_err(|e| format!("{}", e)),
+            |t| Ok(t.0.to_string()),
+        );
+        registry
+    }
+
+    #[test]
+    fn spawn_and_dump() {
+        let registry = registry();
+        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.id_of::<Health>(), Some("health"));
+        assert!(registry.get_type::<Turn>().unwrap().is_resource());
+
+        let mut world = World::default();
+        let health = "12".to_string();
+        let name = "rat".to_string();
+        let entity = registry
+            .spawn(&mut world, [("name", &name), ("health", &health)])
+            .unwrap();
+
+        assert_eq!(
+            *world.read_component::<Health>().get(entity).unwrap(),
+            Health(12)
+        );
+        assert_eq!(
+            registry.dump_entity(&world, entity),
+            vec![("health", Ok(health)), ("name", Ok(name.clone()))]
+        );
+
+        let bad = "many".to_string();
+        let count = (&world.entities()).join().count();
+        assert!(registry
+            .spawn(&mut world, [("name", &name), ("health", &bad)])
+            .is_err());
+        assert!(registry.spawn(&mut world, [("speed", &bad)]).is_err());
+        world.maintain();
+        assert_eq!((&world.entities()).join().count(), count);
+
+        assert!(registry
+            .get("health")
+            .unwrap()
+            .remove_component(&mut world, entity));
+        assert_eq!(registry.dump_entity(&world, entity).len(), 1);
+    }
+
+    #[test]
+    fn resources() {
+        let registry = registry();
+        let turn = registry.get("turn").unwrap();
+
+        let mut world = World::default();
+        assert!(turn.resource_value(&world).is_none());
+        turn.insert_resource(&mut world, &"4".to_string()).unwrap();
+        assert_eq!(world.read_resource::<Turn>().0, 4);
+        assert_eq!(
+            registry.dump_resources(&world),
+            vec![("turn", Ok("4".to_string()))]
+        );
+
+        let mut other = World::default();
+        assert!(turn.clone_resource(&world, &mut other));
+        assert_eq!(other.read_resource::<Turn>().0, 4);
+
+        assert!(turn
+            .insert_component(&mut world, Entity::dead(), &"1".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn clone_between_worlds() {
+        let registry = registry();
+
+        let mut world = World::default();
+        world.register::<Health>();
+        world.register::<Name>();
+        let entity = world.spawn((Health(3), Name("goblin".to_string())));
+
+        let mut other = World::default();
+        let copy = registry.clone_entity(&world, entity, &mut other);
+
+        assert_eq!(
+            *other.read_component::<Health>().get(copy).unwrap(),
+            Health(3)
+        );
+        assert_eq!(
+            registry.dump_entity(&other, copy),
+            registry.dump_entity(&world, entity)
+        );
+        assert!(world.entities().is_alive(entity));
+    }
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct Target(Entity);
+
+    impl MapEntities for Target {
+        fn map_entities(
+            &mut self,
+            entity_map: &EntityMap,
+        ) -> Result<(), crate::entity::MapEntitiesError> {
+            self.0 = entity_map.get(self.0)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn mapped_entities() {
+        let mut registry = TypeRegistry::<Entity>::new();
+        registry.register_mapped_component_with::<Target>(
+            "target",
+            |v| Ok(Target(*v)),
+            |t| Ok(t.0),
+        );
+        let target = registry.get("target").unwrap();
+        assert!(target.has_entities());
+
+        let mut world = World::default();
+        world.register::<Target>();
+        let goblin = world.create_entity().id();
+        let rat = world.spawn(Target(goblin));
+
+        let mut other = World::default();
+        let copy = registry.clone_entity(&world, rat, &mut other);
+        let goblin_copy = other.create_entity().id();
+        let mut entity_map = EntityMap::default();
+        entity_map.insert(goblin, goblin_copy);
+
+        target.map_entities(&mut other, copy, &entity_map).unwrap();
+        assert_eq!(
+            *other.read_component::<Target>().get(copy).unwrap(),
+            Target(goblin_copy)
+        );
+        assert!(target
+            .map_entities(&mut other, copy, &EntityMap::default())
+            .is_err());
+
+        // Clones share the registrations
+        let copy = registry.clone();
+        assert_eq!(copy.id_of::<Target>(), Some("target"));
+    }
+}
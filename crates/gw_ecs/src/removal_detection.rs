@@ -1,274 +1,229 @@
-// //! Alerting events when a component is removed from an entity.
-
-// use crate::{
-//     self as gw_ecs,
-//     component::Component, //, ResourceId, ResourceIdFor},
-//     entity::Entity,
-//     event::{EventId, Events, ManualEventIterator, ManualEventIteratorWithId, ManualEventReader},
-//     prelude::Local,
-//     resources::{ResourceId, ResourceIdOf},
-//     system::{ReadOnlySystemParam, SystemMeta, SystemParam},
-//     world::World,
-// };
-
-// use std::{
-//     collections::HashMap,
-//     fmt::Debug,
-//     iter,
-//     marker::PhantomData,
-//     ops::{Deref, DerefMut},
-//     option,
-// };
-
-// /// Wrapper around [`Entity`] for [`RemovedComponents`].
-// /// Internally, `RemovedComponents` uses these as an `Events<RemovedComponentEntity>`.
-// #[derive(Debug, Clone)]
-// pub struct RemovedComponentEntity(Entity);
-
-// impl From<RemovedComponentEntity> for Entity {
-//     fn from(value: RemovedComponentEntity) -> Self {
-//         value.0
-//     }
-// }
-
-// /// Wrapper around a [`ManualEventReader<RemovedComponentEntity>`] so that we
-// /// can differentiate events between components.
-// #[derive(Debug)]
-// pub struct RemovedComponentReader<T>
-// where
-//     T: Component,
-// {
-//     reader: ManualEventReader<RemovedComponentEntity>,
-//     marker: PhantomData<T>,
-// }
-
-// impl<T: Component> Default for RemovedComponentReader<T> {
-//     fn default() -> Self {
-//         Self {
-//             reader: Default::default(),
-//             marker: PhantomData,
-//         }
-//     }
-// }
-
-// impl<T: Component> Deref for RemovedComponentReader<T> {
-//     type Target = ManualEventReader<RemovedComponentEntity>;
-//     fn deref(&self) -> &Self::Target {
-//         &self.reader
-//     }
-// }
-
-// impl<T: Component> DerefMut for RemovedComponentReader<T> {
-//     fn deref_mut(&mut self) -> &mut Self::Target {
-//         &mut self.reader
-//     }
-// }
-
-// /// Wrapper around a map of components to [`Events<RemovedComponentEntity>`].
-// /// So that we can find the events without naming the type directly.
-// #[derive(Default, Debug)]
-// pub struct RemovedComponentEvents {
-//     event_sets: HashMap<ResourceId, Events<RemovedComponentEntity>>,
-// }
-
-// impl RemovedComponentEvents {
-//     pub fn new() -> Self {
-//         Self::default()
-//     }
-
-//     pub fn update(&mut self) {
-//         for (_component_id, events) in self.event_sets.iter_mut() {
-//             events.update();
-//         }
-//     }
-
-//     pub fn get(
-//         &self,
-//         component_id: impl Into<ResourceId>,
-//     ) -> Option<&Events<RemovedComponentEntity>> {
-//         self.event_sets.get(&component_id.into())
-//     }
-
-//     pub fn send(&mut self, component_id: impl Into<ResourceId>, entity: Entity) {
-//         self.event_sets
-//             .entry(component_id.into())
-//             .or_insert_with(Default::default)
-//             .send(RemovedComponentEntity(entity));
-//     }
-// }
-
-// /// A [`SystemParam`] that grants access to the entities that had their `T` [`Component`] removed.
-// ///
-// /// This acts effectively the same as an [`EventReader`](crate::event::EventReader).
-// ///
-// /// Note that this does not allow you to see which data existed before removal.
-// /// If you need this, you will need to track the component data value on your own,
-// /// using a regularly scheduled system that requests `Query<(Entity, &T), Changed<T>>`
-// /// and stores the data somewhere safe to later cross-reference.
-// ///
-// /// If you are using `gw_ecs` as a standalone crate,
-// /// note that the `RemovedComponents` list will not be automatically cleared for you,
-// /// and will need to be manually flushed using [`World::clear_trackers`](crate::world::World::clear_trackers)
-// ///
-// /// For users of `bevy` and `bevy_app`, this is automatically done in `bevy_app::App::update`.
-// /// For the main world, [`World::clear_trackers`](crate::world::World::clear_trackers) is run after the main schedule is run and after
-// /// `SubApp`'s have run.
-// ///
-// /// # Examples
-// ///
-// /// Basic usage:
-// ///
-// /// ```
-// /// # use gw_ecs::component::Component;
-// /// # use gw_ecs::system::IntoSystem;
-// /// # use gw_ecs::removal_detection::RemovedComponents;
-// /// #
-// /// # #[derive(Component)]
-// /// # struct MyComponent;
-// /// fn react_on_removal(mut removed: RemovedComponents<MyComponent>) {
-// ///     removed.iter().for_each(|removed_entity| println!("{:?}", removed_entity));
-// /// }
-// /// # gw_ecs::system::assert_is_system(react_on_removal);
-// /// ```
-// #[derive(SystemParam)]
-// pub struct RemovedComponents<'w, 's, T: Component> {
-//     component_id: Local<'s, ResourceIdOf<T>>,
-//     reader: Local<'s, RemovedComponentReader<T>>,
-//     event_sets: &'w RemovedComponentEvents,
-// }
-
-// /// Iterator over entities that had a specific component removed.
-// ///
-// /// See [`RemovedComponents`].
-// pub type RemovedIter<'a> = iter::Map<
-//     iter::Flatten<option::IntoIter<iter::Cloned<ManualEventIterator<'a, RemovedComponentEntity>>>>,
-//     fn(RemovedComponentEntity) -> Entity,
-// >;
-
-// /// Iterator over entities that had a specific component removed.
-// ///
-// /// See [`RemovedComponents`].
-// pub type RemovedIterWithId<'a> = iter::Map<
-//     iter::Flatten<option::IntoIter<ManualEventIteratorWithId<'a, RemovedComponentEntity>>>,
-//     fn(
-//         (&RemovedComponentEntity, EventId<RemovedComponentEntity>),
-//     ) -> (Entity, EventId<RemovedComponentEntity>),
-// >;
-
-// fn map_id_events(
-//     (entity, id): (&RemovedComponentEntity, EventId<RemovedComponentEntity>),
-// ) -> (Entity, EventId<RemovedComponentEntity>) {
-//     (entity.clone().into(), id)
-// }
-
-// // For all practical purposes, the api surface of `RemovedComponents<T>`
-// // should be similar to `EventReader<T>` to reduce confusion.
-// impl<'w, 's, T: Component> RemovedComponents<'w, 's, T> {
-//     /// Fetch underlying [`ManualEventReader`].
-//     pub fn reader(&self) -> &ManualEventReader<RemovedComponentEntity> {
-//         &self.reader
-//     }
-
-//     /// Fetch underlying [`ManualEventReader`] mutably.
-//     pub fn reader_mut(&mut self) -> &mut ManualEventReader<RemovedComponentEntity> {
-//         &mut self.reader
-//     }
-
-//     /// Fetch underlying [`Events`].
-//     pub fn events(&self) -> Option<&Events<RemovedComponentEntity>> {
-//         self.event_sets.get(self.component_id.0.id)
-//     }
-
-//     /// Destructures to get a mutable reference to the `ManualEventReader`
-//     /// and a reference to `Events`.
-//     ///
-//     /// This is necessary since Rust can't detect destructuring through methods and most
-//     /// usecases of the reader uses the `Events` as well.
-//     pub fn reader_mut_with_events(
-//         &mut self,
-//     ) -> Option<(
-//         &mut RemovedComponentReader<T>,
-//         &Events<RemovedComponentEntity>,
-//     )> {
-//         self.event_sets
-//             .get(self.component_id.0.id)
-//             .map(|events| (&mut *self.reader, events))
-//     }
-
-//     /// Iterates over the events this [`RemovedComponents`] has not seen yet. This updates the
-//     /// [`RemovedComponents`]'s event counter, which means subsequent event reads will not include events
-//     /// that happened before now.
-//     pub fn iter(&mut self) -> RemovedIter<'_> {
-//         self.reader_mut_with_events()
-//             .map(|(reader, events)| reader.iter(events).cloned())
-//             .into_iter()
-//             .flatten()
-//             .map(RemovedComponentEntity::into)
-//     }
-
-//     /// Like [`iter`](Self::iter), except also returning the [`EventId`] of the events.
-//     pub fn iter_with_id(&mut self) -> RemovedIterWithId<'_> {
-//         self.reader_mut_with_events()
-//             .map(|(reader, events)| reader.iter_with_id(events))
-//             .into_iter()
-//             .flatten()
-//             .map(map_id_events)
-//     }
-
-//     /// Determines the number of removal events available to be read from this [`RemovedComponents`] without consuming any.
-//     pub fn len(&self) -> usize {
-//         self.events()
-//             .map(|events| self.reader.len(events))
-//             .unwrap_or(0)
-//     }
-
-//     /// Returns `true` if there are no events available to read.
-//     pub fn is_empty(&self) -> bool {
-//         self.events()
-//             .map(|events| self.reader.is_empty(events))
-//             .unwrap_or(true)
-//     }
-
-//     /// Consumes all available events.
-//     ///
-//     /// This means these events will not appear in calls to [`RemovedComponents::iter()`] or
-//     /// [`RemovedComponents::iter_with_id()`] and [`RemovedComponents::is_empty()`] will return `true`.
-//     pub fn clear(&mut self) {
-//         if let Some((reader, events)) = self.reader_mut_with_events() {
-//             reader.clear(events);
-//         }
-//     }
-// }
-
-// impl<'a, 'w, 's: 'a, T> IntoIterator for &'a mut RemovedComponents<'w, 's, T>
-// where
-//     T: Component,
-// {
-//     type Item = Entity;
-//     type IntoIter = RemovedIter<'a>;
-//     fn into_iter(self) -> Self::IntoIter {
-//         self.iter()
-//     }
-// }
-
-// // SAFETY: Only reads World removed component events
-// unsafe impl<'a> ReadOnlySystemParam for &'a RemovedComponentEvents {}
-
-// // SAFETY: no component value access, removed component events can be read in parallel and are
-// // never mutably borrowed during system execution
-// unsafe impl<'a> SystemParam for &'a RemovedComponentEvents {
-//     type State = ();
-//     type Item<'w, 's> = &'w RemovedComponentEvents;
-
-//     fn init_state(_world: &mut World, _system_meta: &mut SystemMeta) -> Self::State {}
-
-//     #[inline]
-//     unsafe fn get_param<'w, 's>(
-//         _state: &'s mut Self::State,
-//         _system_meta: &SystemMeta,
-//         world: &'w World,
-//         _change_tick: u32,
-//     ) -> Self::Item<'w, 's> {
-//         world.removed_components()
-//     }
-// }
+//! Tracking the entities that had a component removed (or were deleted).
+
+use crate::{
+    access::AccessItem,
+    components::Component,
+    entity::Entity,
+    resources::{ResRef, ResourceId},
+    storage::MaskedStorage,
+    system::{SystemMeta, SystemParam},
+    tick::Tick,
+    world::World,
+};
+
+/// The entities that had the component removed and the tick it happened on.
+///
+/// Every component storage keeps one of these.  Removals are recorded by [`Storage::remove`](crate::storage::Storage::remove),
+/// entity deletion and moving entities between worlds.  [`World::maintain`] forgets the removals that happened before
+/// the previous maintain, so a removal can be seen until the second maintain after it.
+#[derive(Debug, Default)]
+pub struct RemovedEntities {
+    removed: Vec<(Entity, Tick)>,
+    last_maintain_tick: u32,
+}
+
+impl RemovedEntities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, entity: Entity, world_tick: u32) {
+        self.removed.push((entity, Tick::new(world_tick)));
+    }
+
+    /// All of the removals that are still kept - oldest first
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Tick)> + '_ {
+        self.removed.iter().copied()
+    }
+
+    /// The entities removed after `last_run_tick`
+    pub fn iter_since(
+        &self,
+        last_run_tick: u32,
+        change_tick: u32,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        self.removed
+            .iter()
+            .filter(move |(_, tick)| tick.is_newer_than(last_run_tick, change_tick))
+            .map(|(entity, _)| *entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.removed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+    }
+
+    /// Drops the removals from before the last maintain and keeps the rest from getting too old
+    pub(crate) fn maintain(&mut self, world_tick: u32) {
+        let last_maintain_tick = self.last_maintain_tick;
+        self.removed
+            .retain(|(_, tick)| tick.is_newer_than(last_maintain_tick, world_tick));
+        for (_, tick) in self.removed.iter_mut() {
+            tick.check_tick(world_tick);
+        }
+        self.last_maintain_tick = world_tick;
+    }
+}
+
+/// A [`SystemParam`] that gives the entities that had their `T` [`Component`] removed
+/// (or were deleted) since the system last ran.
+///
+/// Each system has its own view - it is based on the system's change ticks the same way that
+/// `added` and `changed` are.  The removed component values are gone, only the entities are left.
+///
+/// ```
+/// # use gw_ecs::prelude::*;
+/// # #[derive(Component, Default)]
+/// # struct Position;
+/// fn forget_positions(mut removed: RemovedComponents<Position>) {
+///     for entity in removed.iter() {
+///         println!("{:?} lost its position", entity);
+///     }
+/// }
+/// # gw_ecs::system::assert_is_system(forget_positions);
+/// ```
+pub struct RemovedComponents<'w, T: Component> {
+    storage: ResRef<'w, MaskedStorage<T>>,
+    last_run_tick: u32,
+    change_tick: u32,
+}
+
+impl<'w, T: Component> RemovedComponents<'w, T> {
+    pub fn new(
+        storage: ResRef<'w, MaskedStorage<T>>,
+        last_run_tick: u32,
+        change_tick: u32,
+    ) -> Self {
+        RemovedComponents {
+            storage,
+            last_run_tick,
+            change_tick,
+        }
+    }
+
+    /// The entities that had the component removed since the system last ran
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.storage
+            .removed
+            .iter_since(self.last_run_tick, self.change_tick)
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.iter().any(|e| e == entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+// SAFETY: The component access is applied to SystemMeta. If it conflicts with a previous write a panic will occur.
+unsafe impl<'a, T: Component> SystemParam for RemovedComponents<'a, T> {
+    type State = ();
+    type Item<'w, 's> = RemovedComponents<'w, T>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        world.register::<T>();
+
+        let item = AccessItem::Component(ResourceId::of::<T>());
+        assert!(
+            !system_meta.component_access_set.has_write(&item),
+            "error[B0002]: RemovedComponents<{}> in system {} conflicts with a previous WriteComp<{0}> access. Consider removing the duplicate access.",
+            std::any::type_name::<T>(),
+            system_meta.name,
+        );
+        system_meta.component_access_set.add_read(item);
+    }
+
+    #[inline]
+    unsafe fn get_param<'w, 's>(
+        _state: &'s mut Self::State,
+        system_meta: &SystemMeta,
+        world: &'w World,
+        change_tick: u32,
+    ) -> Self::Item<'w, 's> {
+        let storage = world
+            .try_read_resource::<MaskedStorage<T>>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "Component requested by {} does not exist: {}",
+                    system_meta.name,
+                    std::any::type_name::<T>()
+                )
+            });
+
+        RemovedComponents::new(storage, system_meta.last_run_tick, change_tick)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate as gw_ecs;
+    use crate::prelude::*;
+
+    #[derive(Component, Default, Debug)]
+    struct A(u32);
+
+    #[derive(Component, Default, Debug)]
+    struct B(u32);
+
+    #[derive(Default)]
+    struct Seen(Vec<Entity>);
+
+    fn remember_removed(removed: RemovedComponents<A>, mut seen: ResMut<Seen>) {
+        seen.0.extend(removed.iter());
+    }
+
+    #[test]
+    fn remove_tracking() {
+        let mut world = World::default();
+        world.register::<A>();
+        world.register::<B>();
+
+        let a = world.spawn((A(1), B(1)));
+        let b = world.spawn((A(2),));
+
+        world.delete_entity(a);
+        assert_eq!(world.removed::<A>(), vec![a]);
+        assert_eq!(world.removed::<B>(), vec![a]);
+
+        world.write_component::<A>().remove(b);
+        assert_eq!(world.removed::<A>(), vec![a, b]);
+
+        world.maintain();
+        assert!(world.removed::<A>().is_empty());
+        assert!(world.removed::<B>().is_empty());
+    }
+
+    #[test]
+    fn each_system_sees_removals_once() {
+        let mut world = World::default();
+        world.register::<A>();
+        world.insert_resource(Seen::default());
+
+        let a = world.spawn((A(1),));
+        let b = world.spawn((A(2),));
+        world.write_component::<A>().remove(a);
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(remember_removed);
+
+        schedule.run(&mut world);
+        assert_eq!(world.read_resource::<Seen>().0, vec![a]);
+
+        world.maintain();
+        world.delete_entity(b);
+        schedule.run(&mut world);
+        assert_eq!(world.read_resource::<Seen>().0, vec![a, b]);
+
+        schedule.run(&mut world);
+        assert_eq!(world.read_resource::<Seen>().0, vec![a, b]);
+    }
+}
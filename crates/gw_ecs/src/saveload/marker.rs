@@ -0,0 +1,74 @@
+use crate as gw_ecs;
+use crate::components::Component;
+use crate::entity::{Builder, Entity};
+use crate::world::World;
+use bevy_utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Marks an entity that is saved in snapshots.
+/// The id stays the same across a save and load - it is used to find the entity when loading.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SaveMarker(pub u64);
+
+/// Gives out the marker ids and knows which entity has each one.
+/// This is a resource - it is added by [`World::mark_for_save`].
+#[derive(Default, Debug)]
+pub struct SaveMarkers {
+    next_id: u64,
+    entities: HashMap<u64, Entity>,
+}
+
+impl SaveMarkers {
+    pub fn new() -> Self {
+        SaveMarkers::default()
+    }
+
+    /// The entity that has the marker (it might have been deleted)
+    pub fn get(&self, marker: SaveMarker) -> Option<Entity> {
+        self.entities.get(&marker.0).copied()
+    }
+
+    fn insert(&mut self, marker: SaveMarker, entity: Entity) {
+        self.entities.insert(marker.0, entity);
+        self.next_id = self.next_id.max(marker.0 + 1);
+    }
+
+    fn allocate(&mut self, entity: Entity) -> SaveMarker {
+        let marker = SaveMarker(self.next_id);
+        self.insert(marker, entity);
+        marker
+    }
+}
+
+impl World {
+    /// Marks the entity to be saved (if it is not already) and returns its marker
+    pub fn mark_for_save(&mut self, entity: Entity) -> SaveMarker {
+        self.register::<SaveMarker>();
+        self.ensure_resource::<SaveMarkers>();
+
+        if let Some(marker) = self.read_component::<SaveMarker>().get(entity) {
+            return *marker;
+        }
+        let marker = self.write_resource::<SaveMarkers>().allocate(entity);
+        let _ = self.write_component::<SaveMarker>().insert(entity, marker);
+        marker
+    }
+
+    /// The entity with the marker - a new one is created (and marked) if there is not one
+    pub fn marked_entity(&mut self, marker: SaveMarker) -> Entity {
+        self.register::<SaveMarker>();
+        self.ensure_resource::<SaveMarkers>();
+
+        let found = self.read_resource::<SaveMarkers>().get(marker);
+        if let Some(entity) = found {
+            if self.read_component::<SaveMarker>().get(entity).map(|m| *m) == Some(marker) {
+                return entity;
+            }
+        }
+
+        let entity = self.create_entity().id();
+        self.write_resource::<SaveMarkers>().insert(marker, entity);
+        let _ = self.write_component::<SaveMarker>().insert(entity, marker);
+        entity
+    }
+}
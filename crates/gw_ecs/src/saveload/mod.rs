@@ -0,0 +1,12 @@
+//! Saving and loading the marked entities with a chosen set of their components.
+//! Only available with the `serde` feature.
+//!
+//! Entities are marked with [`World::mark_for_save`].  The marker id is saved with the entity
+//! and on load it finds the entity again (or creates a new one), so loading a snapshot into the same world
+//! updates the entities and loading it into a new world recreates them.
+
+mod marker;
+mod snapshot;
+
+pub use marker::*;
+pub use snapshot::*;
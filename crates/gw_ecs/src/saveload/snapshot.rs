@@ -0,0 +1,215 @@
+use super::SaveMarker;
+use crate::components::Component;
+use crate::entity::{Entity, EntityMap};
+use crate::join::Join;
+use crate::storage::MaskedStorage;
+use crate::world::World;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The set of components that are saved in a snapshot.
+/// It is implemented for tuples of components that are `Clone`, `Serialize` and `Deserialize`.
+pub trait SaveComponents {
+    /// The components of one entity - a tuple of `Option`s
+    type Data: Serialize + DeserializeOwned;
+
+    fn save(world: &World, entity: Entity) -> Self::Data;
+
+    /// Inserts the saved components - and removes the ones that were not saved
+    fn load(world: &mut World, entity: Entity, data: Self::Data);
+}
+
+macro_rules! impl_save_components {
+    // use variables to indicate the arity of the tuple
+    ($($from:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<$($from: Component + Clone + Serialize + DeserializeOwned,)*> SaveComponents for ($($from),*,)
+        {
+            type Data = ($(Option<$from>,)*);
+
+            fn save(world: &World, entity: Entity) -> Self::Data {
+                ($(
+                    world.read_component::<$from>().get(entity).map(|c| (*c).clone()),
+                )*)
+            }
+
+            fn load(world: &mut World, entity: Entity, data: Self::Data) {
+                let ($($from,)*) = data;
+                $(
+                    match $from {
+                        Some(comp) => {
+                            let _ = world.write_component::<$from>().insert(entity, comp);
+                        }
+                        None => {
+                            world.write_component::<$from>().remove(entity);
+                        }
+                    }
+                )*
+            }
+        }
+    }
+}
+
+impl_save_components! {A}
+impl_save_components! {A, B}
+impl_save_components! {A, B, C}
+impl_save_components! {A, B, C, D}
+impl_save_components! {A, B, C, D, E}
+impl_save_components! {A, B, C, D, E, F}
+impl_save_components! {A, B, C, D, E, F, G}
+impl_save_components! {A, B, C, D, E, F, G, H}
+impl_save_components! {A, B, C, D, E, F, G, H, I}
+impl_save_components! {A, B, C, D, E, F, G, H, I, J}
+impl_save_components! {A, B, C, D, E, F, G, H, I, J, K}
+impl_save_components! {A, B, C, D, E, F, G, H, I, J, K, L}
+impl_save_components! {A, B, C, D, E, F, G, H, I, J, K, L, M}
+impl_save_components! {A, B, C, D, E, F, G, H, I, J, K, L, M, N}
+impl_save_components! {A, B, C, D, E, F, G, H, I, J, K, L, M, N, O}
+impl_save_components! {A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P}
+
+/// One saved entity
+#[derive(Serialize, Deserialize)]
+struct EntityData<D> {
+    marker: SaveMarker,
+    entity: Entity,
+    components: D,
+}
+
+impl World {
+    /// Writes the `C` components of all of the marked entities
+    pub fn save_snapshot<C, S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        C: SaveComponents,
+        S: Serializer,
+    {
+        let mut data: Vec<EntityData<C::Data>> = Vec::new();
+        if self.has_resource::<MaskedStorage<SaveMarker>>() {
+            let markers: Vec<(Entity, SaveMarker)> =
+                (&self.entities(), &self.read_component::<SaveMarker>())
+                    .join()
+                    .map(|(entity, marker)| (entity, *marker))
+                    .collect();
+            for (entity, marker) in markers {
+                data.push(EntityData {
+                    marker,
+                    entity,
+                    components: C::save(self, entity),
+                });
+            }
+        }
+        data.serialize(serializer)
+    }
+
+    /// Reads a snapshot that was written by `save_snapshot`.
+    /// Each entity is found by its marker (or created) and gets the saved components.
+    /// Returns the map from the saved entities to the loaded ones - the `Parent` and `Children`
+    /// are remapped already, other components with `Entity` fields can use it with `MapEntities`.
+    pub fn load_snapshot<'de, C, D>(&mut self, deserializer: D) -> Result<EntityMap, D::Error>
+    where
+        C: SaveComponents,
+        D: Deserializer<'de>,
+    {
+        let data: Vec<EntityData<C::Data>> = Vec::deserialize(deserializer)?;
+
+        let mut entity_map = EntityMap::default();
+        let mut loaded = Vec::with_capacity(data.len());
+        for item in data {
+            let entity = self.marked_entity(item.marker);
+            entity_map.insert(item.entity, entity);
+            loaded.push((entity, item.components));
+        }
+
+        for (entity, components) in loaded {
+            C::load(self, entity, components);
+        }
+        self.map_hierarchy(&entity_map);
+        self.apply_hooks();
+        Ok(entity_map)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate as gw_ecs;
+    use crate::entity::EntityMap;
+    use crate::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Component, Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+    struct Name(String);
+
+    #[derive(Component, Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[derive(Component, Default, Debug)]
+    struct NotSaved(u32);
+
+    type Saved = (Name, Health, Parent, Children);
+
+    fn make_world() -> World {
+        let mut world = World::default();
+        world.register::<Name>();
+        world.register::<Health>();
+        world.register::<NotSaved>();
+        world
+    }
+
+    fn load(world: &mut World, data: &[u8]) -> EntityMap {
+        let mut de = bincode::Deserializer::from_slice(data, bincode::options());
+        world.load_snapshot::<Saved, _>(&mut de).unwrap()
+    }
+
+    #[test]
+    fn save_and_load() {
+        let mut world = make_world();
+        let hero = world.spawn((Name("hero".into()), Health(10), NotSaved(1)));
+        let sword = world.spawn((Name("sword".into()),));
+        let rat = world.spawn((Name("rat".into()), Health(3)));
+        world.add_child(hero, sword);
+        world.mark_for_save(hero);
+        world.mark_for_save(sword);
+
+        let mut data = Vec::new();
+        world
+            .save_snapshot::<Saved, _>(&mut bincode::Serializer::new(&mut data, bincode::options()))
+            .unwrap();
+
+        // Same world - the marked entities are updated
+        world
+            .write_component::<Health>()
+            .insert(hero, Health(1))
+            .unwrap();
+        world
+            .write_component::<Health>()
+            .insert(sword, Health(5))
+            .unwrap();
+        let entity_map = load(&mut world, &data);
+        assert_eq!(entity_map.get(hero).unwrap(), hero);
+        assert_eq!(
+            *world.read_component::<Health>().get(hero).unwrap(),
+            Health(10)
+        );
+        assert!(world.read_component::<Health>().get(sword).is_none());
+        assert_eq!(world.read_component::<NotSaved>().get(hero).unwrap().0, 1);
+        assert!(world.entities().is_alive(rat));
+
+        // New world - the entities are created and the hierarchy is remapped
+        let mut other = make_world();
+        other.spawn((Name("placeholder".into()),));
+        let entity_map = load(&mut other, &data);
+        let new_hero = entity_map.get(hero).unwrap();
+        let new_sword = entity_map.get(sword).unwrap();
+        assert_ne!(new_hero, hero);
+        assert_eq!(
+            other.read_component::<Name>().get(new_hero).unwrap().0,
+            "hero"
+        );
+        assert!(other.read_component::<NotSaved>().get(new_hero).is_none());
+        assert_eq!(other.children(new_hero), vec![new_sword]);
+        assert_eq!(other.parent(new_sword), Some(new_hero));
+
+        // Loading again finds the same entities
+        let entity_map = load(&mut other, &data);
+        assert_eq!(entity_map.get(hero).unwrap(), new_hero);
+    }
+}
@@ -249,6 +249,7 @@ impl Schedule {
         world.increment_current_tick();
         self.initialize(world).unwrap_or_else(|e| panic!("{e}"));
         self.executor.run(&mut self.executable, world);
+        world.apply_hooks();
     }
 
     /// Initializes any newly-added systems and conditions, rebuilds the executable schedule,
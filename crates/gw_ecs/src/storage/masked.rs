@@ -1,8 +1,9 @@
 use super::{AnyStorage, UnprotectedStorage};
 use crate::{
-    components::Component,
-    entity::{Entity, EntityBuilder, Index},
-    event::Events,
+    components::{Component, ComponentHook, ComponentHooks},
+    entity::{Entities, Entity, EntityBuilder, Index},
+    removal_detection::RemovedEntities,
+    system::{CommandQueue, Commands},
     world::World,
 };
 use hibitset::{BitSet, BitSetLike};
@@ -13,7 +14,10 @@ use hibitset::{BitSet, BitSetLike};
 pub struct MaskedStorage<T: Component> {
     pub(super) mask: BitSet,
     pub(super) inner: T::Storage,
-    pub(crate) removed: Events<Entity>,
+    pub(crate) removed: RemovedEntities,
+    pub(crate) hooks: ComponentHooks<T>,
+    /// The commands queued by the hooks - see `World::apply_hooks`
+    pub(crate) queue: CommandQueue,
 }
 
 impl<T: Component> MaskedStorage<T> {
@@ -24,6 +28,8 @@ impl<T: Component> MaskedStorage<T> {
             mask: BitSet::new(),
             inner,
             removed: Default::default(),
+            hooks: ComponentHooks::new(),
+            queue: CommandQueue::default(),
         }
     }
 
@@ -32,7 +38,7 @@ impl<T: Component> MaskedStorage<T> {
     }
 
     /// Clear the contents of this storage.
-    /// Does not send any removed events
+    /// Does not record any removals
     pub fn clear(&mut self) {
         // SAFETY: `self.mask` is the correct mask as specified.
         unsafe {
@@ -63,17 +69,43 @@ impl<T: Component> MaskedStorage<T> {
         false
     }
 
+    /// Runs the hook (if any) with the component of the entity
+    pub(crate) fn run_hook(
+        &mut self,
+        hook: Option<ComponentHook<T>>,
+        entities: Entities,
+        entity: Entity,
+    ) {
+        if let Some(hook) = hook {
+            if self.mask.contains(entity.id()) {
+                // SAFETY: We checked the mask
+                let value = unsafe { &self.inner.get(entity.id()).data };
+                let mut commands = Commands::new_from_entities(&mut self.queue, entities);
+                hook(&mut commands, entity, value);
+            }
+        }
+    }
+
+    /// Runs the on_remove hook (if any) with a value that was already taken out of the storage
+    pub(crate) fn run_remove_hook(&mut self, entities: Entities, entity: Entity, value: &T) {
+        if let Some(hook) = self.hooks.on_remove {
+            let mut commands = Commands::new_from_entities(&mut self.queue, entities);
+            hook(&mut commands, entity, value);
+        }
+    }
+
     fn update_ticks(&mut self, world_tick: u32) {
         let MaskedStorage {
             mask,
             inner,
             removed,
+            ..
         } = self;
         for id in mask.iter() {
             let comp = unsafe { inner.get_mut(id) };
             comp.ticks.check_ticks(world_tick);
         }
-        removed.update();
+        removed.maintain(world_tick);
     }
 }
 
@@ -87,26 +119,39 @@ impl<T> AnyStorage for MaskedStorage<T>
 where
     T: Component,
 {
-    fn drop(&mut self, entities: &[Entity], world_tick: u32) {
+    fn drop(&mut self, entities: &[Entity], world: &World) {
+        let world_tick = world.current_tick();
         for entity in entities {
+            if self.hooks.on_remove.is_some() {
+                self.run_hook(self.hooks.on_remove, world.entities(), *entity);
+            }
             if MaskedStorage::drop(self, entity.id(), world_tick) {
-                self.removed.send(*entity);
+                self.removed.push(*entity, world_tick);
             }
         }
     }
 
     fn register(&self, world: &mut World) {
         world.register::<T>();
+        if !self.hooks.is_empty() {
+            world.set_hooks::<T>(self.hooks);
+        }
     }
 
     fn maintain(&mut self, world_ticks: u32) {
         self.update_ticks(world_ticks);
     }
 
-    fn try_move_component(&mut self, entity: Entity, source_tick: u32, dest: &mut EntityBuilder) {
+    fn try_move_component(&mut self, entity: Entity, source: &World, dest: &mut EntityBuilder) {
+        let source_tick = source.current_tick();
         if let Some(old) = self.remove(entity.id(), source_tick) {
+            self.run_remove_hook(source.entities(), entity, &old);
             dest.insert(old);
-            self.removed.send(entity);
+            self.removed.push(entity, source_tick);
         }
     }
+
+    fn take_commands(&mut self, queue: &mut CommandQueue) {
+        queue.append(&mut self.queue);
+    }
 }
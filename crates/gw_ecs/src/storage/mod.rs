@@ -4,6 +4,8 @@ mod default_vec;
 mod drain;
 mod generic;
 mod masked;
+#[cfg(feature = "serde")]
+mod serialize;
 mod storage;
 mod unprotected;
 
@@ -1,57 +1,47 @@
-// use super::{DistinctStorage, UnprotectedStorage};
-// use crate::{
-//     components::{CompMut, CompRef},
-//     entity::Index,
-// };
-// use hibitset::BitSetLike;
-
-// /// A null storage type, used for cases where the component
-// /// doesn't contain any data and instead works as a simple flag.
-// pub struct NullStorage<T>(T);
-
-// impl<T> UnprotectedStorage<T> for NullStorage<T>
-// where
-//     T: Default,
-// {
-//     #[cfg(feature = "nightly")]
-//     type AccessMut<'a>
-//     where
-//         T: 'a,
-//     = &'a mut T;
-
-//     unsafe fn clean<B>(&mut self, _has: B)
-//     where
-//         B: BitSetLike,
-//     {
-//     }
-
-//     unsafe fn get(&self, _: Index, last_system_tick: u32, world_tick: u32) -> CompRef<T> {
-//         &self.0
-//     }
-
-//     unsafe fn get_mut(&mut self, _: Index, last_system_tick: u32, world_tick: u32) -> CompMut<T> {
-//         &mut self.0
-//     }
-
-//     unsafe fn insert(&mut self, _: Index, _: T, world_tick: u32) {}
-
-//     unsafe fn remove(&mut self, _: Index, world_tick: u32) -> T {
-//         Default::default()
-//     }
-// }
-
-// impl<T> Default for NullStorage<T>
-// where
-//     T: Default,
-// {
-//     fn default() -> Self {
-//         use std::mem::size_of;
-
-//         assert_eq!(size_of::<T>(), 0, "NullStorage can only be used with ZST");
-
-//         NullStorage(Default::default())
-//     }
-// }
-
-// /// This is safe because you cannot mutate ZSTs.
-// unsafe impl<T> DistinctStorage for NullStorage<T> {}
+use super::{DistinctStorage, StorageCell, UnprotectedStorage};
+use crate::entity::Index;
+use hibitset::BitSetLike;
+use std::collections::HashMap;
+
+/// A null storage type, used for cases where the component
+/// doesn't contain any data and instead works as a simple flag.
+/// Only the change ticks of each entity are kept.
+pub struct NullStorage<T>(HashMap<Index, StorageCell<T>>);
+
+impl<T> Default for NullStorage<T> {
+    fn default() -> Self {
+        use std::mem::size_of;
+
+        assert_eq!(size_of::<T>(), 0, "NullStorage can only be used with ZST");
+
+        NullStorage(Default::default())
+    }
+}
+
+impl<T> UnprotectedStorage<T> for NullStorage<T> {
+    unsafe fn clean<B>(&mut self, _has: B)
+    where
+        B: BitSetLike,
+    {
+        // nothing to do
+    }
+
+    unsafe fn get(&self, id: Index) -> &StorageCell<T> {
+        &self.0[&id]
+    }
+
+    unsafe fn get_mut(&mut self, id: Index) -> &mut StorageCell<T> {
+        self.0.get_mut(&id).unwrap()
+    }
+
+    unsafe fn insert(&mut self, id: Index, v: StorageCell<T>) {
+        self.0.insert(id, v);
+    }
+
+    unsafe fn remove(&mut self, id: Index) -> StorageCell<T> {
+        self.0.remove(&id).unwrap()
+    }
+}
+
+// SAFETY: Each index has its own entry (like HashMapStorage), so distinct indices never alias.
+unsafe impl<T> DistinctStorage for NullStorage<T> {}
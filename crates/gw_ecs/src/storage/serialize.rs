@@ -0,0 +1,116 @@
+use super::{MaskedStorage, Storage};
+use crate::components::Component;
+use crate::entity::Entity;
+use hibitset::BitSetLike;
+use serde::de::Error;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::{Deref, DerefMut};
+
+/// The whole storage is written as a list of `(Entity, component)` pairs.
+/// This works the same way for all of the storage types.
+impl<'e, T, D> Serialize for Storage<'e, T, D>
+where
+    T: Component + Serialize,
+    D: Deref<Target = MaskedStorage<T>>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.count()))?;
+        for id in self.mask().iter() {
+            let entity = self.entities.entity(id);
+            if let Some(comp) = self.get(entity) {
+                seq.serialize_element(&(entity, &*comp))?;
+            }
+        }
+        seq.end()
+    }
+}
+
+impl<'e, T, D> Storage<'e, T, D>
+where
+    T: Component,
+    D: DerefMut<Target = MaskedStorage<T>>,
+{
+    /// Inserts the `(Entity, component)` pairs that were written by `serialize`.
+    /// The entities must be alive in this world - remap them first if they came from another one.
+    pub fn deserialize_into<'de, De>(&mut self, deserializer: De) -> Result<(), De::Error>
+    where
+        De: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let items: Vec<(Entity, T)> = Vec::deserialize(deserializer)?;
+        for (entity, comp) in items {
+            self.insert(entity, comp).map_err(De::Error::custom)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate as gw_ecs;
+    use crate::prelude::*;
+    use crate::storage::{BTreeStorage, HashMapStorage, NullStorage, VecStorage};
+    use bincode::Options;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Component, Serialize, Deserialize, Default, Debug, PartialEq)]
+    #[storage(VecStorage)]
+    struct InVec(u32);
+
+    #[derive(Component, Serialize, Deserialize, Default, Debug, PartialEq)]
+    struct InDenseVec(u32);
+
+    #[derive(Component, Serialize, Deserialize, Default, Debug, PartialEq)]
+    #[storage(HashMapStorage)]
+    struct InHashMap(u32);
+
+    #[derive(Component, Serialize, Deserialize, Default, Debug, PartialEq)]
+    #[storage(BTreeStorage)]
+    struct InBTree(u32);
+
+    #[derive(Component, Serialize, Deserialize, Default, Debug, PartialEq)]
+    #[storage(NullStorage)]
+    struct Flag;
+
+    fn round_trip<T>(make: fn(u32) -> T)
+    where
+        T: Component + Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+        T::Storage: Default,
+    {
+        let mut world = World::default();
+        world.register::<T>();
+        let a = world.spawn((make(1),));
+        let b = world.create_entity().id();
+        let c = world.spawn((make(3),));
+
+        let data = bincode::options()
+            .serialize(&world.read_component::<T>())
+            .unwrap();
+
+        world.write_component::<T>().remove(a);
+        world.write_component::<T>().remove(c);
+        assert_eq!(world.read_component::<T>().count(), 0);
+
+        let mut de = bincode::Deserializer::from_slice(&data, bincode::options());
+        world
+            .write_component::<T>()
+            .deserialize_into(&mut de)
+            .unwrap();
+
+        let storage = world.read_component::<T>();
+        assert_eq!(storage.count(), 2);
+        assert_eq!(*storage.get(a).unwrap(), make(1));
+        assert!(storage.get(b).is_none());
+        assert_eq!(*storage.get(c).unwrap(), make(3));
+    }
+
+    #[test]
+    fn storages_round_trip() {
+        round_trip(InVec);
+        round_trip(InDenseVec);
+        round_trip(InHashMap);
+        round_trip(InBTree);
+        round_trip(|_| Flag);
+    }
+}
@@ -4,9 +4,10 @@ use super::MaskedStorage;
 use super::UnprotectedStorage;
 use super::{Added, Changed, Drain};
 use crate::components::{CompMut, CompRef, Component};
-use crate::entity::EntitiesRes;
+use crate::entity::{Entities, EntitiesRes};
 use crate::join::JoinExt;
 use crate::resources::ResMut;
+use crate::system::CommandQueue;
 use crate::tick::ComponentTicks;
 use crate::{components::CastFrom, resources::ResRef, world::World};
 use crate::{
@@ -97,7 +98,7 @@ pub unsafe trait DistinctStorage {}
 /// A dynamic storage.
 pub trait AnyStorage {
     /// Drop components of given entities.
-    fn drop(&mut self, entities: &[Entity], world_tick: u32);
+    fn drop(&mut self, entities: &[Entity], world: &World);
 
     /// Registers the component in the world - for registry copy
     fn register(&self, world: &mut World);
@@ -105,7 +106,10 @@ pub trait AnyStorage {
     fn maintain(&mut self, world_ticks: u32);
 
     /// Moves the component of the given entity to the other world
-    fn try_move_component(&mut self, entity: Entity, source_tick: u32, dest: &mut EntityBuilder);
+    fn try_move_component(&mut self, entity: Entity, source: &World, dest: &mut EntityBuilder);
+
+    /// Moves the commands queued by the component hooks into the given queue
+    fn take_commands(&mut self, queue: &mut CommandQueue);
 }
 
 unsafe impl<T> CastFrom<T> for dyn AnyStorage
@@ -209,6 +213,13 @@ where
     pub fn mask(&self) -> &BitSet {
         &self.data.mask
     }
+
+    /// The entities that had this component removed (or were deleted) since the last system tick
+    pub fn removed(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.data
+            .removed
+            .iter_since(self.last_system_tick, self.world_tick)
+    }
 }
 
 impl<'e, T> Storage<'e, T, ResRef<'e, MaskedStorage<T>>>
@@ -314,19 +325,23 @@ where
     pub fn insert(&mut self, e: Entity, v: T) -> InsertResult<T> {
         if self.entities.is_alive(e) {
             let id = e.id();
-            if self.data.mask.contains(id) {
+            let entities = Entities::new(ResRef::clone(&self.entities));
+            let data: &mut MaskedStorage<T> = &mut self.data;
+            if data.mask.contains(id) {
                 // TODO - set_changed needs to be done!!!
                 // SAFETY: We checked the mask, so all invariants are met.
                 let mut cell = StorageCell::new(v, self.world_tick);
-                std::mem::swap(&mut cell, unsafe {
-                    self.data.inner.get_mut(id).deref_mut()
-                });
+                std::mem::swap(&mut cell, unsafe { data.inner.get_mut(id).deref_mut() });
+                data.run_remove_hook(entities.clone(), e, &cell.data);
+                data.run_hook(data.hooks.on_insert, entities, e);
                 Ok(Some(cell.data))
             } else {
-                self.data.mask.add(id);
+                data.mask.add(id);
                 // SAFETY: The mask was previously empty, so it is safe to insert.
                 let cell = StorageCell::new(v, self.world_tick);
-                unsafe { self.data.inner.insert(id, cell) };
+                unsafe { data.inner.insert(id, cell) };
+                data.run_hook(data.hooks.on_add, entities.clone(), e);
+                data.run_hook(data.hooks.on_insert, entities, e);
                 Ok(None)
             }
         } else {
@@ -344,7 +359,9 @@ where
             match self.data.remove(e.id(), self.world_tick) {
                 None => None,
                 Some(old) => {
-                    self.data.removed.send(e);
+                    self.data.removed.push(e, self.world_tick);
+                    let entities = Entities::new(ResRef::clone(&self.entities));
+                    self.data.run_remove_hook(entities, e, &old);
                     Some(old)
                 }
             }
@@ -88,6 +88,18 @@ impl CommandQueue {
         }
     }
 
+    /// Are there no queued commands?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Moves all of the commands from `other` to the end of this queue, leaving `other` empty.
+    #[inline]
+    pub fn append(&mut self, other: &mut CommandQueue) {
+        self.bytes.append(&mut other.bytes);
+    }
+
     /// Execute the queued [`Command`]s in the world.
     /// This clears the queue.
     #[inline]
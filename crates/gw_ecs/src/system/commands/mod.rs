@@ -113,6 +113,7 @@ impl SystemBuffer for CommandQueue {
             bevy_utils::tracing::info_span!("system_commands", name = _system_meta.name())
                 .entered();
         self.apply(world);
+        world.apply_hooks();
     }
 }
 
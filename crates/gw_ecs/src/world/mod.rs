@@ -1,16 +1,17 @@
 use crate::components::{Component, ReadComp, WriteComp};
-use crate::components::{ComponentSet, Components};
+use crate::components::{ComponentHooks, ComponentSet, Components};
 use crate::entity::EntityAllocator;
 use crate::entity::{Builder, Entities};
-use crate::entity::{EntitiesRes, Entity, EntityBuilder};
+use crate::entity::{EntitiesRes, Entity, EntityBuilder, EntityMap};
 use crate::event::{AllEvents, Event, Events, ManualEventReader};
 use crate::globals::{GlobalMut, GlobalRef, Globals};
+use crate::hierarchy::{Children, Parent};
 use crate::resources::Resources;
 use crate::resources::{ResMut, ResRef};
 use crate::resources::{Resource, ResourceId};
 use crate::schedule::{Schedule, ScheduleLabel, Schedules};
 use crate::storage::{MaskedStorage, Storage};
-use crate::system::{IntoSystem, System};
+use crate::system::{CommandQueue, IntoSystem, System};
 use atomize::Atom;
 use std::sync::atomic::{AtomicU32, Ordering};
 
@@ -66,13 +67,16 @@ impl World {
         let mut globals = globals;
         globals.ensure_with(|| Schedules::default(), 0);
 
-        World {
+        let mut world = World {
             id: id.into(),
             current_tick: AtomicU32::new(1),
             last_maintain_tick: 0,
             resources,
             globals,
-        }
+        };
+        world.register_with_hooks(crate::hierarchy::parent_hooks());
+        world.register_with_hooks(crate::hierarchy::children_hooks());
+        world
     }
 
     /// Sets the globals on this World.
@@ -776,6 +780,38 @@ impl World {
         self.register_with_storage::<T>(Default::default())
     }
 
+    /// Registers the component (if necessary) and sets its lifecycle hooks
+    pub fn register_with_hooks<T: Component>(&mut self, hooks: ComponentHooks<T>) -> bool
+    where
+        T::Storage: Default,
+    {
+        let added = self.register::<T>();
+        self.set_hooks(hooks);
+        added
+    }
+
+    /// Replaces the lifecycle hooks of a registered component
+    pub fn set_hooks<T: Component>(&mut self, hooks: ComponentHooks<T>) {
+        self.write_resource::<MaskedStorage<T>>().hooks = hooks;
+    }
+
+    /// Applies the commands queued by the component hooks - including any that those commands cause
+    pub fn apply_hooks(&mut self) {
+        loop {
+            let mut queue = CommandQueue::default();
+            {
+                let meta = self.components_mut();
+                for comp in meta.iter_mut(self) {
+                    comp.take_commands(&mut queue);
+                }
+            }
+            if queue.is_empty() {
+                return;
+            }
+            queue.apply(self);
+        }
+    }
+
     pub(crate) fn register_with_storage<T>(&mut self, storage: T::Storage) -> bool
     where
         T: Component,
@@ -862,15 +898,10 @@ impl World {
         self.write_resource::<Events<T>>()
     }
 
-    // pub fn removed_components(&self) -> ResRef<RemovedComponentEvents> {
-    //     self.read_resource::<RemovedComponentEvents>()
-    // }
-
-    // pub fn removed_components_mut(&self) -> ResMut<RemovedComponentEvents> {
-    //     self.write_resource::<RemovedComponentEvents>()
-    // }
-
-    // // Lazy Update
+    /// The entities that had the component removed (or were deleted) since the last maintain
+    pub fn removed<T: Component>(&self) -> Vec<Entity> {
+        self.read_component::<T>().removed().collect()
+    }
 
     // pub fn commands(&self) -> ResRef<Commands> {
     //     self.resources
@@ -915,6 +946,7 @@ impl World {
     pub fn delete_entities(&mut self, delete: &[Entity]) {
         self.delete_components(delete);
         let _ = self.entities_mut().alloc.kill(delete);
+        self.apply_hooks();
     }
 
     pub fn delete_all(&mut self) {
@@ -936,17 +968,32 @@ impl World {
         alloc.generation(e.id()) == Some(e.gen())
     }
 
+    /// Moves the entity (and all of its descendants) to the other world.
+    /// The entity is taken away from its parent and the `Parent`/`Children` of the moved entities are remapped.
+    /// Returns the new entity.
     pub fn move_entity_to(&mut self, entity: Entity, dest: &mut World) -> Entity {
-        let new_entity = {
-            let mut builder = dest.create_entity();
-            let storages = self.read_resource::<Components>();
-            for storage in storages.iter_mut(self) {
-                storage.try_move_component(entity, self.current_tick(), &mut builder);
-            }
-            builder.id()
-        };
-        let _ = self.delete_entity(entity); // Ignore
-        new_entity
+        self.remove_parent(entity);
+        let mut moving = vec![entity];
+        moving.extend(self.read_component::<Children>().iter_descendants(entity));
+
+        let mut entity_map = EntityMap::default();
+        for old_entity in moving.iter() {
+            let new_entity = self.move_components_to(*old_entity, dest);
+            entity_map.insert(*old_entity, new_entity);
+        }
+        // All at once, so that the hierarchy hooks do not unlink entities that are still to be moved
+        self.delete_entities(&moving);
+        dest.map_hierarchy(&entity_map);
+        entity_map.get(entity).unwrap()
+    }
+
+    fn move_components_to(&mut self, entity: Entity, dest: &mut World) -> Entity {
+        let mut builder = dest.create_entity();
+        let storages = self.read_resource::<Components>();
+        for storage in storages.iter_mut(self) {
+            storage.try_move_component(entity, self, &mut builder);
+        }
+        builder.id()
     }
 
     pub fn current_tick(&self) -> u32 {
@@ -964,6 +1011,8 @@ impl World {
 
     pub fn maintain(&mut self) {
         // println!("WORLD MAINTAIN");
+        self.apply_hooks();
+
         // All maintain changes are in new tick so that they can be detected by change trackers
         self.last_maintain_tick = self.increment_current_tick();
 
@@ -994,7 +1043,7 @@ impl World {
             .unwrap()
             .iter_mut(self)
         {
-            storage.drop(delete, self.current_tick());
+            storage.drop(delete, self);
         }
     }
 }
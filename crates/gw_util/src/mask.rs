@@ -30,7 +30,7 @@ pub fn get_area_mask<T: PathfindingSource + BlockedSource>(source: &T) -> Grid<u
                 wrapped_flood_replace(
                     &mut grid,
                     Point::new(x as i32, y as i32),
-                    1,
+                    u8::MAX,
                     area_count,
                     source.wrap(),
                 );
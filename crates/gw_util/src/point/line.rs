@@ -0,0 +1,62 @@
+use super::Point;
+
+/// The points on the (Bresenham) line from start to end - including both ends
+pub fn get_line(start: Point, end: Point) -> Vec<Point> {
+    let dx = (end.x - start.x).abs();
+    let dy = -(end.y - start.y).abs();
+    let sx = if start.x < end.x { 1 } else { -1 };
+    let sy = if start.y < end.y { 1 } else { -1 };
+
+    let mut out = Vec::new();
+    let mut err = dx + dy;
+    let mut current = start;
+
+    loop {
+        out.push(current);
+        if current == end {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            current.x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            current.y += sy;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn straight_and_diagonal() {
+        let line = get_line(Point::new(1, 1), Point::new(4, 1));
+        assert_eq!(
+            line,
+            [(1, 1).into(), (2, 1).into(), (3, 1).into(), (4, 1).into()]
+        );
+
+        let line = get_line(Point::new(3, 3), Point::new(1, 1));
+        assert_eq!(line, [(3, 3).into(), (2, 2).into(), (1, 1).into()]);
+
+        let line = get_line(Point::new(2, 2), Point::new(2, 2));
+        assert_eq!(line, [(2, 2).into()]);
+    }
+
+    #[test]
+    fn shallow() {
+        let line = get_line(Point::new(0, 0), Point::new(4, 2));
+        assert_eq!(line.len(), 5);
+        assert_eq!(line[0], Point::new(0, 0));
+        assert_eq!(line[4], Point::new(4, 2));
+        for pair in line.windows(2) {
+            assert!((pair[1].x - pair[0].x).abs() <= 1);
+            assert!((pair[1].y - pair[0].y).abs() <= 1);
+        }
+    }
+}
@@ -2,3 +2,4 @@ mod point;
 pub use point::*;
 
 pub mod distance;
+pub mod line;
@@ -1,11 +1,13 @@
 use rand::prelude::*;
 use rand_pcg::Mcg128Xsl64;
+use serde_derive::{Deserialize, Serialize};
 
 pub use rand::seq::SliceRandom;
 pub use rand::RngCore;
 
 type RNG = Mcg128Xsl64;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RandomNumberGenerator {
     source: RNG,
 }
@@ -52,6 +52,34 @@ impl Into<Value> for toml::Value {
     }
 }
 
+/// Converts the value into toml - entities and empty values can not be converted
+pub fn to_toml(value: &Value) -> Result<toml::Value, String> {
+    let toml = match value {
+        Value::Empty => return Err("Empty values can not be converted to toml".to_string()),
+        Value::Entity(_) => return Err("Entities can not be converted to toml".to_string()),
+        Value::Index(v) => toml::Value::Integer(*v as i64),
+        Value::Integer(v) => toml::Value::Integer(*v),
+        Value::Float(v) => toml::Value::Float(*v),
+        Value::String(v) => toml::Value::String(v.clone()),
+        Value::Boolean(v) => toml::Value::Boolean(*v),
+        Value::Point(x, y) => toml::Value::Array(vec![
+            toml::Value::Integer(*x as i64),
+            toml::Value::Integer(*y as i64),
+        ]),
+        Value::List(list) => {
+            toml::Value::Array(list.iter().map(to_toml).collect::<Result<_, _>>()?)
+        }
+        Value::Map(map) => {
+            let mut table = Table::new();
+            for (key, value) in map.iter() {
+                table.insert(key.to_string(), to_toml(value)?);
+            }
+            toml::Value::Table(table)
+        }
+    };
+    Ok(toml)
+}
+
 // pub fn parse_reader(reader: &mut dyn BufRead) -> Result<Value, String> {
 //     let mut out = HashMap::new();
 
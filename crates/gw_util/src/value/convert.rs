@@ -0,0 +1,126 @@
+use super::Value;
+use crate::toml::to_toml;
+use gw_ecs::components::Component;
+use gw_ecs::entity::MapEntities;
+use gw_ecs::registry::TypeRegistry;
+use gw_ecs::resources::Resource;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Converts serializable data into a `Value`.
+/// The conversion goes through toml, so unit structs and `None` can not be converted on their own.
+pub fn to_value<T: Serialize>(data: &T) -> Result<Value, String> {
+    match toml::Value::try_from(data) {
+        Err(e) => Err(e.to_string()),
+        Ok(v) => Ok(v.into()),
+    }
+}
+
+/// Builds the data from a `Value`
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T, String> {
+    let toml = to_toml(value)?;
+    toml.try_into().map_err(|e: toml::de::Error| e.to_string())
+}
+
+/// Registers types with a `TypeRegistry<Value>` using serde to convert them - no per type code needed.
+pub trait RegisterValue {
+    fn register_component<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+        T::Storage: Default;
+
+    /// Registers a component that holds entity references (see `MapEntities`)
+    fn register_mapped_component<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned + MapEntities,
+        T::Storage: Default;
+
+    fn register_resource<R>(&mut self, id: &str)
+    where
+        R: Resource + Clone + Send + Sync + Serialize + DeserializeOwned;
+}
+
+impl RegisterValue for TypeRegistry<Value> {
+    fn register_component<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+        T::Storage: Default,
+    {
+        self.register_component_with::<T>(id, from_value::<T>, to_value::<T>);
+    }
+
+    fn register_mapped_component<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned + MapEntities,
+        T::Storage: Default,
+    {
+        self.register_mapped_component_with::<T>(id, from_value::<T>, to_value::<T>);
+    }
+
+    fn register_resource<R>(&mut self, id: &str)
+    where
+        R: Resource + Clone + Send + Sync + Serialize + DeserializeOwned,
+    {
+        self.register_resource_with::<R>(id, from_value::<R>, to_value::<R>);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gw_ecs::prelude::*;
+    use serde::Deserialize;
+
+    #[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Stats {
+        hp: i32,
+        name: String,
+        speed: Option<f32>,
+        tags: Vec<String>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Depth(u32);
+
+    #[test]
+    fn round_trip() {
+        let stats = Stats {
+            hp: 10,
+            name: "rat".to_string(),
+            speed: None,
+            tags: vec!["animal".to_string()],
+        };
+        let value = to_value(&stats).unwrap();
+        assert_eq!(value.get_value("hp").unwrap(), &Value::Integer(10));
+        assert_eq!(from_value::<Stats>(&value).unwrap(), stats);
+
+        assert_eq!(to_value(&Depth(3)).unwrap(), Value::Integer(3));
+        assert!(from_value::<Depth>(&Value::String("deep".into())).is_err());
+    }
+
+    #[test]
+    fn registry() {
+        let mut registry = TypeRegistry::<Value>::new();
+        registry.register_component::<Stats>("stats");
+        registry.register_resource::<Depth>("depth");
+
+        let value = crate::toml::parse_string("hp = 4\nname = 'bat'\ntags = []").unwrap();
+
+        let mut world = World::default();
+        let entity = registry.spawn(&mut world, [("stats", &value)]).unwrap();
+        assert_eq!(
+            world.read_component::<Stats>().get(entity).unwrap().name,
+            "bat"
+        );
+
+        let dump = registry.dump_entity(&world, entity);
+        assert_eq!(dump.len(), 1);
+        assert_eq!(dump[0].1.as_ref().unwrap(), &value);
+
+        registry
+            .get("depth")
+            .unwrap()
+            .insert_resource(&mut world, &Value::Integer(2))
+            .unwrap();
+        assert_eq!(world.read_resource::<Depth>().0, 2);
+    }
+}
@@ -1,3 +1,6 @@
+mod convert;
+pub use convert::*;
+
 mod key;
 pub use key::*;
 
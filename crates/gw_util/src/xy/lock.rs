@@ -1,4 +1,6 @@
-#[derive(Debug, Copy, Clone, Default)]
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
 pub enum Lock {
     #[default]
     None,
@@ -1,6 +1,7 @@
 use crate::rect::Rect;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
 pub enum Wrap {
     #[default]
     None,
@@ -1,20 +1,94 @@
 use crate::action::{Action, ActionResult};
+use crate::being::{award_kill_xp, Being, BeingFlags, Stat, Stats};
+use crate::combat::being_name;
+use crate::hero::Hero;
+use crate::horde::release_followers;
+use crate::log::Logger;
+use crate::task::Executor;
 use gw_ecs::prelude::{Entity, World};
 
 #[derive(Copy, Clone, Debug)]
 pub struct DeadAction {
     pub entity: Entity,
+    pub killer: Option<Entity>,
 }
 
 impl DeadAction {
     pub fn new(entity: Entity) -> DeadAction {
-        DeadAction { entity }
+        DeadAction {
+            entity,
+            killer: None,
+        }
+    }
+
+    pub fn killed_by(entity: Entity, killer: Entity) -> DeadAction {
+        DeadAction {
+            entity,
+            killer: Some(killer),
+        }
     }
 }
 
 impl Action for DeadAction {
-    fn execute(&mut self, _world: &mut World) -> ActionResult {
-        // TODO - Delete entity from ECS?
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        if let Some(killer) = self.killer {
+            award_kill_xp(world, killer, self.entity);
+        }
+        kill_being(world, self.entity);
         ActionResult::Dead(self.entity)
     }
 }
+
+/// Takes the being off of the map and out of the executor.
+/// The hero is only marked as destroyed (the entity is kept so the game can end properly).
+pub fn kill_being(world: &mut World, entity: Entity) {
+    if !world.entities().is_alive(entity) {
+        return;
+    }
+
+    world.write_resource::<Executor>().remove(entity);
+
+    if let Some(mut being) = world.write_component::<Being>().get_mut(entity) {
+        being.set_flag(BeingFlags::DESTROYED);
+    }
+
+    release_followers(world, entity, true);
+
+    if world.read_resource::<Hero>().entity == entity {
+        return;
+    }
+
+    // The Position hooks take the being off of the map
+    world.delete_entity(entity);
+}
+
+/// Kills the being (logging "X dies") if its health has run out.
+/// The killer (if any) is awarded the being's xp.
+pub fn kill_if_dead(world: &mut World, entity: Entity, killer: Option<Entity>) -> bool {
+    if !is_dead(world, entity) {
+        return false;
+    }
+    let name = being_name(world, entity);
+    world
+        .write_global::<Logger>()
+        .log_combat(format!("{} dies", name), true);
+
+    let mut action = match killer {
+        None => DeadAction::new(entity),
+        Some(killer) => DeadAction::killed_by(entity, killer),
+    };
+    matches!(action.execute(world), ActionResult::Dead(_))
+}
+
+fn is_dead(world: &World, entity: Entity) -> bool {
+    if !world.entities().is_alive(entity) {
+        return false; // Already handled
+    }
+    match world.read_component::<Stats>().get(entity) {
+        None => false,
+        Some(stats) => match stats.get(Stat::HEALTH) {
+            None => false,
+            Some(health) => health <= 0,
+        },
+    }
+}
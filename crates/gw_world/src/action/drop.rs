@@ -0,0 +1,88 @@
+use crate::action::idle::IdleAction;
+use crate::action::{Action, ActionResult};
+use crate::being::Being;
+use crate::combat::being_name;
+use crate::effect::fire_cell_action;
+use crate::item::{item_name, place_item, Inventory};
+use crate::log::Logger;
+use crate::map::Map;
+use crate::position::Position;
+use gw_ecs::prelude::{Entity, World};
+
+/// Drops an item from the being's inventory at its location
+#[derive(Copy, Clone, Debug)]
+pub struct DropAction {
+    pub entity: Entity,
+    pub item: Entity,
+}
+
+impl DropAction {
+    pub fn new(entity: Entity, item: Entity) -> DropAction {
+        DropAction { entity, item }
+    }
+
+    fn validate(&mut self, world: &mut World) -> Option<ActionResult> {
+        let act_time = match world.read_component::<Being>().get(self.entity) {
+            None => return Some(ActionResult::Dead(self.entity)),
+            Some(being) => being.act_time,
+        };
+
+        let has_item = match world.read_component::<Inventory>().get(self.entity) {
+            None => false,
+            Some(inventory) => inventory.contains(self.item),
+        };
+
+        if !has_item || !world.read_component::<Position>().contains(self.entity) {
+            return Some(ActionResult::Replace(Box::new(IdleAction::new(
+                self.entity,
+                act_time,
+            ))));
+        }
+
+        None
+    }
+
+    fn do_action(&mut self, world: &mut World) -> ActionResult {
+        let pos = world
+            .read_component::<Position>()
+            .get(self.entity)
+            .unwrap()
+            .point();
+        let idx = world
+            .read_resource::<Map>()
+            .get_wrapped_index(pos.x, pos.y)
+            .unwrap();
+
+        if let Some(mut inventory) = world.write_component::<Inventory>().get_mut(self.entity) {
+            inventory.remove(self.item);
+        }
+        place_item(world, self.item, idx);
+
+        let msg = format!(
+            "{} drops {}",
+            being_name(world, self.entity),
+            item_name(world, self.item)
+        );
+        world.write_global::<Logger>().log(msg);
+
+        // The cell effects act on the item (e.g. store it in a chest)
+        fire_cell_action(world, pos, "drop", Some(self.item));
+
+        let act_time = world
+            .read_component::<Being>()
+            .get(self.entity)
+            .unwrap()
+            .act_time;
+        ActionResult::Done(act_time)
+    }
+}
+
+impl Action for DropAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        if let Some(res) = self.validate(world) {
+            return res;
+        }
+
+        self.do_action(world)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::action::idle::IdleAction;
+use crate::action::{Action, ActionResult};
+use crate::being::Being;
+use crate::combat::being_name;
+use crate::item::{item_name, EquipSlot, Inventory, Item};
+use crate::log::Logger;
+use gw_ecs::prelude::{Entity, World};
+
+/// Equips (wields/wears) an item from the being's inventory
+#[derive(Copy, Clone, Debug)]
+pub struct EquipAction {
+    pub entity: Entity,
+    pub item: Entity,
+}
+
+impl EquipAction {
+    pub fn new(entity: Entity, item: Entity) -> EquipAction {
+        EquipAction { entity, item }
+    }
+
+    fn validate(&mut self, world: &mut World) -> Result<EquipSlot, ActionResult> {
+        let act_time = match world.read_component::<Being>().get(self.entity) {
+            None => return Err(ActionResult::Dead(self.entity)),
+            Some(being) => being.act_time,
+        };
+
+        let has_item = match world.read_component::<Inventory>().get(self.entity) {
+            None => false,
+            Some(inventory) => inventory.contains(self.item),
+        };
+        let slot = world
+            .read_component::<Item>()
+            .get(self.item)
+            .and_then(|item| item.slot);
+
+        match (has_item, slot) {
+            (true, Some(slot)) => Ok(slot),
+            _ => Err(ActionResult::Replace(Box::new(IdleAction::new(
+                self.entity,
+                act_time,
+            )))),
+        }
+    }
+
+    fn do_action(&mut self, world: &mut World, slot: EquipSlot) -> ActionResult {
+        let old = world
+            .write_component::<Inventory>()
+            .get_mut(self.entity)
+            .unwrap()
+            .equip(slot, self.item);
+
+        let name = being_name(world, self.entity);
+        let mut logger = world.write_global::<Logger>();
+        if let Some(old) = old {
+            logger.log(format!("{} removes {}", name, item_name(world, old)));
+        }
+        logger.log(format!("{} equips {}", name, item_name(world, self.item)));
+        drop(logger);
+
+        let act_time = world
+            .read_component::<Being>()
+            .get(self.entity)
+            .unwrap()
+            .act_time;
+        ActionResult::Done(act_time)
+    }
+}
+
+impl Action for EquipAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        match self.validate(world) {
+            Err(res) => res,
+            Ok(slot) => self.do_action(world, slot),
+        }
+    }
+}
+
+/// Takes off whatever is in the slot (it stays in the inventory)
+#[derive(Copy, Clone, Debug)]
+pub struct UnequipAction {
+    pub entity: Entity,
+    pub slot: EquipSlot,
+}
+
+impl UnequipAction {
+    pub fn new(entity: Entity, slot: EquipSlot) -> UnequipAction {
+        UnequipAction { entity, slot }
+    }
+}
+
+impl Action for UnequipAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        let act_time = match world.read_component::<Being>().get(self.entity) {
+            None => return ActionResult::Dead(self.entity),
+            Some(being) => being.act_time,
+        };
+
+        let old = match world.write_component::<Inventory>().get_mut(self.entity) {
+            None => None,
+            Some(mut inventory) => inventory.unequip(self.slot),
+        };
+
+        match old {
+            None => ActionResult::Replace(Box::new(IdleAction::new(self.entity, act_time))),
+            Some(old) => {
+                let msg = format!(
+                    "{} removes {}",
+                    being_name(world, self.entity),
+                    item_name(world, old)
+                );
+                world.write_global::<Logger>().log(msg);
+                ActionResult::Done(act_time)
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::action::idle::IdleAction;
+use crate::action::{Action, ActionResult};
+use crate::being::Being;
+use crate::horde::free_captive;
+use gw_ecs::prelude::{Entity, World};
+
+/// Breaks the chains of a captive - who then becomes an ally
+#[derive(Copy, Clone, Debug)]
+pub struct FreeCaptiveAction {
+    pub entity: Entity,
+    pub captive: Entity,
+}
+
+impl FreeCaptiveAction {
+    pub fn new(entity: Entity, captive: Entity) -> FreeCaptiveAction {
+        FreeCaptiveAction { entity, captive }
+    }
+}
+
+impl Action for FreeCaptiveAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        let act_time = match world.read_component::<Being>().get(self.entity) {
+            None => return ActionResult::Dead(self.entity),
+            Some(being) => being.act_time,
+        };
+
+        match free_captive(world, self.captive, self.entity) {
+            false => ActionResult::Replace(Box::new(IdleAction::new(self.entity, act_time))),
+            true => ActionResult::Done(act_time),
+        }
+    }
+}
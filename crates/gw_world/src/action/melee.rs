@@ -0,0 +1,286 @@
+use crate::action::dead::kill_if_dead;
+use crate::action::idle::IdleAction;
+use crate::action::{Action, ActionResult};
+use crate::being::{AIFlags, Being, BeingFlags, BeingKindFlags};
+use crate::combat::{being_name, hit_chance, Attack, Melee};
+use crate::horde::set_leader_target;
+use crate::item::{EquipSlot, Inventory, Item, ItemKind, ItemKinds};
+use crate::log::Logger;
+use crate::position::Position;
+use gw_ecs::prelude::{Entity, World};
+use gw_util::rng::RandomNumberGenerator;
+use std::sync::Arc;
+
+/// The chance that an EVASIVE being dodges a blow that would have hit
+pub const EVADE_CHANCE: u32 = 33;
+
+#[derive(Copy, Clone, Debug)]
+pub struct MeleeAction {
+    pub entity: Entity,
+    pub target: Entity,
+}
+
+impl MeleeAction {
+    pub fn new(entity: Entity, target: Entity) -> MeleeAction {
+        MeleeAction { entity, target }
+    }
+
+    fn validate(&mut self, world: &mut World) -> Option<ActionResult> {
+        let beings = world.read_component::<Being>();
+
+        let act_time = match beings.get(self.entity) {
+            None => return Some(ActionResult::Dead(self.entity)),
+            Some(being) => being.act_time,
+        };
+
+        if !world.read_component::<Melee>().contains(self.entity) {
+            return Some(ActionResult::Replace(Box::new(IdleAction::new(
+                self.entity,
+                act_time,
+            ))));
+        }
+
+        let can_attack = match beings.get(self.target) {
+            None => false,
+            Some(target) => {
+                !target
+                    .kind_flags
+                    .contains(BeingKindFlags::CANNOT_BE_ATTACKED)
+                    && !target.has_flag(BeingFlags::DESTROYED)
+            }
+        };
+
+        if !can_attack || !world.read_component::<Position>().contains(self.target) {
+            return Some(ActionResult::Replace(Box::new(IdleAction::new(
+                self.entity,
+                act_time,
+            ))));
+        }
+
+        None
+    }
+
+    fn pick_attack(&self, world: &World) -> Option<Attack> {
+        let mut rng = world.write_resource::<RandomNumberGenerator>();
+
+        // An equipped weapon replaces the natural attacks
+        if let Some(kind) = equipped_weapon(world, self.entity) {
+            if let Some(ref melee) = kind.melee {
+                return melee.pick_attack(&mut rng).cloned();
+            }
+        }
+
+        let melees = world.read_component::<Melee>();
+        let melee = melees.get(self.entity)?;
+        melee.pick_attack(&mut rng).cloned()
+    }
+
+    fn do_action(&mut self, world: &mut World) -> ActionResult {
+        let act_time = world
+            .read_component::<Being>()
+            .get(self.entity)
+            .unwrap()
+            .act_time;
+
+        let attack = match self.pick_attack(world) {
+            None => return ActionResult::Replace(Box::new(IdleAction::new(self.entity, act_time))),
+            Some(attack) => attack,
+        };
+
+        // Followers join in
+        set_leader_target(world, self.entity, self.target);
+
+        let (never_miss, evasive) = {
+            let beings = world.read_component::<Being>();
+            let flags = |entity| beings.get(entity).map(|b| b.ai_flags).unwrap_or_default();
+            (
+                flags(self.entity).contains(AIFlags::NEVER_MISS),
+                flags(self.target).contains(AIFlags::EVASIVE),
+            )
+        };
+
+        let hit = never_miss
+            || world
+                .write_resource::<RandomNumberGenerator>()
+                .chance(hit_chance(world, self.entity, self.target, attack.chance()));
+
+        if !hit {
+            attack.miss_msg().log(world, self.entity, self.target);
+            return ActionResult::Done(act_time);
+        }
+
+        if evasive
+            && world
+                .write_resource::<RandomNumberGenerator>()
+                .chance(EVADE_CHANCE)
+        {
+            let msg = format!(
+                "{} evades {}",
+                being_name(world, self.target),
+                being_name(world, self.entity)
+            );
+            world.write_global::<Logger>().log_combat(msg, true);
+            return ActionResult::Done(act_time);
+        }
+
+        attack.hit_msg().log(world, self.entity, self.target);
+
+        let target_pt = world
+            .read_component::<Position>()
+            .get(self.target)
+            .unwrap()
+            .point();
+
+        for effect in attack.effects().iter() {
+            effect.fire(world, target_pt, Some(self.target));
+            if !world.entities().is_alive(self.target) {
+                break;
+            }
+        }
+
+        kill_if_dead(world, self.target, Some(self.entity));
+
+        ActionResult::Done(act_time)
+    }
+}
+
+impl Action for MeleeAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        if let Some(res) = self.validate(world) {
+            return res;
+        }
+
+        self.do_action(world)
+    }
+}
+
+fn equipped_weapon(world: &World, entity: Entity) -> Option<Arc<ItemKind>> {
+    let weapon = world
+        .read_component::<Inventory>()
+        .get(entity)?
+        .equipped(EquipSlot::WEAPON)?;
+    let id = world.read_component::<Item>().get(weapon)?.id.clone();
+    world.try_read_global::<ItemKinds>()?.get(&id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::action::move_step::MoveStepAction;
+    use crate::being::{Stat, Stats};
+    use crate::combat::parse_melee;
+    use crate::hero::Hero;
+    use crate::map::Map;
+    use crate::task::Executor;
+    use crate::test_util::make_ecs;
+    use gw_util::value::Value;
+    use std::collections::HashMap;
+
+    fn make_being(world: &mut World, name: &str, x: i32, health: i32) -> Entity {
+        let mut being = Being::new(name.to_string());
+        being.name = Some(name.to_string());
+
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, health);
+
+        let mut data = HashMap::new();
+        data.insert("damage".into(), Value::from(5));
+        data.insert("chance".into(), Value::from(100));
+        let melee = parse_melee(&Value::from(data)).ok().unwrap();
+
+        let entity = world.spawn((being, Position::new(x, 1), stats, melee));
+        world.write_resource::<Executor>().insert(entity, 100);
+        entity
+    }
+
+    #[test]
+    fn bump_to_kill() {
+        let mut ecs = make_ecs(5, 3);
+        let world = ecs.current_world_mut();
+
+        let hero = make_being(world, "hero", 1, 10);
+        world.insert_resource(Hero::new(hero));
+        let rat = make_being(world, "rat", 2, 8);
+
+        let mut action = MoveStepAction::new(hero, 1, 0);
+        let mut result = action.execute(world);
+        // bump -> melee
+        while let ActionResult::Replace(mut next) = result {
+            result = next.execute(world);
+        }
+        assert!(matches!(result, ActionResult::Done(100)));
+        assert_eq!(
+            world
+                .read_component::<Stats>()
+                .get(rat)
+                .unwrap()
+                .get(Stat::HEALTH),
+            Some(3)
+        );
+
+        let mut action = MeleeAction::new(hero, rat);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert!(!world.entities().is_alive(rat));
+        assert_eq!(world.read_resource::<Map>().iter_beings(2 + 5).count(), 0);
+        assert_eq!(world.read_resource::<Executor>().len(), 1);
+
+        let mut logger = world.write_global::<Logger>();
+        let msgs: Vec<String> = logger.iter().map(|m| m.msg.clone()).collect();
+        assert_eq!(
+            msgs[0],
+            "hero hits rat #[red][5], hero hits rat #[red][5], rat dies"
+        );
+    }
+
+    #[test]
+    fn defense_and_xp() {
+        let mut ecs = make_ecs(5, 3);
+        let world = ecs.current_world_mut();
+
+        let hero = make_being(world, "hero", 1, 10);
+        world.insert_resource(Hero::new(hero));
+        let rat = make_being(world, "rat", 2, 5);
+        world.write_component::<Being>().get_mut(rat).unwrap().xp = 7;
+
+        // Too well defended to hit
+        world
+            .write_component::<Stats>()
+            .get_mut(rat)
+            .unwrap()
+            .set(Stat::DEFENSE, 100);
+        let mut action = MeleeAction::new(hero, rat);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert!(world.entities().is_alive(rat));
+
+        world
+            .write_component::<Stats>()
+            .get_mut(rat)
+            .unwrap()
+            .set(Stat::DEFENSE, 0);
+        let mut action = MeleeAction::new(hero, rat);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert!(!world.entities().is_alive(rat));
+        assert_eq!(world.read_component::<Being>().get(hero).unwrap().xp, 7);
+    }
+
+    #[test]
+    fn monster_does_not_kill_hero() {
+        let mut ecs = make_ecs(5, 3);
+        let world = ecs.current_world_mut();
+
+        let hero = make_being(world, "hero", 1, 5);
+        world.insert_resource(Hero::new(hero));
+        let rat = make_being(world, "rat", 2, 8);
+
+        let mut action = MeleeAction::new(rat, hero);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+
+        // hero stays in the world, but is destroyed
+        assert!(world.entities().is_alive(hero));
+        assert!(world
+            .read_component::<Being>()
+            .get(hero)
+            .unwrap()
+            .has_flag(BeingFlags::DESTROYED));
+    }
+}
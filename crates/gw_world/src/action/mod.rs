@@ -2,6 +2,15 @@ mod action;
 pub use action::*;
 
 pub mod dead;
+pub mod drop;
+pub mod equip;
+pub mod free_captive;
 pub mod idle;
+pub mod melee;
 pub mod move_step;
 pub mod need_input;
+pub mod open_door;
+pub mod pickup;
+pub mod ranged;
+pub mod search;
+pub mod use_item;
@@ -1,15 +1,23 @@
+use crate::action::free_captive::FreeCaptiveAction;
 use crate::action::idle::IdleAction;
+use crate::action::melee::MeleeAction;
 use crate::action::{Action, ActionResult};
-use crate::being::Being;
+use crate::being::{AIFlags, Being, BeingFlags, BeingKindFlags};
+use crate::combat::Melee;
 use crate::effect::fire_cell_action;
 use crate::hero::Hero;
+use crate::horde::are_enemies;
 use crate::log::Logger;
 use crate::map::cell_flavor;
+use crate::map::promote_on_step;
 use crate::map::Map;
 use crate::position::Position;
+use crate::status::{StatusEffects, StatusKind};
+use crate::task::{ensure_scent_map, Executor, ScentMap};
 use gw_app::log;
-use gw_ecs::prelude::{Entity, ReadComp, ResMut, ResRef, World};
-use gw_util::point::Point;
+use gw_ecs::prelude::{Entity, GlobalMut, ReadComp, ResRef, World};
+use gw_util::point::{Point, DIRS};
+use gw_util::rng::RandomNumberGenerator;
 
 #[derive(Copy, Clone, Debug)]
 pub struct MoveStepAction {
@@ -29,7 +37,7 @@ impl MoveStepAction {
             ResRef<Hero>,
             ReadComp<Being>,
             ReadComp<Position>,
-            ResMut<Logger>,
+            GlobalMut<Logger>,
         )>();
 
         let being = match beings.get(self.entity) {
@@ -79,6 +87,9 @@ impl MoveStepAction {
 
         let actor_is_hero = self.entity == hero.entity;
         let act_time = being.act_time;
+        let will_attack = !being.kind_flags.contains(BeingKindFlags::WILL_NOT_ATTACK)
+            && world.read_component::<Melee>().contains(self.entity);
+        let pass_wall = being.ai_flags.contains(AIFlags::PASS_WALL);
         drop(being);
 
         if map.is_blocked(idx) {
@@ -97,8 +108,22 @@ impl MoveStepAction {
                             ))));
                         }
                     }
-                    // Check for combat?
-                    // if hero and will make other hostile ask for
+                    // Check for captives
+                    if actor_is_hero && other_being.has_flag(BeingFlags::CAPTIVE) {
+                        return Some(ActionResult::Replace(Box::new(FreeCaptiveAction::new(
+                            self.entity,
+                            other,
+                        ))));
+                    }
+
+                    // Check for combat
+                    // TODO - if hero and will make other hostile ask for confirmation
+                    if will_attack && are_enemies(world, self.entity, other) {
+                        return Some(ActionResult::Replace(Box::new(MeleeAction::new(
+                            self.entity,
+                            other,
+                        ))));
+                    }
 
                     // Should this be a different thing?
                     if actor_is_hero {
@@ -111,6 +136,11 @@ impl MoveStepAction {
                 }
             }
 
+            // Walls do not stop some monsters
+            if pass_wall && map.iter_beings(idx).next().is_none() {
+                return None;
+            }
+
             let flavor = cell_flavor(&*map, world, idx);
             if actor_is_hero {
                 logger.log(format!("Blocked by {}", flavor));
@@ -125,6 +155,24 @@ impl MoveStepAction {
         None
     }
 
+    /// Confused beings stumble in a random direction half of the time
+    fn confuse(&mut self, world: &mut World) {
+        let confused = match world.read_component::<StatusEffects>().get(self.entity) {
+            None => false,
+            Some(status) => status.has(StatusKind::CONFUSION),
+        };
+        if !confused {
+            return;
+        }
+
+        let mut rng = world.write_resource::<RandomNumberGenerator>();
+        if rng.chance(50) {
+            let dir = *rng.random_slice_entry(&DIRS).unwrap();
+            self.dx = dir.x;
+            self.dy = dir.y;
+        }
+    }
+
     fn do_action(&mut self, world: &mut World) -> ActionResult {
         // let Level {
         //     resources, world, ..
@@ -168,6 +216,16 @@ impl MoveStepAction {
 
         // TODO - How to check for permission to enter?
         fire_cell_action(world, new_pt, "enter", Some(self.entity));
+        promote_on_step(world, new_pt);
+
+        // Leave a trail for the monsters that track by scent
+        if world.read_resource::<Hero>().entity == self.entity {
+            ensure_scent_map(world);
+            let time = world.read_resource::<Executor>().time();
+            world
+                .write_resource::<ScentMap>()
+                .lay(new_pt.x, new_pt.y, time);
+        }
 
         // if let Some(mut fov) = entry.get_component_mut::<FOV>() {
         //     fov.set_needs_update();
@@ -199,6 +257,7 @@ impl MoveStepAction {
 
 impl Action for MoveStepAction {
     fn execute(&mut self, world: &mut World) -> ActionResult {
+        self.confuse(world);
         if let Some(res) = self.validate(world) {
             return res;
         }
@@ -0,0 +1,88 @@
+use crate::action::idle::IdleAction;
+use crate::action::{Action, ActionResult};
+use crate::being::Being;
+use crate::combat::being_name;
+use crate::effect::fire_cell_action;
+use crate::log::Logger;
+use crate::map::Map;
+use gw_ecs::prelude::{Entity, World};
+use gw_util::point::Point;
+use gw_util::rng::RandomNumberGenerator;
+
+/// Chance (out of 100) that bashing a door works
+pub const BASH_DOOR_CHANCE: u32 = 50;
+
+/// Opens (fires the "open" effects) or bashes (fires "unlock" then "open") the door at the point.
+#[derive(Copy, Clone, Debug)]
+pub struct OpenDoorAction {
+    pub entity: Entity,
+    pub point: Point,
+    pub bash: bool,
+}
+
+impl OpenDoorAction {
+    pub fn new(entity: Entity, point: Point) -> OpenDoorAction {
+        OpenDoorAction {
+            entity,
+            point,
+            bash: false,
+        }
+    }
+
+    pub fn bash(entity: Entity, point: Point) -> OpenDoorAction {
+        OpenDoorAction {
+            entity,
+            point,
+            bash: true,
+        }
+    }
+}
+
+impl Action for OpenDoorAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        let act_time = match world.read_component::<Being>().get(self.entity) {
+            None => return ActionResult::Dead(self.entity),
+            Some(being) => being.act_time,
+        };
+
+        let (can_open, can_unlock) = {
+            let map = world.read_resource::<Map>();
+            match map.get_wrapped_index(self.point.x, self.point.y) {
+                None => (false, false),
+                Some(idx) => (
+                    map.has_cell_effects(idx, "open"),
+                    map.has_cell_effects(idx, "unlock"),
+                ),
+            }
+        };
+
+        if !(can_open || self.bash && can_unlock) {
+            return ActionResult::Replace(Box::new(IdleAction::new(self.entity, act_time)));
+        }
+
+        if self.bash {
+            let name = being_name(world, self.entity);
+            let success = world
+                .write_resource::<RandomNumberGenerator>()
+                .chance(BASH_DOOR_CHANCE);
+            if !success {
+                world
+                    .write_global::<Logger>()
+                    .log(format!("{} bashes the door", name));
+                return ActionResult::Done(act_time);
+            }
+            world
+                .write_global::<Logger>()
+                .log(format!("{} bashes the door open", name));
+            if can_unlock {
+                fire_cell_action(world, self.point, "unlock", Some(self.entity));
+            }
+        }
+
+        if can_open {
+            fire_cell_action(world, self.point, "open", Some(self.entity));
+        }
+
+        ActionResult::Done(act_time)
+    }
+}
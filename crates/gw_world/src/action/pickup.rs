@@ -0,0 +1,224 @@
+use crate::action::idle::IdleAction;
+use crate::action::{Action, ActionResult};
+use crate::being::Being;
+use crate::combat::being_name;
+use crate::effect::fire_cell_action;
+use crate::hero::Hero;
+use crate::item::{item_name, lift_item, Inventory};
+use crate::log::Logger;
+use crate::map::Map;
+use crate::position::Position;
+use gw_ecs::prelude::{Entity, World};
+
+/// Picks up the top item at the being's location
+#[derive(Copy, Clone, Debug)]
+pub struct PickupAction {
+    pub entity: Entity,
+}
+
+impl PickupAction {
+    pub fn new(entity: Entity) -> PickupAction {
+        PickupAction { entity }
+    }
+
+    fn validate(&mut self, world: &mut World) -> Result<Entity, ActionResult> {
+        let act_time = match world.read_component::<Being>().get(self.entity) {
+            None => return Err(ActionResult::Dead(self.entity)),
+            Some(being) => being.act_time,
+        };
+        let idle = || ActionResult::Replace(Box::new(IdleAction::new(self.entity, act_time)));
+        let is_hero = world.read_resource::<Hero>().entity == self.entity;
+
+        let item = {
+            let pos = match world.read_component::<Position>().get(self.entity) {
+                None => return Err(ActionResult::Dead(self.entity)),
+                Some(pos) => *pos,
+            };
+            let map = world.read_resource::<Map>();
+            map.get_wrapped_index(pos.x, pos.y)
+                .and_then(|idx| map.iter_items(idx).last())
+        };
+
+        let item = match item {
+            None => {
+                if is_hero {
+                    world
+                        .write_global::<Logger>()
+                        .log("There is nothing here to pick up.");
+                }
+                return Err(idle());
+            }
+            Some(item) => item,
+        };
+
+        let is_full = match world.read_component::<Inventory>().get(self.entity) {
+            None => true,
+            Some(inventory) => inventory.is_full(),
+        };
+        if is_full {
+            if is_hero {
+                world
+                    .write_global::<Logger>()
+                    .log("You cannot carry any more.");
+            }
+            return Err(idle());
+        }
+
+        Ok(item)
+    }
+
+    fn do_action(&mut self, world: &mut World, item: Entity) -> ActionResult {
+        let pos = world
+            .read_component::<Position>()
+            .get(self.entity)
+            .unwrap()
+            .point();
+
+        lift_item(world, item);
+        if let Some(mut inventory) = world.write_component::<Inventory>().get_mut(self.entity) {
+            inventory.add(item);
+        }
+
+        let msg = format!(
+            "{} picks up {}",
+            being_name(world, self.entity),
+            item_name(world, item)
+        );
+        world.write_global::<Logger>().log(msg);
+
+        fire_cell_action(world, pos, "pickup", Some(item));
+
+        let act_time = world
+            .read_component::<Being>()
+            .get(self.entity)
+            .unwrap()
+            .act_time;
+        ActionResult::Done(act_time)
+    }
+}
+
+impl Action for PickupAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        match self.validate(world) {
+            Err(res) => res,
+            Ok(item) => self.do_action(world, item),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::action::drop::DropAction;
+    use crate::action::equip::EquipAction;
+    use crate::action::use_item::UseItemAction;
+    use crate::item::{load_item_data, spawn_item, Item, ItemKinds};
+    use crate::test_util::{add_test_world, make_empty_ecs};
+    use gw_ecs::prelude::Ecs;
+    use gw_util::point::Point;
+
+    fn make_ecs() -> (Ecs, Entity) {
+        let mut ecs = make_empty_ecs();
+        let data = r#"{
+            "DAGGER": { "ch": "|", "name": "dagger", "slot": "weapon", "melee": { "damage": 2 } },
+            "POTION": { "ch": "!", "name": "potion", "use": { "heal": true }, "consumable": true }
+        }"#;
+        load_item_data(
+            &mut ecs.write_global::<ItemKinds>(),
+            gw_util::json::parse_string(data).unwrap(),
+        )
+        .unwrap();
+
+        let world = add_test_world(&mut ecs, 5, 3);
+        let mut being = Being::new("HERO".to_string());
+        being.name = Some("hero".to_string());
+        let hero = world.spawn((being, Position::new(1, 1), Inventory::new(2)));
+        world.insert_resource(Hero::new(hero));
+        (ecs, hero)
+    }
+
+    fn spawn(world: &mut World, id: &str) -> Entity {
+        let kind = world.read_global::<ItemKinds>().get(id).unwrap();
+        spawn_item(&kind, world, Point::new(1, 1))
+    }
+
+    #[test]
+    fn pickup_equip_drop() {
+        let (mut ecs, hero) = make_ecs();
+        let world = ecs.current_world_mut();
+        let dagger = spawn(world, "DAGGER");
+        let idx = world.read_resource::<Map>().get_index(1, 1).unwrap();
+        assert_eq!(world.read_resource::<Map>().iter_items(idx).count(), 1);
+
+        let mut action = PickupAction::new(hero);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert_eq!(world.read_resource::<Map>().iter_items(idx).count(), 0);
+        assert!(!world.read_component::<Position>().contains(dagger));
+        assert!(world
+            .read_component::<Inventory>()
+            .get(hero)
+            .unwrap()
+            .contains(dagger));
+
+        let mut action = EquipAction::new(hero, dagger);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert!(world
+            .read_component::<Inventory>()
+            .get(hero)
+            .unwrap()
+            .is_equipped(dagger));
+
+        let mut action = DropAction::new(hero, dagger);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert_eq!(
+            world
+                .read_resource::<Map>()
+                .iter_items(idx)
+                .collect::<Vec<_>>(),
+            vec![dagger]
+        );
+        assert!(world
+            .read_component::<Inventory>()
+            .get(hero)
+            .unwrap()
+            .is_empty());
+
+        let mut logger = world.write_global::<Logger>();
+        let msgs: Vec<String> = logger.iter().map(|m| m.msg.clone()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                "hero drops dagger",
+                "hero equips dagger",
+                "hero picks up dagger"
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_to_pickup() {
+        let (mut ecs, hero) = make_ecs();
+        let world = ecs.current_world_mut();
+
+        let mut action = PickupAction::new(hero);
+        assert!(matches!(action.execute(world), ActionResult::Replace(_)));
+    }
+
+    #[test]
+    fn use_consumable() {
+        let (mut ecs, hero) = make_ecs();
+        let world = ecs.current_world_mut();
+        let potion = spawn(world, "POTION");
+
+        PickupAction::new(hero).execute(world);
+        let mut action = UseItemAction::new(hero, potion);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert!(!world.entities().is_alive(potion));
+        assert!(world
+            .read_component::<Inventory>()
+            .get(hero)
+            .unwrap()
+            .is_empty());
+        assert!(!world.read_component::<Item>().contains(potion));
+    }
+}
@@ -0,0 +1,266 @@
+use crate::action::dead::kill_if_dead;
+use crate::action::idle::IdleAction;
+use crate::action::{Action, ActionResult};
+use crate::animation::{Animations, Bolt};
+use crate::being::{AIFlags, Being};
+use crate::combat::{find_ammo, hit_chance, trace_projectile, Ranged};
+use crate::hero::Hero;
+use crate::horde::set_leader_target;
+use crate::item::Inventory;
+use crate::log::Logger;
+use crate::map::Map;
+use crate::position::Position;
+use gw_ecs::prelude::{Entity, World};
+use gw_util::point::Point;
+use gw_util::rng::RandomNumberGenerator;
+
+/// Fires the entity's ranged attack toward the target point
+#[derive(Copy, Clone, Debug)]
+pub struct RangedAction {
+    pub entity: Entity,
+    pub target: Point,
+}
+
+impl RangedAction {
+    pub fn new(entity: Entity, target: Point) -> RangedAction {
+        RangedAction { entity, target }
+    }
+
+    fn validate(&mut self, world: &mut World) -> Option<ActionResult> {
+        let act_time = match world.read_component::<Being>().get(self.entity) {
+            None => return Some(ActionResult::Dead(self.entity)),
+            Some(being) => being.act_time,
+        };
+
+        let ammo = match world.read_component::<Ranged>().get(self.entity) {
+            None => {
+                return Some(ActionResult::Replace(Box::new(IdleAction::new(
+                    self.entity,
+                    act_time,
+                ))))
+            }
+            Some(ranged) => ranged.ammo.clone(),
+        };
+
+        if let Some(ammo) = ammo {
+            if find_ammo(world, self.entity, &ammo).is_none() {
+                if world.read_resource::<Hero>().entity == self.entity {
+                    world
+                        .write_global::<Logger>()
+                        .log("You have nothing to fire.");
+                    return Some(ActionResult::Fail("No ammo".to_string()));
+                }
+                return Some(ActionResult::Replace(Box::new(IdleAction::new(
+                    self.entity,
+                    act_time,
+                ))));
+            }
+        }
+
+        None
+    }
+
+    fn use_ammo(&self, world: &mut World, ammo: &str) {
+        let item = match find_ammo(world, self.entity, ammo) {
+            None => return,
+            Some(item) => item,
+        };
+        if let Some(mut inventory) = world.write_component::<Inventory>().get_mut(self.entity) {
+            inventory.remove(item);
+        }
+        world.delete_entity(item);
+    }
+
+    fn do_action(&mut self, world: &mut World) -> ActionResult {
+        let act_time = world
+            .read_component::<Being>()
+            .get(self.entity)
+            .unwrap()
+            .act_time;
+
+        let ranged = world
+            .read_component::<Ranged>()
+            .get(self.entity)
+            .unwrap()
+            .clone();
+
+        let origin = match world.read_component::<Position>().get(self.entity) {
+            None => return ActionResult::Dead(self.entity),
+            Some(pos) => pos.point(),
+        };
+
+        let trace = {
+            let map = world.read_resource::<Map>();
+            trace_projectile(&map, origin, self.target, ranged.range)
+        };
+
+        if let Some(ref ammo) = ranged.ammo {
+            self.use_ammo(world, ammo);
+        }
+
+        if let Some(mut animations) = world.try_write_resource::<Animations>() {
+            animations.push_bolt(Bolt::new(trace.path.clone(), ranged.glyph, ranged.fg));
+        }
+
+        let attack = &ranged.attack;
+        let target = match trace.target {
+            None => {
+                for effect in attack.effects().iter() {
+                    effect.fire(world, trace.impact, None);
+                }
+                return ActionResult::Done(act_time);
+            }
+            Some(target) => target,
+        };
+        set_leader_target(world, self.entity, target);
+
+        let never_miss = world
+            .read_component::<Being>()
+            .get(self.entity)
+            .map(|b| b.ai_flags.contains(AIFlags::NEVER_MISS))
+            .unwrap_or(false);
+
+        let hit = never_miss
+            || world
+                .write_resource::<RandomNumberGenerator>()
+                .chance(hit_chance(world, self.entity, target, attack.chance()));
+
+        if !hit {
+            attack.miss_msg().log(world, self.entity, target);
+            return ActionResult::Done(act_time);
+        }
+
+        attack.hit_msg().log(world, self.entity, target);
+
+        for effect in attack.effects().iter() {
+            effect.fire(world, trace.impact, Some(target));
+            if !world.entities().is_alive(target) {
+                break;
+            }
+        }
+
+        kill_if_dead(world, target, Some(self.entity));
+
+        ActionResult::Done(act_time)
+    }
+}
+
+impl Action for RangedAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        if let Some(res) = self.validate(world) {
+            return res;
+        }
+
+        self.do_action(world)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::{Stat, Stats};
+    use crate::combat::parse_ranged;
+    use crate::item::Item;
+    use crate::test_util;
+    use crate::tile::Tiles;
+    use gw_ecs::prelude::Ecs;
+    use gw_util::value::Value;
+    use std::collections::HashMap;
+
+    fn make_ecs() -> Ecs {
+        let ecs = test_util::make_ecs(10, 3);
+        {
+            let world = ecs.current_world();
+            let wall = world.read_global::<Tiles>().get("WALL").unwrap();
+            world.write_resource::<Map>().reset_tiles(8 + 10, wall);
+        }
+        ecs
+    }
+
+    fn make_being(world: &mut World, name: &str, x: i32) -> Entity {
+        let mut being = Being::new(name.to_string());
+        being.name = Some(name.to_string());
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 8);
+
+        world.spawn((being, Position::new(x, 1), stats))
+    }
+
+    fn make_archer(world: &mut World, x: i32, arrows: usize) -> Entity {
+        let entity = make_being(world, "hero", x);
+
+        let mut data = HashMap::new();
+        data.insert("damage".into(), Value::from(5));
+        data.insert("ammo".into(), Value::from("ARROW"));
+        let ranged = parse_ranged(&Value::from(data)).ok().unwrap();
+
+        let mut inventory = Inventory::default();
+        for _ in 0..arrows {
+            let arrow = world.spawn((Item::new("ARROW".to_string()),));
+            inventory.add(arrow);
+        }
+        let _ = world.write_component::<Ranged>().insert(entity, ranged);
+        let _ = world
+            .write_component::<Inventory>()
+            .insert(entity, inventory);
+        entity
+    }
+
+    fn health(world: &World, entity: Entity) -> Option<i32> {
+        world
+            .read_component::<Stats>()
+            .get(entity)
+            .unwrap()
+            .get(Stat::HEALTH)
+    }
+
+    #[test]
+    fn shoot_and_use_ammo() {
+        let mut ecs = make_ecs();
+        let world = ecs.current_world_mut();
+
+        let hero = make_archer(world, 1, 1);
+        world.insert_resource(Hero::new(hero));
+        let rat = make_being(world, "rat", 4);
+
+        let mut action = RangedAction::new(hero, Point::new(6, 1));
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert_eq!(health(world, rat), Some(3));
+        assert!(world
+            .read_component::<Inventory>()
+            .get(hero)
+            .unwrap()
+            .is_empty());
+        assert_eq!(world.read_resource::<Animations>().len(), 1);
+
+        let msgs: Vec<String> = world
+            .write_global::<Logger>()
+            .iter()
+            .map(|m| m.msg.clone())
+            .collect();
+        assert_eq!(msgs[0], "hero hits rat #[red][5]");
+
+        // Out of arrows
+        let mut action = RangedAction::new(hero, Point::new(6, 1));
+        assert!(matches!(action.execute(world), ActionResult::Fail(_)));
+        assert_eq!(health(world, rat), Some(3));
+    }
+
+    #[test]
+    fn walls_stop_missiles() {
+        let mut ecs = make_ecs();
+        let world = ecs.current_world_mut();
+
+        let hero = make_archer(world, 1, 2);
+        world.insert_resource(Hero::new(hero));
+        let rat = make_being(world, "rat", 9);
+
+        let mut action = RangedAction::new(hero, Point::new(9, 1));
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+        assert_eq!(health(world, rat), Some(8));
+        assert_eq!(
+            world.read_component::<Inventory>().get(hero).unwrap().len(),
+            1
+        );
+    }
+}
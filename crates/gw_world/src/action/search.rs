@@ -0,0 +1,235 @@
+use crate::action::{Action, ActionResult};
+use crate::combat::being_name;
+use crate::fov::has_line_of_sight;
+use crate::log::Logger;
+use crate::map::{Cell, Map};
+use crate::position::Position;
+use crate::tile::{Tile, TileFlags, TileLayer, Tiles};
+use gw_app::log;
+use gw_ecs::prelude::{Entity, World};
+use gw_util::point::Point;
+use gw_util::rng::RandomNumberGenerator;
+use std::sync::Arc;
+
+/// How far the search action reaches and the chance (out of 100) to find an adjacent secret
+pub const SEARCH_RADIUS: i32 = 5;
+pub const SEARCH_STRENGTH: u32 = 100;
+
+/// The hero also searches a little every turn
+pub const PASSIVE_SEARCH_RADIUS: i32 = 2;
+pub const PASSIVE_SEARCH_STRENGTH: u32 = 20;
+
+/// How much the chance to find a secret drops with each step of distance
+const SEARCH_FALLOFF: u32 = 15;
+
+#[derive(Copy, Clone, Debug)]
+pub struct SearchAction {
+    pub entity: Entity,
+    pub time: u32,
+}
+
+impl SearchAction {
+    pub fn new(entity: Entity, time: u32) -> SearchAction {
+        SearchAction { entity, time }
+    }
+}
+
+impl Action for SearchAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        if !world.entities().is_alive(self.entity) {
+            return ActionResult::Dead(self.entity);
+        }
+
+        if search(world, self.entity, SEARCH_RADIUS, SEARCH_STRENGTH) == 0 {
+            let msg = format!("{} finds nothing", being_name(world, self.entity));
+            world.write_global::<Logger>().log(msg);
+        }
+        ActionResult::Done(self.time)
+    }
+}
+
+/// The secret tile in the cell (if any) - it must know what it becomes when discovered
+fn secret_tile(map: &Map, idx: usize) -> Option<Arc<Tile>> {
+    let cell = map.get_cell(idx)?;
+    let tile = [cell.fixture(), cell.ground()]
+        .into_iter()
+        .find(|tile| tile.flags.contains(TileFlags::SECRET) && tile.discover.is_some())
+        .cloned();
+    tile
+}
+
+/// Is there an undiscovered secret in the cell?
+pub fn is_secret(map: &Map, idx: usize) -> bool {
+    secret_tile(map, idx).is_some()
+}
+
+/// Replaces the secret tile (that looks like its mimic) with the real one
+pub fn discover_secret(world: &mut World, idx: usize, entity: Option<Entity>) -> bool {
+    let secret = match secret_tile(&world.read_resource::<Map>(), idx) {
+        None => return false,
+        Some(tile) => tile,
+    };
+    let id = secret.discover.as_ref().unwrap();
+    let real = match world.read_global::<Tiles>().get(id) {
+        None => {
+            log(format!("Unknown discover tile - {} => {}", secret.id, id));
+            return false;
+        }
+        Some(tile) => tile,
+    };
+
+    {
+        let mut map = world.write_resource::<Map>();
+        if secret.layer == TileLayer::FIXTURE && real.layer != TileLayer::FIXTURE {
+            map.clear_fixture(idx);
+        }
+        map.force_tile(idx, real.clone());
+    }
+
+    let what = match real.flavor.is_empty() {
+        true => "a secret".to_string(),
+        false => real.flavor.clone(),
+    };
+    let msg = match entity {
+        None => format!("You discover {}", what),
+        Some(entity) => format!("{} discovers {}", being_name(world, entity), what),
+    };
+    world.write_global::<Logger>().log(msg);
+    true
+}
+
+/// Rolls to discover each secret within the radius that the entity can see.
+/// The chance is the strength next to the entity and drops with distance.  Returns the number found.
+pub fn search(world: &mut World, entity: Entity, radius: i32, strength: u32) -> u32 {
+    let origin = match world.read_component::<Position>().get(entity) {
+        None => return 0,
+        Some(pos) => pos.point(),
+    };
+
+    let mut candidates = Vec::new();
+    {
+        let map = world.read_resource::<Map>();
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if let Some(idx) = map.get_wrapped_index(origin.x + dx, origin.y + dy) {
+                    if is_secret(&map, idx) {
+                        let distance = dx.abs().max(dy.abs()) as u32;
+                        candidates.push((idx, Point::new(origin.x + dx, origin.y + dy), distance));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut found = 0;
+    for (idx, point, distance) in candidates {
+        if !has_line_of_sight(world, origin, point) {
+            continue;
+        }
+        let chance = strength.saturating_sub(distance.saturating_sub(1) * SEARCH_FALLOFF);
+        if !world
+            .write_resource::<RandomNumberGenerator>()
+            .chance(chance)
+        {
+            continue;
+        }
+        if discover_secret(world, idx, Some(entity)) {
+            found += 1;
+        }
+    }
+    found
+}
+
+/// The search that the hero does every turn
+pub fn passive_search(world: &mut World, entity: Entity) -> u32 {
+    search(
+        world,
+        entity,
+        PASSIVE_SEARCH_RADIUS,
+        PASSIVE_SEARCH_STRENGTH,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{add_test_world, make_empty_ecs};
+    use crate::tile::TileBuilder;
+    use gw_ecs::prelude::Ecs;
+
+    fn make_ecs() -> (Ecs, Entity) {
+        let mut ecs = make_empty_ecs();
+        {
+            let mut tiles = ecs.write_global::<Tiles>();
+            let wall = tiles.get("WALL").unwrap();
+            tiles.insert(TileBuilder::new("DOOR").flavor("a door").build());
+            tiles.insert(
+                TileBuilder::new("SECRET_DOOR")
+                    .blocks()
+                    .flags(TileFlags::SECRET)
+                    .mimic(wall)
+                    .discover("DOOR")
+                    .build(),
+            );
+        }
+
+        let world = add_test_world(&mut ecs, 9, 3);
+        let secret = world.read_global::<Tiles>().get("SECRET_DOOR").unwrap();
+        {
+            let mut map = world.write_resource::<Map>();
+            let idx = map.get_index(3, 1).unwrap();
+            map.force_tile(idx, secret);
+        }
+
+        let entity = world.spawn((Position::new(2, 1),));
+        (ecs, entity)
+    }
+
+    #[test]
+    fn looks_like_mimic() {
+        let (ecs, _) = make_ecs();
+        let world = ecs.current_world();
+        let map = world.read_resource::<Map>();
+        let wall = world.read_global::<Tiles>().get("WALL").unwrap();
+
+        let idx = map.get_index(3, 1).unwrap();
+        assert!(is_secret(&map, idx));
+        let sprite = map.get_cell(idx).unwrap().sprite();
+        assert_eq!(sprite.glyph, wall.glyph);
+        assert_eq!(sprite.bg, wall.bg);
+    }
+
+    #[test]
+    fn search_finds_adjacent_secret() {
+        let (mut ecs, entity) = make_ecs();
+        let world = ecs.current_world_mut();
+
+        let mut action = SearchAction::new(entity, 100);
+        assert!(matches!(action.execute(world), ActionResult::Done(100)));
+
+        let map = world.read_resource::<Map>();
+        let idx = map.get_index(3, 1).unwrap();
+        assert!(!is_secret(&map, idx));
+        assert_eq!(map.get_cell(idx).unwrap().ground().id, "DOOR");
+        assert!(map.needs_snapshot(idx));
+
+        let mut logger = world.write_global::<Logger>();
+        let msgs: Vec<String> = logger.iter().map(|m| m.msg.clone()).collect();
+        assert!(msgs.contains(&"something discovers a door".to_string()));
+    }
+
+    #[test]
+    fn no_search_out_of_range() {
+        let (mut ecs, entity) = make_ecs();
+        let world = ecs.current_world_mut();
+        world
+            .write_component::<Position>()
+            .get_mut(entity)
+            .unwrap()
+            .set(7, 1);
+
+        assert_eq!(passive_search(world, entity), 0);
+        let map = world.read_resource::<Map>();
+        assert!(is_secret(&map, map.get_index(3, 1).unwrap()));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::action::idle::IdleAction;
+use crate::action::{Action, ActionResult};
+use crate::being::Being;
+use crate::combat::being_name;
+use crate::item::{item_name, Inventory, Item, ItemKinds};
+use crate::log::Logger;
+use crate::position::Position;
+use gw_ecs::prelude::{Entity, World};
+
+/// Uses (quaffs, reads, ...) an item in the being's inventory - firing the kind's "use" effects
+#[derive(Copy, Clone, Debug)]
+pub struct UseItemAction {
+    pub entity: Entity,
+    pub item: Entity,
+}
+
+impl UseItemAction {
+    pub fn new(entity: Entity, item: Entity) -> UseItemAction {
+        UseItemAction { entity, item }
+    }
+
+    fn validate(&mut self, world: &mut World) -> Option<ActionResult> {
+        let act_time = match world.read_component::<Being>().get(self.entity) {
+            None => return Some(ActionResult::Dead(self.entity)),
+            Some(being) => being.act_time,
+        };
+
+        let has_item = match world.read_component::<Inventory>().get(self.entity) {
+            None => false,
+            Some(inventory) => inventory.contains(self.item),
+        };
+
+        if !has_item || !world.read_component::<Position>().contains(self.entity) {
+            return Some(ActionResult::Replace(Box::new(IdleAction::new(
+                self.entity,
+                act_time,
+            ))));
+        }
+
+        None
+    }
+
+    fn do_action(&mut self, world: &mut World) -> ActionResult {
+        let pos = world
+            .read_component::<Position>()
+            .get(self.entity)
+            .unwrap()
+            .point();
+
+        let (item_id, consumable) = match world.read_component::<Item>().get(self.item) {
+            None => (String::new(), false),
+            Some(item) => (item.id.clone(), item.consumable),
+        };
+
+        let msg = format!(
+            "{} uses {}",
+            being_name(world, self.entity),
+            item_name(world, self.item)
+        );
+        world.write_global::<Logger>().log(msg);
+
+        let effects = world
+            .try_read_global::<ItemKinds>()
+            .and_then(|kinds| kinds.get(&item_id))
+            .map(|kind| kind.use_effects.clone())
+            .unwrap_or_default();
+
+        if effects.is_empty() {
+            world.write_global::<Logger>().log("Nothing happens.");
+        }
+        for effect in effects.iter() {
+            effect.fire(world, pos, Some(self.entity));
+        }
+
+        if consumable {
+            if let Some(mut inventory) = world.write_component::<Inventory>().get_mut(self.entity) {
+                inventory.remove(self.item);
+            }
+            world.delete_entity(self.item);
+        }
+
+        let act_time = world
+            .read_component::<Being>()
+            .get(self.entity)
+            .map(|b| b.act_time)
+            .unwrap_or(100);
+        ActionResult::Done(act_time)
+    }
+}
+
+impl Action for UseItemAction {
+    fn execute(&mut self, world: &mut World) -> ActionResult {
+        if let Some(res) = self.validate(world) {
+            return res;
+        }
+
+        self.do_action(world)
+    }
+}
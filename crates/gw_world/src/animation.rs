@@ -0,0 +1,135 @@
+use gw_app::{color::named, Glyph, RGBA};
+use gw_ecs::prelude::World;
+use gw_util::point::Point;
+
+/// How many frames a bolt stays on each cell of its path
+pub const BOLT_FRAMES_PER_STEP: u32 = 2;
+
+/// A glyph that travels along a path (e.g. an arrow) - drawn by the Viewport
+#[derive(Debug, Clone)]
+pub struct Bolt {
+    path: Vec<Point>,
+    pub glyph: Glyph,
+    pub fg: RGBA,
+    pub frames_per_step: u32,
+    frame: u32,
+}
+
+impl Bolt {
+    pub fn new(path: Vec<Point>, glyph: Glyph, fg: RGBA) -> Self {
+        Bolt {
+            path,
+            glyph,
+            fg,
+            frames_per_step: BOLT_FRAMES_PER_STEP,
+            frame: 0,
+        }
+    }
+
+    /// Where the bolt is this frame
+    pub fn point(&self) -> Option<Point> {
+        let step = (self.frame / self.frames_per_step.max(1)) as usize;
+        self.path.get(step).copied()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.point().is_none()
+    }
+
+    /// Moves to the next frame, returns the point the bolt left (if any)
+    pub fn tick(&mut self) -> Option<Point> {
+        let old = self.point();
+        self.frame += 1;
+        match old == self.point() {
+            true => None,
+            false => old,
+        }
+    }
+}
+
+impl Default for Bolt {
+    fn default() -> Self {
+        Bolt::new(Vec::new(), '*' as Glyph, named::YELLOW.into())
+    }
+}
+
+/// The animations that are playing.  The executor waits for these to finish before running more tasks.
+#[derive(Debug, Default)]
+pub struct Animations {
+    bolts: Vec<Bolt>,
+}
+
+impl Animations {
+    pub fn new() -> Self {
+        Animations::default()
+    }
+
+    pub fn push_bolt(&mut self, bolt: Bolt) {
+        if !bolt.is_done() {
+            self.bolts.push(bolt);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bolts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bolts.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Bolt> {
+        self.bolts.iter()
+    }
+
+    /// Advances all of the animations one frame, returns the points that need to be redrawn
+    pub fn tick(&mut self) -> Vec<Point> {
+        let mut dirty = Vec::new();
+        for bolt in self.bolts.iter_mut() {
+            if let Some(pt) = bolt.tick() {
+                dirty.push(pt);
+            }
+        }
+        self.bolts.retain(|b| !b.is_done());
+        dirty
+    }
+
+    pub fn clear(&mut self) {
+        self.bolts.clear();
+    }
+}
+
+/// Are there animations that need to finish before the game continues?
+pub fn is_animating(world: &World) -> bool {
+    match world.try_read_resource::<Animations>() {
+        None => false,
+        Some(animations) => !animations.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bolt_travels() {
+        let path = vec![Point::new(1, 1), Point::new(2, 1), Point::new(3, 1)];
+        let mut animations = Animations::new();
+        animations.push_bolt(Bolt::new(path, '*' as Glyph, named::RED.into()));
+        animations.push_bolt(Bolt::default());
+        assert_eq!(animations.len(), 1);
+
+        let mut points = Vec::new();
+        let mut dirty = Vec::new();
+        while !animations.is_empty() {
+            points.push(animations.iter().next().unwrap().point().unwrap());
+            dirty.extend(animations.tick());
+        }
+
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[0], Point::new(1, 1));
+        assert_eq!(points[5], Point::new(3, 1));
+        assert_eq!(dirty.len(), 3);
+        assert!(animations.is_empty());
+    }
+}
@@ -2,7 +2,9 @@ use super::AIFlags;
 use super::BeingFlags;
 use super::BeingKindFlags;
 use super::MoveFlags;
-use gw_ecs::prelude::Component;
+use crate::position::{add_to_map, remove_from_map, Position};
+use gw_ecs::components::ComponentHooks;
+use gw_ecs::prelude::{Component, World};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default, Component)]
@@ -16,12 +18,40 @@ pub struct Being {
     pub act_time: u32,
 
     pub xp: u32,
+    #[serde(default = "default_level")]
+    pub level: u32,
     pub name: Option<String>,
     pub talk: Option<String>,
     pub flavor: Option<String>,
     pub description: Option<String>,
 }
 
+/// Adds the being to the `Map` if it already has a `Position` (and takes it out when the `Being` is removed)
+/// The other half of `position_hooks`.
+pub fn being_hooks() -> ComponentHooks<Being> {
+    ComponentHooks::<Being>::new()
+        .on_add(|commands, entity, _| {
+            commands.add(move |world: &mut World| {
+                let pos = world.read_component::<Position>().get(entity).map(|p| *p);
+                if let Some(pos) = pos {
+                    add_to_map(world, entity, pos);
+                }
+            });
+        })
+        .on_remove(|commands, entity, _| {
+            commands.add(move |world: &mut World| {
+                let pos = world.read_component::<Position>().get(entity).map(|p| *p);
+                if let Some(pos) = pos {
+                    remove_from_map(world, entity, pos);
+                }
+            });
+        })
+}
+
+fn default_level() -> u32 {
+    1
+}
+
 impl Being {
     pub fn new(id: String) -> Self {
         Being {
@@ -35,6 +65,7 @@ impl Being {
             kind_flags: BeingKindFlags::empty(),
 
             xp: 0,
+            level: 1,
             name: None,
             talk: None,
             flavor: None,
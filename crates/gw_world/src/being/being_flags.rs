@@ -45,6 +45,9 @@ bitflags! {
         const FORMAL_NAME = fl!(20); // "Henry" instead of "the Goblin"
         const ALWAYS_PLURAL = fl!(21); // So that nouns and verbs are tensed correctly (mostly for player)
 
+        const CAPTIVE = fl!(22); // in chains - can be freed by the hero
+        const ALLY = fl!(23); // fights on the side of the hero
+
         const DEFAULT_ACTOR = 0;
         const DEFAULT_ITEM = 0;
 
@@ -112,6 +115,9 @@ impl FromStr for BeingFlags {
                 "FORMAL_NAME" => result |= BeingFlags::FORMAL_NAME,
                 "ALWAYS_PLURAL" => result |= BeingFlags::ALWAYS_PLURAL,
 
+                "CAPTIVE" => result |= BeingFlags::CAPTIVE,
+                "ALLY" => result |= BeingFlags::ALLY,
+
                 "DEFAULT_ACTOR" => result |= BeingFlags::DEFAULT_ACTOR,
                 "DEFAULT_ITEM" => result |= BeingFlags::DEFAULT_ITEM,
 
@@ -1,6 +1,8 @@
 use super::{Being, BeingKind, BeingKindFlags, BeingKinds, Stat, Stats};
 use crate::{
-    combat::{parse_melee, Melee},
+    combat::{parse_melee, parse_ranged, Melee, Ranged},
+    fov::{parse_vision, Vision},
+    light::{parse_light, Light},
     sprite::{Sprite, SpriteParseError},
 };
 use gw_app::{log, Glyph, RGBA};
@@ -13,7 +15,11 @@ pub struct BeingKindBuilder {
     pub(super) being: Being,
     pub(super) task: String,
     pub(super) melee: Option<Melee>,
+    pub(super) ranged: Option<Ranged>,
     pub(super) stats: Stats,
+    pub(super) vision: Option<Vision>,
+    pub(super) light: Option<Light>,
+    pub(super) components: Vec<(String, Value)>,
 }
 
 impl BeingKindBuilder {
@@ -24,7 +30,11 @@ impl BeingKindBuilder {
             being: Being::new(id.to_string()),
             task: "IDLE".to_string(),
             melee: None,
+            ranged: None,
             stats: Stats::new(),
+            vision: None,
+            light: None,
+            components: Vec::new(),
         }
     }
 
@@ -34,7 +44,11 @@ impl BeingKindBuilder {
         self.being = kind.being.clone();
         self.task = kind.task.clone();
         self.melee = kind.melee.clone();
+        self.ranged = kind.ranged.clone();
         self.stats = kind.stats.clone();
+        self.vision = kind.vision.clone();
+        self.light = kind.light;
+        self.components = kind.components.clone();
         self
     }
 
@@ -115,6 +129,34 @@ impl BeingKindBuilder {
         self
     }
 
+    pub fn ranged(&mut self, ranged: Ranged) -> &mut Self {
+        self.ranged = Some(ranged);
+        self
+    }
+
+    pub fn vision(&mut self, vision: Vision) -> &mut Self {
+        self.vision = Some(vision);
+        self
+    }
+
+    pub fn light(&mut self, light: Light) -> &mut Self {
+        self.light = Some(light);
+        self
+    }
+
+    pub fn stat(&mut self, stat: Stat, value: i32) -> &mut Self {
+        self.stats.set(stat, value);
+        self
+    }
+
+    /// A component that is built from the value (using the `TypeRegistry<Value>`) when the being is spawned
+    pub fn component(&mut self, id: &str, value: Value) -> &mut Self {
+        let id = id.to_uppercase();
+        self.components.retain(|(other, _)| *other != id);
+        self.components.push((id, value));
+        self
+    }
+
     pub fn build(self) -> Arc<BeingKind> {
         Arc::new(BeingKind::new(self))
     }
@@ -130,7 +172,9 @@ impl BeingKindBuilder {
        "bg": "<RGBA_CONFIG>",
 
        "flavor": <STRING>,
-       "description": <STRING>
+       "description": <STRING>,
+
+       "components": { <ID>: <VALUE> } // any registered component - e.g. "LEADER": {}
    }
 */
 
@@ -221,7 +265,27 @@ pub fn set_field(
                 Ok(())
             }
         },
-        "ranged" => Ok(()),
+        "ranged" => match parse_ranged(value) {
+            Err(_) => Err(BuilderError::BadField("ranged".to_string(), value.clone())),
+            Ok(ranged) => {
+                builder.ranged(ranged);
+                Ok(())
+            }
+        },
+        "vision" => match parse_vision(value) {
+            Err(_) => Err(BuilderError::BadField("vision".to_string(), value.clone())),
+            Ok(vision) => {
+                builder.vision(vision);
+                Ok(())
+            }
+        },
+        "light" => match parse_light(value) {
+            Err(_) => Err(BuilderError::BadField("light".to_string(), value.clone())),
+            Ok(light) => {
+                builder.light(light);
+                Ok(())
+            }
+        },
         "melee" => {
             if value.is_bool() {
                 if value.as_bool().unwrap() == false {
@@ -256,6 +320,45 @@ pub fn set_field(
                 Err(BuilderError::BadField("mp".to_string(), value.clone()))
             }
         }
+        "strength" | "defense" | "accuracy" | "speed" => match value.as_int() {
+            None => Err(BuilderError::BadField(field.to_string(), value.clone())),
+            Some(v) => {
+                builder.stat(field.parse().unwrap(), v as i32);
+                Ok(())
+            }
+        },
+        "stats" => match value.as_map() {
+            None => Err(BuilderError::BadField("stats".to_string(), value.clone())),
+            Some(map) => {
+                for (name, v) in map.iter() {
+                    let stat: Stat = match name.to_string().parse() {
+                        Err(_) => {
+                            return Err(BuilderError::BadField("stats".to_string(), value.clone()))
+                        }
+                        Ok(stat) => stat,
+                    };
+                    match v.as_int() {
+                        None => {
+                            return Err(BuilderError::BadField("stats".to_string(), value.clone()))
+                        }
+                        Some(v) => builder.stat(stat, v as i32),
+                    };
+                }
+                Ok(())
+            }
+        },
+        "components" => match value.as_map() {
+            None => Err(BuilderError::BadField(
+                "components".to_string(),
+                value.clone(),
+            )),
+            Some(map) => {
+                for (id, v) in map.iter() {
+                    builder.component(&id.to_string(), v.clone());
+                }
+                Ok(())
+            }
+        },
         _ => Err(BuilderError::UnknownField(field.to_string())),
     }
 }
@@ -1,15 +1,22 @@
 use std::sync::Arc;
 
 use super::{Being, BeingKindBuilder, BeingKindFlags, Stats};
+use crate::fov::Vision;
 use crate::hero::Hero;
+use crate::light::Light;
 use crate::map::Map;
 use crate::position::Position;
 use crate::sprite::Sprite;
 use crate::task::Task;
-use crate::{combat::Melee, task::Executor};
-use gw_app::{ecs::Entity, log};
-use gw_ecs::prelude::{Builder, Commands, Entities, ResMut, ResRef, World};
+use crate::{
+    combat::{Melee, Ranged},
+    task::Executor,
+};
+use gw_app::log;
+use gw_ecs::prelude::{Entity, World};
+use gw_ecs::registry::TypeRegistry;
 use gw_util::point::Point;
+use gw_util::value::Value;
 
 #[derive(Debug, Clone)]
 pub struct BeingKind {
@@ -18,7 +25,12 @@ pub struct BeingKind {
     pub being: Being,
     pub task: String,
     pub melee: Option<Melee>,
+    pub ranged: Option<Ranged>,
     pub stats: Stats,
+    pub vision: Option<Vision>,
+    pub light: Option<Light>,
+    /// The registered components - by id - added when the being is spawned
+    pub components: Vec<(String, Value)>,
 }
 
 impl BeingKind {
@@ -33,52 +45,107 @@ impl BeingKind {
             being: builder.being,
             task: builder.task,
             melee: builder.melee,
+            ranged: builder.ranged,
             stats: builder.stats,
+            vision: builder.vision,
+            light: builder.light,
+            components: builder.components,
         }
     }
 }
 
-// pub fn spawn_being(kind: &Arc<BeingKind>, world: &World, point: Point) -> Entity {
-//     let (mut map, mut lazy_update, entities, mut executor) =
-//         world.fetch::<(ResMut<Map>, Commands, Entities, ResMut<Executor>)>();
+/// Creates a being of the given kind at the point - adding it to the map and the schedule
+pub fn spawn_being(kind: &Arc<BeingKind>, world: &mut World, point: Point) -> Entity {
+    if world
+        .read_resource::<Map>()
+        .get_index(point.x, point.y)
+        .is_none()
+    {
+        panic!(
+            "Trying to add being to position that does not exist! kind={}, pos={},{}",
+            kind.id, point.x, point.y
+        );
+    }
 
-//     let index = map.get_index(point.x, point.y);
+    let pos = Position::new(point.x, point.y).with_blocking(true);
+    let entity = world.spawn((
+        kind.being.clone(),
+        pos,
+        kind.sprite.clone(),
+        Task::new(kind.task.clone()),
+        kind.stats.clone(),
+    ));
 
-//     if let Some(idx) = index {
-//         let pos = Position::from(point).with_blocking(true);
+    if let Some(ref melee) = kind.melee {
+        let _ = world
+            .write_component::<Melee>()
+            .insert(entity, melee.clone());
+    }
+    if let Some(ref ranged) = kind.ranged {
+        let _ = world
+            .write_component::<Ranged>()
+            .insert(entity, ranged.clone());
+    }
+    if let Some(ref vision) = kind.vision {
+        let _ = world
+            .write_component::<Vision>()
+            .insert(entity, vision.clone());
+    }
+    if let Some(light) = kind.light {
+        let _ = world.write_component::<Light>().insert(entity, light);
+    }
 
-//         println!("spawn being({}) - task={}", kind.id, kind.task);
+    if !kind.components.is_empty() {
+        // A (cheap) copy so that the world can be changed while using it
+        let registry = match world.try_read_global::<TypeRegistry<Value>>() {
+            None => None,
+            Some(registry) => Some(TypeRegistry::<Value>::clone(&registry)),
+        };
+        let comps = kind.components.iter().map(|(id, v)| (id.as_str(), v));
+        let result = match registry {
+            None => Err("No type registry".to_string()),
+            Some(registry) => registry.insert_components(world, entity, comps),
+        };
+        if let Err(e) = result {
+            log(format!(
+                "Failed to add being components - {} : {}",
+                kind.id, e
+            ));
+        }
+    }
 
-//         let mut builder = lazy_update
-//             .create_entity(&entities)
-//             .with(kind.being.clone())
-//             .with(pos)
-//             .with(kind.sprite.clone())
-//             .with(Task::new(kind.task.clone()))
-//             .with(kind.stats.clone());
+    if kind.being.kind_flags.contains(BeingKindFlags::HERO) {
+        world.write_resource::<Hero>().entity = entity;
+    }
 
-//         if let Some(ref melee) = kind.melee {
-//             builder = builder.with(melee.clone());
-//             log(format!("SPAWN MELEE!!!"));
-//         }
-//         let entity = builder.build();
+    // Add to schedule
+    world
+        .write_resource::<Executor>()
+        .insert(entity, kind.being.act_time as u64);
 
-//         if kind.being.kind_flags.contains(BeingKindFlags::HERO) {
-//             let mut hero = world.write_resource::<Hero>();
-//             hero.entity = entity;
-//         }
+    entity
+}
 
-//         // make map aware of actor
-//         map.add_being(idx, entity, true);
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::{load_being_data, BeingKinds};
+    use crate::horde::Leader;
+    use crate::test_util::make_ecs;
 
-//         // Add to schedule
-//         executor.insert(entity, kind.being.act_time as u64);
+    #[test]
+    fn spawn_registered_components() {
+        let mut ecs = make_ecs(5, 5);
+        let data = r#"{ "CHIEF": { "ch": "c", "components": { "leader": {} } } }"#;
+        let value = gw_util::json::parse_string(data).unwrap();
+        load_being_data(&mut ecs.write_global::<BeingKinds>(), value).unwrap();
 
-//         return entity;
-//     }
+        let kind = ecs.read_global::<BeingKinds>().get("CHIEF").unwrap();
+        assert_eq!(kind.components.len(), 1);
+        assert_eq!(kind.components[0].0, "LEADER");
 
-//     panic!(
-//         "Trying to add actor to position that does not exist! kind={}, pos={},{}",
-//         kind.id, point.x, point.y
-//     );
-// }
+        let world = ecs.current_world_mut();
+        let chief = spawn_being(&kind, world, Point::new(2, 2));
+        assert!(world.read_component::<Leader>().get(chief).is_some());
+    }
+}
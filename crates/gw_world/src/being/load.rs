@@ -22,6 +22,8 @@ use gw_util::value::Value;
         "description": <STRING>,
 
         "ai": <STRING>,
+
+        "components": { <ID>: <VALUE> },
    }
 */
 
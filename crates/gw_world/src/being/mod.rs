@@ -30,3 +30,6 @@ pub use task::*;
 
 mod stats;
 pub use stats::*;
+
+mod progression;
+pub use progression::*;
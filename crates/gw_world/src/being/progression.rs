@@ -0,0 +1,321 @@
+use super::{Being, Stat, Stats};
+use crate::combat::being_name;
+use crate::hero::Hero;
+use crate::log::Logger;
+use gw_app::loader::{LoadError, LoadHandler};
+use gw_app::log;
+use gw_app::screen::BoxedScreen;
+use gw_ecs::prelude::{Ecs, Entity, World};
+use gw_util::value::Value;
+use lazy_static::lazy_static;
+use std::{collections::HashMap, sync::Mutex};
+
+/// Builds the screen shown when the hero gains a level (entity, new level)
+pub type LevelUpScreenFn = fn(&mut World, Entity, u32) -> BoxedScreen;
+
+lazy_static! {
+    static ref LEVEL_UP_SCREEN: Mutex<Option<LevelUpScreenFn>> = Mutex::new(None);
+}
+
+/// Sets the screen to push whenever the hero gains a level
+pub fn register_level_up_screen(func: LevelUpScreenFn) {
+    *LEVEL_UP_SCREEN.lock().unwrap() = Some(func);
+}
+
+/// The xp thresholds for each level and the stat growth for each level gained
+#[derive(Debug, Default, Clone)]
+pub struct Progression {
+    levels: Vec<u32>, // total xp needed for level 2, 3, ...
+    growth: HashMap<Stat, i32>,
+}
+
+impl Progression {
+    pub fn new() -> Self {
+        Progression::default()
+    }
+
+    pub fn set_levels(&mut self, levels: Vec<u32>) {
+        self.levels = levels;
+    }
+
+    pub fn set_growth(&mut self, stat: Stat, delta: i32) {
+        self.growth.insert(stat, delta);
+    }
+
+    pub fn max_level(&self) -> u32 {
+        self.levels.len() as u32 + 1
+    }
+
+    /// The total xp needed to reach the given level
+    pub fn threshold(&self, level: u32) -> Option<u32> {
+        match level {
+            0 | 1 => Some(0),
+            _ => self.levels.get(level as usize - 2).copied(),
+        }
+    }
+
+    /// The level a being with this much xp has reached
+    pub fn level_for(&self, xp: u32) -> u32 {
+        self.levels.iter().take_while(|t| xp >= **t).count() as u32 + 1
+    }
+
+    pub fn growth(&self) -> impl Iterator<Item = (&Stat, &i32)> {
+        self.growth.iter()
+    }
+}
+
+/// Pending level ups for the hero (used to show the level up screen)
+#[derive(Debug, Default)]
+pub struct LevelUps {
+    pending: Vec<(Entity, u32)>,
+}
+
+impl LevelUps {
+    pub fn push(&mut self, entity: Entity, level: u32) {
+        self.pending.push((entity, level));
+    }
+
+    pub fn take(&mut self) -> Option<(Entity, u32)> {
+        match self.pending.is_empty() {
+            true => None,
+            false => Some(self.pending.remove(0)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Adds the xp to the being, handling any levels gained - returns the number of levels gained
+pub fn gain_xp(world: &mut World, entity: Entity, xp: u32) -> u32 {
+    let (old_level, new_level) = {
+        let progression = world.read_global::<Progression>();
+        let mut beings = world.write_component::<Being>();
+        let mut being = match beings.get_mut(entity) {
+            None => return 0,
+            Some(being) => being,
+        };
+        being.xp = being.xp.saturating_add(xp);
+        let old_level = being.level;
+        being.level = old_level.max(progression.level_for(being.xp));
+        (old_level, being.level)
+    };
+
+    for level in (old_level + 1)..=new_level {
+        level_up(world, entity, level);
+    }
+
+    new_level - old_level
+}
+
+/// Gives the killer the xp value of the victim
+pub fn award_kill_xp(world: &mut World, killer: Entity, victim: Entity) -> u32 {
+    if killer == victim || !world.entities().is_alive(killer) {
+        return 0;
+    }
+    let xp = match world.read_component::<Being>().get(victim) {
+        None => return 0,
+        Some(being) => being.xp,
+    };
+    if xp == 0 {
+        return 0;
+    }
+    gain_xp(world, killer, xp)
+}
+
+fn level_up(world: &mut World, entity: Entity, level: u32) {
+    {
+        let progression = world.read_global::<Progression>();
+        if let Some(mut stats) = world.write_component::<Stats>().get_mut(entity) {
+            for (stat, delta) in progression.growth() {
+                stats.grow(stat.clone(), *delta);
+            }
+        }
+    }
+
+    let msg = format!("{} reaches level {}!", being_name(world, entity), level);
+    world.write_global::<Logger>().log(msg);
+
+    if world.read_resource::<Hero>().entity == entity {
+        world.write_resource::<LevelUps>().push(entity, level);
+    }
+}
+
+/// Returns the level up screen to show (if the hero gained a level and a screen is registered)
+pub fn take_level_up_screen(world: &mut World) -> Option<BoxedScreen> {
+    let (entity, level) = world.try_write_resource::<LevelUps>()?.take()?;
+    let func = (*LEVEL_UP_SCREEN.lock().unwrap())?;
+    Some(func(world, entity, level))
+}
+
+/*
+   JSON format:
+   {
+       "levels": [10, 25, 50, ...],   // total xp for level 2, 3, 4, ...
+       "growth": { "health": 5, "strength": 1, ... }
+   }
+*/
+
+pub fn load_progression_data(dest: &mut Progression, data: Value) -> Result<(), String> {
+    let map = match data.as_map() {
+        None => return Err("Progression data must be a map.".to_string()),
+        Some(v) => v,
+    };
+
+    if let Some(levels) = map.get(&"levels".into()) {
+        let list = match levels.as_list() {
+            None => return Err("Progression levels must be a list.".to_string()),
+            Some(v) => v,
+        };
+        let mut thresholds = Vec::new();
+        for v in list.iter() {
+            match v.as_int() {
+                Some(xp) if xp > 0 => thresholds.push(xp as u32),
+                _ => return Err(format!("Bad level threshold - {:?}", v)),
+            }
+        }
+        if thresholds.windows(2).any(|w| w[0] >= w[1]) {
+            return Err("Progression levels must be increasing.".to_string());
+        }
+        dest.set_levels(thresholds);
+    }
+
+    if let Some(growth) = map.get(&"growth".into()) {
+        let growth = match growth.as_map() {
+            None => return Err("Progression growth must be a map.".to_string()),
+            Some(v) => v,
+        };
+        for (name, v) in growth.iter() {
+            let stat: Stat = name.to_string().parse()?;
+            match v.as_int() {
+                None => return Err(format!("Bad stat growth - {} : {:?}", name, v)),
+                Some(delta) => dest.set_growth(stat, delta as i32),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct ProgressionLoader;
+
+impl ProgressionLoader {
+    pub fn new() -> ProgressionLoader {
+        ProgressionLoader
+    }
+}
+
+impl LoadHandler for ProgressionLoader {
+    fn file_loaded(&mut self, path: &str, data: Vec<u8>, ecs: &mut Ecs) -> Result<(), LoadError> {
+        let string = match String::from_utf8(data) {
+            Err(e) => {
+                return Err(LoadError::ParseError(format!(
+                    "Malformed file data '{}' : {}",
+                    path, e
+                )))
+            }
+            Ok(v) => v,
+        };
+
+        let value = if path.ends_with(".toml") {
+            gw_util::toml::parse_string(&string)
+        } else if path.ends_with(".json") || path.ends_with(".jsonc") {
+            gw_util::json::parse_string(&string)
+        } else {
+            return Err(LoadError::ParseError(
+                "Unsupported file format - require '.toml' or '.json' or '.jsonc'".to_string(),
+            ));
+        };
+        let value = value
+            .map_err(|e| LoadError::ParseError(format!("Failed to parse '{}' => {}", path, e)))?;
+
+        ecs.ensure_global::<Progression>();
+        let mut progression = ecs.write_global::<Progression>();
+
+        match load_progression_data(&mut progression, value) {
+            Err(e) => Err(LoadError::ProcessError(e)),
+            Ok(()) => {
+                log(format!(
+                    "Loaded progression :: max level={}",
+                    progression.max_level()
+                ));
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{add_test_world, make_empty_ecs};
+    use gw_ecs::prelude::Ecs;
+
+    fn make_ecs() -> Ecs {
+        let mut ecs = make_empty_ecs();
+        let data = r#"{ "levels": [10, 30], "growth": { "health": 5, "luck": 1 } }"#;
+        let value = gw_util::json::parse_string(data).unwrap();
+        load_progression_data(&mut ecs.write_global::<Progression>(), value).unwrap();
+
+        add_test_world(&mut ecs, 5, 3);
+        ecs
+    }
+
+    #[test]
+    fn thresholds() {
+        let mut progression = Progression::new();
+        progression.set_levels(vec![10, 30, 60]);
+        assert_eq!(progression.level_for(0), 1);
+        assert_eq!(progression.level_for(10), 2);
+        assert_eq!(progression.level_for(59), 3);
+        assert_eq!(progression.level_for(1000), 4);
+        assert_eq!(progression.threshold(3), Some(30));
+        assert_eq!(progression.threshold(5), None);
+    }
+
+    #[test]
+    fn bad_data() {
+        let mut progression = Progression::new();
+        let value = gw_util::json::parse_string(r#"{ "levels": [10, 5] }"#).unwrap();
+        assert!(load_progression_data(&mut progression, value).is_err());
+    }
+
+    #[test]
+    fn kill_levels_up() {
+        let mut ecs = make_ecs();
+        let world = ecs.current_world_mut();
+
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 10);
+        let hero = world.spawn((Being::new("HERO".to_string()), stats));
+        world.insert_resource(Hero::new(hero));
+
+        let mut orc = Being::new("ORC".to_string());
+        orc.xp = 35;
+        let orc = world.spawn((orc,));
+
+        assert_eq!(award_kill_xp(world, hero, orc), 2);
+        {
+            let beings = world.read_component::<Being>();
+            let being = beings.get(hero).unwrap();
+            assert_eq!(being.xp, 35);
+            assert_eq!(being.level, 3);
+
+            let stats = world.read_component::<Stats>();
+            let stats = stats.get(hero).unwrap();
+            assert_eq!(stats.get_max(Stat::HEALTH), Some(20));
+            assert_eq!(stats.get(Stat::CUSTOM("LUCK".to_string())), Some(2));
+        }
+
+        let mut logger = world.write_global::<Logger>();
+        let msgs: Vec<String> = logger.iter().map(|m| m.msg.clone()).collect();
+        assert!(msgs.contains(&"HERO reaches level 3!".to_string()));
+        drop(logger);
+
+        // No screen registered, but the level ups are queued
+        assert!(!world.read_resource::<LevelUps>().is_empty());
+        assert!(take_level_up_screen(world).is_none());
+    }
+}
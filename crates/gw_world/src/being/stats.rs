@@ -1,6 +1,7 @@
-use gw_ecs::prelude::Component;
-use serde::{Deserialize, Serialize};
+use gw_ecs::prelude::{Component, Entity, World};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
+use std::{fmt, str::FromStr};
 
 #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
 pub struct StatValue {
@@ -39,17 +40,78 @@ impl StatValue {
         self.value
     }
 
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+
+    /// Raises (or lowers) both the max and the current value
+    pub fn grow(&mut self, delta: i32) -> i32 {
+        self.max = self.max.saturating_add(delta);
+        self.update(delta)
+    }
+
     pub fn update(&mut self, delta: i32) -> i32 {
         self.value = self.value.saturating_add(delta).clamp(self.min, self.max);
         self.value
     }
 }
 
+/// Stats are saved (and loaded from data) by name, so custom stats can be added by the game.
 #[non_exhaustive]
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Stat {
     HEALTH,
     MAGIC,
+    STRENGTH,
+    DEFENSE,
+    ACCURACY,
+    SPEED,
+    CUSTOM(String),
+}
+
+impl FromStr for Stat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_uppercase();
+        match name.as_str() {
+            "" => Err("Empty stat name".to_string()),
+            "HEALTH" | "HP" => Ok(Stat::HEALTH),
+            "MAGIC" | "MP" => Ok(Stat::MAGIC),
+            "STRENGTH" | "STR" => Ok(Stat::STRENGTH),
+            "DEFENSE" | "DEF" => Ok(Stat::DEFENSE),
+            "ACCURACY" | "ACC" => Ok(Stat::ACCURACY),
+            "SPEED" => Ok(Stat::SPEED),
+            _ => Ok(Stat::CUSTOM(name)),
+        }
+    }
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stat::HEALTH => write!(f, "HEALTH"),
+            Stat::MAGIC => write!(f, "MAGIC"),
+            Stat::STRENGTH => write!(f, "STRENGTH"),
+            Stat::DEFENSE => write!(f, "DEFENSE"),
+            Stat::ACCURACY => write!(f, "ACCURACY"),
+            Stat::SPEED => write!(f, "SPEED"),
+            Stat::CUSTOM(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl Serialize for Stat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Stat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Component)]
@@ -71,6 +133,10 @@ impl Stats {
         }
     }
 
+    pub fn get_max(&self, stat: Stat) -> Option<i32> {
+        self.data.get(&stat).map(|val| val.max())
+    }
+
     pub fn update(&mut self, stat: Stat, delta: i32) -> i32 {
         match self.data.get_mut(&stat) {
             None => {
@@ -89,4 +155,93 @@ impl Stats {
             Some(val) => val.set_value(value),
         }
     }
+
+    /// Raises the max and current value of the stat (e.g. on level up)
+    pub fn grow(&mut self, stat: Stat, delta: i32) -> i32 {
+        match self.data.get_mut(&stat) {
+            None => {
+                self.data.insert(stat, StatValue::new(delta));
+                delta
+            }
+            Some(val) => val.grow(delta),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Stat, &StatValue)> {
+        self.data.iter()
+    }
+}
+
+/// The time until the entity acts again, adjusted by its SPEED.
+/// Each point of speed makes it 1% faster - negative speeds are slower (at most twice as slow).
+pub fn speed_adjusted_time(world: &World, entity: Entity, time: u64) -> u64 {
+    let speed = world
+        .read_component::<Stats>()
+        .get(entity)
+        .and_then(|stats| stats.get(Stat::SPEED))
+        .unwrap_or(0);
+    if speed == 0 || time == 0 {
+        return time;
+    }
+    let speed = speed.max(-50) as i64;
+    (time as i64 * 100 / (100 + speed)).max(1) as u64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::make_ecs;
+
+    #[test]
+    fn parse_stats() {
+        assert_eq!("health".parse::<Stat>().unwrap(), Stat::HEALTH);
+        assert_eq!("STR".parse::<Stat>().unwrap(), Stat::STRENGTH);
+        assert_eq!(
+            "luck".parse::<Stat>().unwrap(),
+            Stat::CUSTOM("LUCK".to_string())
+        );
+        assert!("".parse::<Stat>().is_err());
+    }
+
+    #[test]
+    fn save_by_name() {
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 10);
+        stats.set(Stat::CUSTOM("LUCK".to_string()), 3);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"LUCK\""));
+
+        let loaded: Stats = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get(Stat::HEALTH), Some(10));
+        assert_eq!(loaded.get(Stat::CUSTOM("LUCK".to_string())), Some(3));
+    }
+
+    #[test]
+    fn grow() {
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 10);
+        stats.update(Stat::HEALTH, -4);
+        assert_eq!(stats.grow(Stat::HEALTH, 5), 11);
+        assert_eq!(stats.get_max(Stat::HEALTH), Some(15));
+    }
+
+    #[test]
+    fn speed_changes_time() {
+        let mut ecs = make_ecs(5, 3);
+        let world = ecs.current_world_mut();
+
+        let mut fast = Stats::new();
+        fast.set(Stat::SPEED, 100);
+        let fast = world.spawn((fast,));
+        let mut slow = Stats::new();
+        slow.set(Stat::SPEED, -80);
+        let slow = world.spawn((slow,));
+        let normal = world.spawn((Stats::new(),));
+
+        assert_eq!(speed_adjusted_time(world, fast, 100), 50);
+        assert_eq!(speed_adjusted_time(world, slow, 100), 200);
+        assert_eq!(speed_adjusted_time(world, normal, 100), 100);
+        assert_eq!(speed_adjusted_time(world, fast, 0), 0);
+    }
 }
@@ -0,0 +1,74 @@
+use super::FeatureFlags;
+use crate::horde::HordeFlags;
+use gw_util::frequency::{self, Frequency};
+use gw_util::value::Value;
+use std::collections::HashMap;
+
+/// One of the things that a blueprint places in its area (a tile, an item, a horde, cell effects - or any mix of them)
+#[derive(Debug, Clone)]
+pub struct Feature {
+    pub tile: Option<String>,
+    pub item: Option<String>,
+    pub horde: Option<HordeFlags>, // the required flags of the horde
+    pub effects: HashMap<String, Vec<Value>>, // action -> effects data (parsed when added to the cell)
+    pub count: (u32, u32),
+    pub location: Option<String>, // names the cell (with the machine suffix) so that effects can find it
+    pub flags: FeatureFlags,
+}
+
+impl Feature {
+    pub fn new() -> Self {
+        Feature {
+            tile: None,
+            item: None,
+            horde: None,
+            effects: HashMap::new(),
+            count: (1, 1),
+            location: None,
+            flags: FeatureFlags::empty(),
+        }
+    }
+
+    pub fn add_effects(&mut self, action: &str, effects: Value) {
+        self.effects
+            .entry(action.to_uppercase())
+            .or_default()
+            .push(effects);
+    }
+}
+
+impl Default for Feature {
+    fn default() -> Self {
+        Feature::new()
+    }
+}
+
+/// A themed set of features (a machine) that is built into an interior area of a level - e.g. a key vault or a boss room
+#[derive(Debug, Clone)]
+pub struct Blueprint {
+    pub id: String,
+    pub frequency: Frequency,
+    pub size: (u32, u32), // the number of cells in the area
+    pub machine_id: u32,  // horde features use the hordes with this machine id
+    pub features: Vec<Feature>,
+}
+
+impl Blueprint {
+    pub fn new(id: &str) -> Self {
+        Blueprint {
+            id: id.to_uppercase(),
+            frequency: frequency::from_value(&Value::from(100)).unwrap(),
+            size: (1, 10000),
+            machine_id: 0,
+            features: Vec::new(),
+        }
+    }
+
+    pub fn frequency(&self, level: u32) -> u32 {
+        self.frequency.get_weight(level)
+    }
+
+    pub fn fits(&self, area_size: usize) -> bool {
+        area_size >= self.size.0 as usize && area_size <= self.size.1 as usize
+    }
+}
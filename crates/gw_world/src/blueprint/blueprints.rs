@@ -0,0 +1,65 @@
+use super::Blueprint;
+use gw_app::log;
+use gw_util::rng::RandomNumberGenerator;
+use std::sync::Arc;
+
+#[derive(Default, Debug)]
+pub struct Blueprints {
+    all: Vec<Arc<Blueprint>>,
+}
+
+impl Blueprints {
+    pub fn new() -> Self {
+        Blueprints { all: Vec::new() }
+    }
+
+    pub fn push(&mut self, blueprint: Blueprint) {
+        self.all.push(Arc::new(blueprint));
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<Blueprint>> {
+        let id = id.to_uppercase();
+        self.all.iter().find(|b| b.id == id).cloned()
+    }
+
+    pub fn dump(&self) {
+        log("Blueprints");
+        for blueprint in self.all.iter() {
+            log(format!("{:?}", blueprint));
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<Blueprint>> {
+        self.all.iter()
+    }
+
+    /// Picks a random blueprint for the depth that fits in an area of the given size, weighted by frequency
+    pub fn pick(
+        &self,
+        rng: &mut RandomNumberGenerator,
+        depth: u32,
+        area_size: usize,
+    ) -> Option<Arc<Blueprint>> {
+        let allowed: Vec<(&Arc<Blueprint>, u32)> = self
+            .all
+            .iter()
+            .filter(|b| b.fits(area_size))
+            .map(|b| (b, b.frequency(depth)))
+            .filter(|(_, freq)| *freq > 0)
+            .collect();
+
+        let total: u32 = allowed.iter().map(|(_, freq)| *freq).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut index = rng.range(1, total as i32 + 1) as u32;
+        for (blueprint, freq) in allowed {
+            if index <= freq {
+                return Some(Arc::clone(blueprint));
+            }
+            index -= freq;
+        }
+        None
+    }
+}
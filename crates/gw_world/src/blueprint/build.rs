@@ -0,0 +1,475 @@
+use super::{Blueprint, Blueprints, FeatureFlags};
+use crate::horde::{pick_machine_horde, spawn_horde};
+use crate::item::{spawn_item, ItemKind, ItemKinds};
+use crate::map::{Cell, Map};
+use crate::tile::{Tile, Tiles};
+use gw_app::log;
+use gw_ecs::prelude::World;
+use gw_util::point::DIRS;
+use gw_util::rng::RandomNumberGenerator;
+use gw_util::value::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+/// A part of the level that can only be reached through its entrance (the origin).
+/// The cells are in walking order from the origin (which is not one of them).
+#[derive(Debug, Clone)]
+pub struct Area {
+    pub origin: usize,
+    pub cells: Vec<usize>,
+}
+
+fn is_passable(map: &Map, idx: usize) -> bool {
+    !map.get_cell(idx).unwrap().blocks()
+}
+
+/// The passable cells reachable from the start without going through the origin - None if there are more than max_size
+fn flood_area(map: &Map, origin: usize, start: usize, max_size: usize) -> Option<Vec<usize>> {
+    let mut cells = vec![start];
+    let mut seen: HashSet<usize> = HashSet::from([origin, start]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(idx) = queue.pop_front() {
+        let pt = map.to_point(idx);
+        for dir in DIRS.iter() {
+            let next = match map.get_index(pt.x + dir.x, pt.y + dir.y) {
+                None => continue,
+                Some(next) => next,
+            };
+            if seen.contains(&next) || !is_passable(map, next) {
+                continue;
+            }
+            seen.insert(next);
+            cells.push(next);
+            if cells.len() > max_size {
+                return None;
+            }
+            queue.push_back(next);
+        }
+    }
+    Some(cells)
+}
+
+/// Finds the areas (up to max_size cells) that are closed off by a single chokepoint (e.g. a room with one door).
+/// Areas with any of the map's locations in them (e.g. the stairs) are skipped.
+pub fn find_interior_areas(map: &Map, max_size: usize) -> Vec<Area> {
+    let (width, height) = map.full_size();
+    let locations: HashSet<usize> = map.locations.values().copied().collect();
+    let mut found: HashSet<Vec<usize>> = HashSet::new();
+    let mut areas = Vec::new();
+
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            let origin = map.get_index(x, y).unwrap();
+            if !is_passable(map, origin) || map.passable_arc_count(x, y) < 2 {
+                continue;
+            }
+
+            for dir in DIRS.iter() {
+                let start = match map.get_index(x + dir.x, y + dir.y) {
+                    None => continue,
+                    Some(idx) => idx,
+                };
+                if !is_passable(map, start) {
+                    continue;
+                }
+                let cells = match flood_area(map, origin, start, max_size) {
+                    None => continue,
+                    Some(cells) => cells,
+                };
+                if cells.iter().any(|idx| locations.contains(idx)) {
+                    continue;
+                }
+                let mut key = cells.clone();
+                key.sort_unstable();
+                if found.insert(key) {
+                    areas.push(Area { origin, cells });
+                }
+            }
+        }
+    }
+    areas
+}
+
+type FeatureKinds = (Option<Arc<Tile>>, Option<Arc<ItemKind>>);
+
+/// Points the at_location effects in the data at this machine's copy of the blueprint locations (adds the machine suffix)
+fn add_machine_suffix(data: &Value, names: &HashSet<&str>, origin: usize) -> Value {
+    match data {
+        Value::List(list) => Value::List(
+            list.iter()
+                .map(|v| add_machine_suffix(v, names, origin))
+                .collect(),
+        ),
+        Value::Map(map) => {
+            let mut output = HashMap::new();
+            for (key, value) in map.iter() {
+                let mut value = add_machine_suffix(value, names, origin);
+                if key.to_string().to_lowercase() == "at_location" {
+                    if let Value::Map(ref mut at) = value {
+                        if let Some(location) = at.get_mut(&"location".into()) {
+                            let name = location.to_string().to_uppercase();
+                            if names.contains(name.as_str()) {
+                                *location = Value::String(format!("{}#{}", name, origin));
+                            }
+                        }
+                    }
+                }
+                output.insert(key.clone(), value);
+            }
+            Value::Map(output)
+        }
+        _ => data.clone(),
+    }
+}
+
+/// Builds the blueprint into the area.  Nothing is changed if a (non optional) feature does not fit.
+/// Locations get the machine suffix ("#<origin index>") so that many copies of a blueprint can be on one level.
+/// The at_location effects of the features are pointed at the locations of their own copy.
+pub fn build_blueprint(
+    world: &mut World,
+    blueprint: &Blueprint,
+    area: &Area,
+    depth: u32,
+) -> Result<(), String> {
+    // Check the ids first
+    let mut kinds: Vec<FeatureKinds> = Vec::new();
+    {
+        let tiles = world.read_global::<Tiles>();
+        let item_kinds = world.read_global::<ItemKinds>();
+        for feature in blueprint.features.iter() {
+            let tile = match feature.tile.as_ref() {
+                None => None,
+                Some(id) => match tiles.get(id) {
+                    None => {
+                        return Err(format!(
+                            "Unknown tile in blueprint {} - {}",
+                            blueprint.id, id
+                        ))
+                    }
+                    Some(tile) => Some(tile),
+                },
+            };
+            let item = match feature.item.as_ref() {
+                None => None,
+                Some(id) => match item_kinds.get(id) {
+                    None => {
+                        return Err(format!(
+                            "Unknown item in blueprint {} - {}",
+                            blueprint.id, id
+                        ))
+                    }
+                    Some(kind) => Some(kind),
+                },
+            };
+            kinds.push((tile, item));
+        }
+    }
+
+    // Pick the cells for all of the features
+    let mut plan: Vec<(usize, usize)> = Vec::new();
+    {
+        let mut rng = world.write_resource::<RandomNumberGenerator>();
+        let mut taken: HashSet<usize> = HashSet::new();
+        let len = area.cells.len();
+        let quarter = (len / 4).max(1).min(len);
+
+        for (index, feature) in blueprint.features.iter().enumerate() {
+            let cells: &[usize] = if feature.flags.contains(FeatureFlags::ORIGIN) {
+                std::slice::from_ref(&area.origin)
+            } else if feature.flags.contains(FeatureFlags::NEAR_ORIGIN) {
+                &area.cells[..quarter]
+            } else if feature.flags.contains(FeatureFlags::FAR_FROM_ORIGIN) {
+                &area.cells[len - quarter..]
+            } else {
+                &area.cells[..]
+            };
+            let mut open: Vec<usize> = cells
+                .iter()
+                .filter(|idx| !taken.contains(idx))
+                .copied()
+                .collect();
+
+            let (min, max) = feature.count;
+            let count = rng.range(min as i32, max.max(min) as i32 + 1) as u32;
+            let mut placed = 0;
+            while placed < count && !open.is_empty() {
+                let idx = open.swap_remove(rng.rand(open.len() as u32) as usize);
+                taken.insert(idx);
+                plan.push((index, idx));
+                placed += 1;
+            }
+
+            if placed < min && !feature.flags.contains(FeatureFlags::OPTIONAL) {
+                return Err(format!(
+                    "Blueprint {} does not fit - feature {} placed {} of {}",
+                    blueprint.id, index, placed, min
+                ));
+            }
+        }
+    }
+
+    let names: HashSet<&str> = blueprint
+        .features
+        .iter()
+        .filter_map(|f| f.location.as_deref())
+        .collect();
+
+    for (index, idx) in plan {
+        let feature = &blueprint.features[index];
+        let (tile, item) = &kinds[index];
+        let point = {
+            let mut map = world.write_resource::<Map>();
+            if let Some(tile) = tile {
+                map.force_tile(idx, tile.clone());
+            }
+            for (action, effects) in feature.effects.iter() {
+                for data in effects.iter() {
+                    let data = add_machine_suffix(data, &names, area.origin);
+                    map.add_effects(idx, action, data)?;
+                }
+            }
+            if let Some(name) = feature.location.as_ref() {
+                map.set_location(&format!("{}#{}", name, area.origin), idx);
+            }
+            map.to_point(idx)
+        };
+
+        if let Some(kind) = item {
+            spawn_item(kind, world, point);
+        }
+
+        if let Some(flags) = feature.horde {
+            match pick_machine_horde(world, depth, blueprint.machine_id, flags) {
+                None => log(format!(
+                    "No horde for blueprint {} - flags={}",
+                    blueprint.id, flags
+                )),
+                Some(horde) => {
+                    spawn_horde(&horde, world, point);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds up to count random blueprints into the interior areas of the level.  Returns the number built.
+pub fn build_machines(world: &mut World, depth: u32, count: u32) -> u32 {
+    let max_size = match world
+        .read_global::<Blueprints>()
+        .iter()
+        .map(|b| b.size.1)
+        .max()
+    {
+        None => return 0,
+        Some(size) => size as usize,
+    };
+    let areas = find_interior_areas(&world.read_resource::<Map>(), max_size);
+    let order = world
+        .write_resource::<RandomNumberGenerator>()
+        .sequence(areas.len());
+
+    let mut used: HashSet<usize> = HashSet::new();
+    let mut built = 0;
+    for index in order {
+        if built >= count {
+            break;
+        }
+        let area = &areas[index];
+        if used.contains(&area.origin) || area.cells.iter().any(|idx| used.contains(idx)) {
+            continue;
+        }
+
+        let blueprint: Option<Arc<Blueprint>> = {
+            let blueprints = world.read_global::<Blueprints>();
+            let mut rng = world.write_resource::<RandomNumberGenerator>();
+            blueprints.pick(&mut rng, depth, area.cells.len())
+        };
+        let blueprint = match blueprint {
+            None => continue,
+            Some(blueprint) => blueprint,
+        };
+
+        match build_blueprint(world, &blueprint, area, depth) {
+            Err(e) => log(e),
+            Ok(()) => {
+                used.insert(area.origin);
+                used.extend(area.cells.iter().copied());
+                built += 1;
+            }
+        }
+    }
+    built
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::blueprint::load_blueprint_data;
+    use crate::effect::fire_cell_action;
+    use crate::test_util::{add_test_world, make_empty_ecs};
+    use gw_ecs::prelude::Ecs;
+
+    // ########
+    // #......#
+    // ###.####   <- the door (origin)
+    // #.....##   <- the closet
+    // ########
+    fn make_ecs() -> Ecs {
+        let mut ecs = make_empty_ecs();
+        let world = add_test_world(&mut ecs, 8, 5);
+        let (floor, wall) = {
+            let tiles = world.read_global::<Tiles>();
+            (tiles.get("FLOOR").unwrap(), tiles.get("WALL").unwrap())
+        };
+        let mut map = world.write_resource::<Map>();
+        map.fill(wall);
+        for x in 1..7 {
+            let idx = map.get_index(x, 1).unwrap();
+            map.force_tile(idx, floor.clone());
+        }
+        for x in 1..6 {
+            let idx = map.get_index(x, 3).unwrap();
+            map.force_tile(idx, floor.clone());
+        }
+        let door = map.get_index(3, 2).unwrap();
+        map.force_tile(door, floor);
+        let start = map.get_index(1, 1).unwrap();
+        map.set_location("START", start);
+        drop(map);
+        ecs
+    }
+
+    #[test]
+    fn closet_is_interior() {
+        let ecs = make_ecs();
+        let world = ecs.current_world();
+        let map = world.read_resource::<Map>();
+
+        let areas = find_interior_areas(&map, 20);
+        let door = map.get_index(3, 2).unwrap();
+        let closet = areas.iter().find(|a| a.origin == door).unwrap();
+        assert_eq!(closet.cells.len(), 5);
+
+        let start = map.get_location("START").unwrap();
+        assert!(areas.iter().all(|a| !a.cells.contains(&start)));
+    }
+
+    #[test]
+    fn key_opens_vault() {
+        let mut ecs = make_ecs();
+        let world = ecs.current_world_mut();
+
+        let data = gw_util::json::parse_string(
+            r#"[{
+                "id": "vault",
+                "size": [4, 6],
+                "features": [
+                    { "tile": "WALL", "location": "VAULT_DOOR", "flags": "ORIGIN" },
+                    { "location": "ALTAR", "flags": "FAR_FROM_ORIGIN",
+                      "effects": { "drop": { "at_location": { "location": "VAULT_DOOR", "effects": { "tile": "FLOOR" } } } } }
+                ]
+            }]"#,
+        )
+        .unwrap();
+        let count = load_blueprint_data(&mut world.write_global::<Blueprints>(), data).unwrap();
+        assert_eq!(count, 1);
+
+        assert_eq!(build_machines(world, 1, 1), 1);
+
+        let (door, altar) = {
+            let map = world.read_resource::<Map>();
+            let door = map.get_index(3, 2).unwrap();
+            assert_eq!(
+                map.find_location("VAULT_DOOR", map.to_point(door)),
+                Some(door)
+            );
+            assert!(map.get_cell(door).unwrap().blocks());
+            let altar = map.find_location("ALTAR", map.to_point(door)).unwrap();
+            assert!(map.has_cell_effects(altar, "DROP"));
+            (door, map.to_point(altar))
+        };
+
+        fire_cell_action(world, altar, "drop", None);
+        assert!(!world
+            .read_resource::<Map>()
+            .get_cell(door)
+            .unwrap()
+            .blocks());
+    }
+
+    #[test]
+    fn vaults_open_their_own_doors() {
+        let mut ecs = make_empty_ecs();
+        let world = add_test_world(&mut ecs, 11, 9);
+        {
+            let (floor, wall) = {
+                let tiles = world.read_global::<Tiles>();
+                (tiles.get("FLOOR").unwrap(), tiles.get("WALL").unwrap())
+            };
+            // The far end of the right vault is as close to the left door as to its own
+            let rows = [
+                "###########",
+                "#.........#",
+                "##.#####.##",
+                "##.#####.##",
+                "##.#####.##",
+                "##.#####.##",
+                "##.#####.##",
+                "####.....##",
+                "###########",
+            ];
+            let mut map = world.write_resource::<Map>();
+            for (y, row) in rows.iter().enumerate() {
+                for (x, ch) in row.chars().enumerate() {
+                    let idx = map.get_index(x as i32, y as i32).unwrap();
+                    let tile = if ch == '#' {
+                        wall.clone()
+                    } else {
+                        floor.clone()
+                    };
+                    map.force_tile(idx, tile);
+                }
+            }
+            let start = map.get_index(1, 1).unwrap();
+            map.set_location("START", start);
+        }
+
+        let data = gw_util::json::parse_string(
+            r#"[{
+                "id": "vault",
+                "features": [
+                    { "tile": "WALL", "location": "VAULT_DOOR", "flags": "ORIGIN" },
+                    { "location": "ALTAR", "flags": "FAR_FROM_ORIGIN",
+                      "effects": { "drop": { "at_location": { "location": "VAULT_DOOR", "effects": { "tile": "FLOOR" } } } } }
+                ]
+            }]"#,
+        )
+        .unwrap();
+        load_blueprint_data(&mut world.write_global::<Blueprints>(), data).unwrap();
+        let blueprint = world.read_global::<Blueprints>().get("VAULT").unwrap();
+
+        let (left, right) = {
+            let map = world.read_resource::<Map>();
+            (map.get_index(2, 2).unwrap(), map.get_index(8, 2).unwrap())
+        };
+        let areas = find_interior_areas(&world.read_resource::<Map>(), 20);
+        for door in [left, right] {
+            let area = areas.iter().find(|a| a.origin == door).unwrap();
+            build_blueprint(world, &blueprint, area, 1).unwrap();
+        }
+
+        let altar = {
+            let map = world.read_resource::<Map>();
+            let altar = map.get_location(&format!("ALTAR#{}", right)).unwrap();
+            map.to_point(altar)
+        };
+        fire_cell_action(world, altar, "drop", None);
+
+        let map = world.read_resource::<Map>();
+        assert!(!map.get_cell(right).unwrap().blocks());
+        assert!(map.get_cell(left).unwrap().blocks());
+    }
+}
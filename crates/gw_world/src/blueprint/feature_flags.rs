@@ -0,0 +1,58 @@
+use bitflags::bitflags;
+use gw_util::fl;
+use std::fmt;
+use std::str::FromStr;
+
+bitflags! {
+    #[derive(Default)]
+    pub struct FeatureFlags: u32 {
+
+        // !!!!!!!!!!!!!!!!!!!!!
+        // NOTE - If you add anything, you must add to FromStr impl below!!!!
+        // !!!!!!!!!!!!!!!!!!!!!
+
+        const ORIGIN = fl!(0);            // placed at the entrance of the area (e.g. the vault door)
+        const NEAR_ORIGIN = fl!(1);       // placed in the quarter of the area closest to the entrance
+        const FAR_FROM_ORIGIN = fl!(2);   // placed in the quarter of the area farthest from the entrance
+        const OPTIONAL = fl!(3);          // the blueprint can be built even if this feature does not fit
+    }
+}
+
+impl FeatureFlags {
+    pub fn apply(&mut self, flags: &str) {
+        for val in flags.split('|') {
+            if val.trim().starts_with('!') {
+                if let Ok(flag) = FeatureFlags::from_str(&val.trim()[1..]) {
+                    self.remove(flag);
+                }
+            } else if let Ok(flag) = FeatureFlags::from_str(val) {
+                self.insert(flag);
+            }
+        }
+    }
+}
+
+impl FromStr for FeatureFlags {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = FeatureFlags::empty();
+        for val in s.split('|') {
+            match val.trim().to_uppercase().as_ref() {
+                "ORIGIN" | "BUILD_AT_ORIGIN" => result |= FeatureFlags::ORIGIN,
+                "NEAR_ORIGIN" => result |= FeatureFlags::NEAR_ORIGIN,
+                "FAR_FROM_ORIGIN" | "FAR" => result |= FeatureFlags::FAR_FROM_ORIGIN,
+                "OPTIONAL" => result |= FeatureFlags::OPTIONAL,
+                "" => {}
+                _ => return Err(format!("Unknown FeatureFlag: {}", s)),
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl fmt::Display for FeatureFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
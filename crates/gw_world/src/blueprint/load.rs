@@ -0,0 +1,222 @@
+use super::{Blueprint, Blueprints, Feature, FeatureFlags};
+use crate::effect::parse_effects;
+use crate::horde::HordeFlags;
+use gw_app::loader::{LoadError, LoadHandler};
+use gw_app::log;
+use gw_ecs::prelude::Ecs;
+use gw_util::frequency;
+use gw_util::value::Value;
+use std::str::FromStr;
+
+/*
+   JSON format:
+   {
+    id: <ID>,   // REQUIRED
+    frequency: <INT> or { <INT>: <FORMULA>, "<A>-<B>": <FORMULA>, "C+": <FORMULA> } // defaults to 100
+    size: <INT> or [<MIN>, <MAX>],  // the number of cells in the area
+    machine: <INT>,     // horde features use the hordes with this machineId
+    features: [ {
+        tile: <ID>,
+        item: <ID>,
+        horde: <STRING>,    // the horde flags that the horde must have (e.g. "MACHINE_BOSS")
+        count: <INT> or [<MIN>, <MAX>],     // defaults to 1
+        location: <NAME>,   // names the cell (with a machine suffix) so that effects can find it
+        flags: <STRING>,    // Any feature flags in flags string format
+        effects: { <ACTION>: { <EFFECTS> }, ... },  // effects added to the cell (e.g. "drop")
+    },* ]
+   }
+*/
+
+fn parse_range(value: &Value) -> Option<(u32, u32)> {
+    if let Some(v) = value.as_int() {
+        return Some((v as u32, v as u32));
+    }
+    let list = value.as_list()?;
+    match list.as_slice() {
+        [min, max] => Some((min.as_int()? as u32, max.as_int()? as u32)),
+        _ => None,
+    }
+}
+
+fn parse_feature(value: &Value) -> Result<Feature, String> {
+    let map = match value.as_map() {
+        None => return Err(format!("Blueprint feature must be an object - {:?}", value)),
+        Some(v) => v,
+    };
+
+    let mut feature = Feature::new();
+    for (key, value) in map.iter() {
+        match key.to_string().to_lowercase().as_str() {
+            "tile" => feature.tile = Some(value.to_string().to_uppercase()),
+            "item" => feature.item = Some(value.to_string().to_uppercase()),
+            "horde" => match HordeFlags::from_str(&value.to_string()) {
+                Err(e) => return Err(e),
+                Ok(flags) => feature.horde = Some(flags),
+            },
+            "count" => match parse_range(value) {
+                None => return Err(format!("Bad feature count - {:?}", value)),
+                Some(count) => feature.count = count,
+            },
+            "location" => feature.location = Some(value.to_string().to_uppercase()),
+            "flags" => match FeatureFlags::from_str(&value.to_string()) {
+                Err(e) => return Err(e),
+                Ok(flags) => feature.flags = flags,
+            },
+            "effects" => {
+                let actions = match value.as_map() {
+                    None => return Err(format!("Feature effects must be an object - {:?}", value)),
+                    Some(v) => v,
+                };
+                for (action, effects) in actions.iter() {
+                    parse_effects(effects)?; // checked here, parsed again when built
+                    feature.add_effects(&action.to_string(), effects.clone());
+                }
+            }
+            field => return Err(format!("Unknown blueprint feature field - {}", field)),
+        }
+    }
+    Ok(feature)
+}
+
+pub fn load_blueprint_data(dest: &mut Blueprints, data: Value) -> Result<u32, String> {
+    let list = match data.to_list() {
+        None => return Err("Blueprint data must be an array.".to_string()),
+        Some(v) => v,
+    };
+
+    let mut count: u32 = 0;
+
+    for data in list.iter() {
+        let data_table = match data.as_map() {
+            None => return Err(format!("Bad data format - {}", data)),
+            Some(v) => v,
+        };
+
+        let id = match data_table.get(&"id".into()) {
+            None => {
+                return Err(format!(
+                    "blueprint entry missing id field. - {:?}",
+                    data_table
+                ))
+            }
+            Some(id) => id.to_string(),
+        };
+
+        let mut blueprint = Blueprint::new(&id);
+
+        for (key, value) in data_table.iter() {
+            match key.to_string().to_lowercase().as_str() {
+                "id" => {}
+                "frequency" => match frequency::from_value(value) {
+                    Err(e) => return Err(format!("Bad blueprint frequency - {}: {:?}", id, e)),
+                    Ok(f) => blueprint.frequency = f,
+                },
+                "size" => match parse_range(value) {
+                    None => return Err(format!("Bad blueprint size - {}: {:?}", id, value)),
+                    Some(size) => blueprint.size = size,
+                },
+                "machine" | "machine_id" | "machineid" => match value.as_int() {
+                    None => return Err(format!("Bad blueprint machine - {}: {:?}", id, value)),
+                    Some(v) => blueprint.machine_id = v as u32,
+                },
+                "features" => match value.as_list() {
+                    None => return Err(format!("Blueprint features must be an array - {}", id)),
+                    Some(list) => {
+                        for entry in list.iter() {
+                            match parse_feature(entry) {
+                                Err(e) => {
+                                    return Err(format!(
+                                        "Error processing blueprint {} - {}",
+                                        id, e
+                                    ))
+                                }
+                                Ok(feature) => blueprint.features.push(feature),
+                            }
+                        }
+                    }
+                },
+                field => return Err(format!("Unknown blueprint field - {}: {}", id, field)),
+            }
+        }
+
+        dest.push(blueprint);
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+pub struct BlueprintsLoader {
+    dump: bool,
+}
+
+impl BlueprintsLoader {
+    pub fn new() -> BlueprintsLoader {
+        BlueprintsLoader { dump: false }
+    }
+
+    pub fn with_dump(mut self) -> Self {
+        self.dump = true;
+        self
+    }
+}
+
+impl Default for BlueprintsLoader {
+    fn default() -> Self {
+        BlueprintsLoader::new()
+    }
+}
+
+impl LoadHandler for BlueprintsLoader {
+    fn file_loaded(&mut self, path: &str, data: Vec<u8>, ecs: &mut Ecs) -> Result<(), LoadError> {
+        let string = match String::from_utf8(data) {
+            Err(e) => {
+                return Err(LoadError::ParseError(format!(
+                    "Malformed file data '{}' : {}",
+                    path, e
+                )))
+            }
+            Ok(v) => v,
+        };
+
+        let value = if path.ends_with(".toml") {
+            match gw_util::toml::parse_string(&string) {
+                Err(e) => {
+                    return Err(LoadError::ParseError(format!(
+                        "Failed to parse '{}' => {}",
+                        path, e
+                    )))
+                }
+                Ok(v) => v,
+            }
+        } else if path.ends_with(".json") || path.ends_with(".jsonc") {
+            match gw_util::json::parse_string(&string) {
+                Err(e) => {
+                    return Err(LoadError::ParseError(format!(
+                        "Failed to parse '{}' => {}",
+                        path, e
+                    )))
+                }
+                Ok(v) => v,
+            }
+        } else {
+            return Err(LoadError::ParseError(
+                "Unsupported file format - require '.toml' or '.json' or '.jsonc'".to_string(),
+            ));
+        };
+
+        ecs.ensure_global::<Blueprints>();
+        let mut blueprints = ecs.write_global::<Blueprints>();
+
+        match load_blueprint_data(&mut blueprints, value) {
+            Err(e) => return Err(LoadError::ProcessError(e)),
+            Ok(count) => log(format!("Loaded {} blueprints", count)),
+        }
+
+        if self.dump {
+            blueprints.dump();
+        }
+
+        Ok(())
+    }
+}
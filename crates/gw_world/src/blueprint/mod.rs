@@ -0,0 +1,11 @@
+mod blueprint;
+mod blueprints;
+mod build;
+mod feature_flags;
+mod load;
+
+pub use blueprint::*;
+pub use blueprints::*;
+pub use build::*;
+pub use feature_flags::*;
+pub use load::*;
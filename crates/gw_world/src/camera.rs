@@ -74,6 +74,10 @@ impl Camera {
         self.needs_draw = true;
     }
 
+    pub fn follows(&self) -> Option<Entity> {
+        self.follows
+    }
+
     pub fn set_follows(&mut self, entity: Entity) {
         self.follows = Some(entity);
         self.needs_draw = true;
@@ -1,7 +1,9 @@
 use std::fmt::Display;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum DamageKind {
+    #[default]
     BASH,
     SLASH,
     PIERCE,
@@ -11,6 +13,28 @@ pub enum DamageKind {
     POISON,
 }
 
+impl Display for DamageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for DamageKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_ref() {
+            "BASH" => Ok(DamageKind::BASH),
+            "SLASH" => Ok(DamageKind::SLASH),
+            "PIERCE" => Ok(DamageKind::PIERCE),
+            "MAGIC" => Ok(DamageKind::MAGIC),
+            "HEAL" => Ok(DamageKind::HEAL),
+            "POISON" => Ok(DamageKind::POISON),
+            _ => Err(format!("Unknown DamageKind: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct DamageInfo {
     pub kind: DamageKind,
@@ -25,6 +49,29 @@ impl DamageInfo {
 
 impl Display for DamageInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "#[red][{}]", self.amount)
+        match self.kind {
+            DamageKind::BASH => write!(f, "#[red][{}]", self.amount),
+            kind => write!(f, "#[red][{} {}]", self.amount, kind),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn kind_in_message() {
+        assert_eq!("slash".parse::<DamageKind>().unwrap(), DamageKind::SLASH);
+        assert!("fire".parse::<DamageKind>().is_err());
+
+        assert_eq!(
+            DamageInfo::new(DamageKind::BASH, 3).to_string(),
+            "#[red][3]"
+        );
+        assert_eq!(
+            DamageInfo::new(DamageKind::POISON, 3).to_string(),
+            "#[red][3 poison]"
+        );
     }
 }
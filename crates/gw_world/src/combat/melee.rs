@@ -1,8 +1,9 @@
-use super::DamageInfo;
+use crate::being::{Being, Stat, Stats};
 use crate::effect::{parse_damage, BoxedEffect};
 use crate::log::Logger;
 use gw_ecs::prelude::Component;
 use gw_ecs::prelude::{Entity, World};
+use gw_util::rng::RandomNumberGenerator;
 use gw_util::value::Value;
 use serde::{Deserialize, Serialize};
 
@@ -20,25 +21,47 @@ pub enum CombatMessage {
 }
 
 impl CombatMessage {
-    pub fn log(&self, world: &mut World, attacker: Entity, target: Entity, damage: DamageInfo) {
-        let attack_name = "attacker";
-        let target_name = "target";
+    /// Adds the message to the combat log line (effects - e.g. damage - add to the same line)
+    pub fn log(&self, world: &World, attacker: Entity, target: Entity) {
+        let attack_name = being_name(world, attacker);
+        let target_name = being_name(world, target);
         let mut logger = world.write_global::<Logger>();
         match self {
             CombatMessage::Hit => {
-                logger.log(format!("{} hit {} [{}]", attack_name, target_name, damage))
+                logger.log_combat(format!("{} hits {}", attack_name, target_name), true)
             }
-            CombatMessage::Miss => logger.log(format!("{} miss {}", attack_name, target_name)),
-            CombatMessage::Verb(v) => logger.log(format!(
-                "{} {} {} [{}]",
-                attack_name, v, target_name, damage
-            )),
-            CombatMessage::Replace(m) => logger.log(m),
+            CombatMessage::Miss => {
+                logger.log_combat(format!("{} misses {}", attack_name, target_name), true)
+            }
+            CombatMessage::Verb(v) => {
+                logger.log_combat(format!("{} {} {}", attack_name, v, target_name), true)
+            }
+            CombatMessage::Replace(m) => logger.log_combat(
+                m.replace("{attacker}", &attack_name)
+                    .replace("{target}", &target_name),
+                true,
+            ),
         }
     }
 }
 
-#[derive(Debug, Default, Clone /* Serialize, Deserialize */)]
+/// The attack's hit chance adjusted by the attacker's ACCURACY and the target's DEFENSE
+pub fn hit_chance(world: &World, attacker: Entity, target: Entity, base: u32) -> u32 {
+    let stats = world.read_component::<Stats>();
+    let stat = |entity, stat| stats.get(entity).and_then(|s| s.get(stat)).unwrap_or(0);
+    let chance = base as i32 + stat(attacker, Stat::ACCURACY) - stat(target, Stat::DEFENSE);
+    chance.clamp(0, 100) as u32
+}
+
+/// The name to use for the entity in combat messages
+pub fn being_name(world: &World, entity: Entity) -> String {
+    match world.read_component::<Being>().get(entity) {
+        None => "something".to_string(),
+        Some(being) => being.name().clone(),
+    }
+}
+
+#[derive(Debug, Clone /* Serialize, Deserialize */)]
 pub struct Attack {
     weight: u32, // choice from multiple chance
     chance: u32, // hit chance
@@ -47,6 +70,40 @@ pub struct Attack {
     miss_msg: CombatMessage,
 }
 
+impl Attack {
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn chance(&self) -> u32 {
+        self.chance
+    }
+
+    pub fn effects(&self) -> &Vec<BoxedEffect> {
+        &self.effects
+    }
+
+    pub fn hit_msg(&self) -> &CombatMessage {
+        &self.hit_msg
+    }
+
+    pub fn miss_msg(&self) -> &CombatMessage {
+        &self.miss_msg
+    }
+}
+
+impl Default for Attack {
+    fn default() -> Self {
+        Attack {
+            weight: 100,
+            chance: 100,
+            effects: Vec::new(),
+            hit_msg: CombatMessage::Hit,
+            miss_msg: CombatMessage::Miss,
+        }
+    }
+}
+
 pub fn parse_attack(value: &Value) -> Result<Attack, CombatParseError> {
     if value.is_string() {
         // This is a damage string - e.g. "1-5" or "1d6+1"
@@ -78,8 +135,17 @@ pub fn parse_attack(value: &Value) -> Result<Attack, CombatParseError> {
         // {
         //   weight: <INT> // pick weight of attack (to make some more likely than others) [default] = 100
         //   damage: <STRING> | <INT>
-        if let Some(damage_value) = value_map.get(&"damage".into()) {
-            let effect: BoxedEffect = match parse_damage(damage_value) {
+        if let Some(weight_value) = value_map.get(&"weight".into()) {
+            let weight = weight_value
+                .as_int()
+                .ok_or(CombatParseError::InvalidValueType)?;
+            attack.weight = weight as u32;
+        }
+
+        //   kind: <STRING> // kind of damage - e.g. "slash" [default] = "bash"
+        if value_map.contains_key(&"damage".into()) {
+            // The attack map has the same damage and kind fields as a damage map
+            let effect: BoxedEffect = match parse_damage(value) {
                 Err(_) => return Err(CombatParseError::InvalidValueType),
                 Ok(v) => v,
             };
@@ -89,6 +155,18 @@ pub fn parse_attack(value: &Value) -> Result<Attack, CombatParseError> {
         //   verb: <STRING> // e.g.[default] - "hit~" for "hit" or "hits"
         //   miss_verb: <STRING> // e.g. [default] - "miss~~" for "miss" or "misses"
         //   chance: <INT>  // chance to hit out of 100 - default = 100
+        if let Some(verb_value) = value_map.get(&"verb".into()) {
+            if !verb_value.is_string() {
+                return Err(CombatParseError::InvalidValueType);
+            }
+            attack.hit_msg = CombatMessage::Verb(verb_value.to_string());
+        }
+        if let Some(verb_value) = value_map.get(&"miss_verb".into()) {
+            if !verb_value.is_string() {
+                return Err(CombatParseError::InvalidValueType);
+            }
+            attack.miss_msg = CombatMessage::Verb(verb_value.to_string());
+        }
         if let Some(chance_value) = value_map.get(&"chance".into()) {
             let chance = chance_value
                 .as_int()
@@ -100,6 +178,18 @@ pub fn parse_attack(value: &Value) -> Result<Attack, CombatParseError> {
         //   hit_msg: <STRING> // e.g. [default] - "{attacker} {verb} {target} [{damage}]"
         //   miss_msg: <STRING> // e.g. [default] - "{attacker} {verb} {target}"
         // }
+        if let Some(msg_value) = value_map.get(&"hit_msg".into()) {
+            if !msg_value.is_string() {
+                return Err(CombatParseError::InvalidValueType);
+            }
+            attack.hit_msg = CombatMessage::Replace(msg_value.to_string());
+        }
+        if let Some(msg_value) = value_map.get(&"miss_msg".into()) {
+            if !msg_value.is_string() {
+                return Err(CombatParseError::InvalidValueType);
+            }
+            attack.miss_msg = CombatMessage::Replace(msg_value.to_string());
+        }
 
         Ok(attack)
     } else {
@@ -118,11 +208,40 @@ impl Melee {
             attacks: Vec::new(),
         }
     }
+
+    pub fn push(&mut self, attack: Attack) {
+        self.attacks.push(attack);
+    }
+
+    pub fn attacks(&self) -> &Vec<Attack> {
+        &self.attacks
+    }
+
+    /// Picks one of the attacks using their weights
+    pub fn pick_attack(&self, rng: &mut RandomNumberGenerator) -> Option<&Attack> {
+        let total: u32 = self.attacks.iter().map(|a| a.weight).sum();
+        if total == 0 {
+            return self.attacks.first();
+        }
+        let mut roll = rng.rand(total);
+        for attack in self.attacks.iter() {
+            if roll < attack.weight {
+                return Some(attack);
+            }
+            roll -= attack.weight;
+        }
+        None
+    }
 }
 
 pub fn parse_melee(value: &Value) -> Result<Melee, CombatParseError> {
     if value.is_list() {
-        Err(CombatParseError::InvalidValueType)
+        // A list of attacks - picked using their weights
+        let mut melee = Melee::new();
+        for item in value.as_list().unwrap().iter() {
+            melee.attacks.push(parse_attack(item)?);
+        }
+        Ok(melee)
     } else if value.is_string() {
         Err(CombatParseError::InvalidValueType)
     } else if value.is_map() {
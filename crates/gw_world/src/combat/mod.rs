@@ -1,5 +1,7 @@
 mod damage;
 mod melee;
+mod ranged;
 
 pub use damage::*;
 pub use melee::*;
+pub use ranged::*;
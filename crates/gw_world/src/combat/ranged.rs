@@ -0,0 +1,208 @@
+use super::{parse_attack, Attack, CombatParseError};
+use crate::item::{Inventory, Item};
+use crate::map::{Cell, Map};
+use crate::tile::TileMove;
+use gw_app::{color::named, Glyph, RGBA};
+use gw_ecs::prelude::{Component, Entity, World};
+use gw_util::point::{line::get_line, Point};
+use gw_util::value::Value;
+
+pub const DEFAULT_RANGE: u32 = 6;
+
+/// A ranged attack - e.g. a bow or a breath weapon
+#[derive(Debug, Clone, Component)]
+pub struct Ranged {
+    pub range: u32,
+    pub attack: Attack,
+    /// The item id that is used up with each shot
+    pub ammo: Option<String>,
+    pub glyph: Glyph,
+    pub fg: RGBA,
+}
+
+impl Ranged {
+    pub fn new(attack: Attack) -> Self {
+        Ranged {
+            range: DEFAULT_RANGE,
+            attack,
+            ammo: None,
+            glyph: '*' as Glyph,
+            fg: named::YELLOW.into(),
+        }
+    }
+}
+
+/*
+   JSON format:
+   "ranged": "<DAMAGE>" | <INT> | {
+       "range": <INT>,
+       "ammo": <ITEM_ID>,
+       "glyph" | "ch": <STRING>,
+       "fg": <RGBA_CONFIG>,
+       ... all of the attack fields (damage, chance, verb, ...)
+   }
+*/
+pub fn parse_ranged(value: &Value) -> Result<Ranged, CombatParseError> {
+    let mut ranged = Ranged::new(parse_attack(value)?);
+
+    let map = match value.as_map() {
+        None => return Ok(ranged),
+        Some(map) => map,
+    };
+
+    if let Some(range) = map.get(&"range".into()) {
+        ranged.range = range.as_int().ok_or(CombatParseError::InvalidValueType)? as u32;
+    }
+    if let Some(ammo) = map.get(&"ammo".into()) {
+        ranged.ammo = Some(ammo.to_string().to_uppercase());
+    }
+    if let Some(glyph) = map.get(&"glyph".into()).or_else(|| map.get(&"ch".into())) {
+        ranged.glyph = match glyph.as_int() {
+            Some(v) => v as Glyph,
+            None => match glyph.to_string().chars().next() {
+                None => return Err(CombatParseError::InvalidValueType),
+                Some(ch) => ch as Glyph,
+            },
+        };
+    }
+    if let Some(fg) = map.get(&"fg".into()) {
+        ranged.fg = fg
+            .try_into()
+            .map_err(|_| CombatParseError::InvalidValueType)?;
+    }
+
+    Ok(ranged)
+}
+
+/// Where a projectile went
+#[derive(Debug, Clone)]
+pub struct ProjectilePath {
+    /// The cells the projectile passed through (not including the origin)
+    pub path: Vec<Point>,
+    /// Where the projectile stopped
+    pub impact: Point,
+    /// The being that stopped the projectile
+    pub target: Option<Entity>,
+}
+
+/// Traces the path of a projectile toward the target point.
+/// It stops before cells that block missiles and at the first blocking being.
+pub fn trace_projectile(map: &Map, from: Point, to: Point, range: u32) -> ProjectilePath {
+    let mut path = Vec::new();
+    let mut impact = from;
+
+    for pt in get_line(from, to).into_iter().skip(1) {
+        if path.len() >= range as usize {
+            break;
+        }
+        let idx = match map.get_wrapped_index(pt.x, pt.y) {
+            None => break,
+            Some(idx) => idx,
+        };
+        if map
+            .get_cell(idx)
+            .unwrap()
+            .has_move_flag(TileMove::BLOCKS_MISSILES)
+        {
+            break;
+        }
+
+        path.push(pt);
+        impact = pt;
+
+        if let Some(target) = map.blocking_being(idx) {
+            return ProjectilePath {
+                path,
+                impact,
+                target: Some(target),
+            };
+        }
+    }
+
+    ProjectilePath {
+        path,
+        impact,
+        target: None,
+    }
+}
+
+/// The first item in the entity's inventory that is the ammo (None if no ammo is needed)
+pub fn find_ammo(world: &World, entity: Entity, ammo: &str) -> Option<Entity> {
+    let inventories = world.read_component::<Inventory>();
+    let inventory = inventories.get(entity)?;
+    let items = world.read_component::<Item>();
+    let found = inventory.iter().find(|item| match items.get(*item) {
+        None => false,
+        Some(item) => item.id == ammo,
+    });
+    found
+}
+
+/// Does the entity have what it needs to fire?
+pub fn has_ammo(world: &World, entity: Entity, ranged: &Ranged) -> bool {
+    match ranged.ammo {
+        None => true,
+        Some(ref ammo) => find_ammo(world, entity, ammo).is_some(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tile::Tiles;
+    use gw_ecs::prelude::Builder;
+    use std::collections::HashMap;
+
+    fn make_map() -> Map {
+        let tiles = Tiles::default();
+        let mut map = Map::new(10, 3);
+        map.fill(tiles.get("FLOOR").unwrap());
+        let idx = map.get_index(7, 1).unwrap();
+        map.reset_tiles(idx, tiles.get("WALL").unwrap());
+        map
+    }
+
+    #[test]
+    fn stops_at_walls() {
+        let map = make_map();
+
+        let trace = trace_projectile(&map, Point::new(1, 1), Point::new(9, 1), 20);
+        assert_eq!(trace.impact, Point::new(6, 1));
+        assert_eq!(trace.path.len(), 5);
+        assert!(trace.target.is_none());
+
+        let trace = trace_projectile(&map, Point::new(1, 1), Point::new(9, 1), 3);
+        assert_eq!(trace.impact, Point::new(4, 1));
+    }
+
+    #[test]
+    fn stops_at_beings() {
+        let mut map = make_map();
+        let mut world = World::empty(1);
+        let entity = world.create_entity().id();
+        let idx = map.get_index(4, 1).unwrap();
+        map.add_being(idx, entity, true);
+
+        let trace = trace_projectile(&map, Point::new(1, 1), Point::new(6, 1), 20);
+        assert_eq!(trace.impact, Point::new(4, 1));
+        assert_eq!(trace.target, Some(entity));
+    }
+
+    #[test]
+    fn parse() {
+        let ranged = parse_ranged(&Value::from("1d4")).ok().unwrap();
+        assert_eq!(ranged.range, DEFAULT_RANGE);
+        assert!(ranged.ammo.is_none());
+
+        let mut data = HashMap::new();
+        data.insert("damage".into(), Value::from(3));
+        data.insert("range".into(), Value::from(8));
+        data.insert("ammo".into(), Value::from("arrow"));
+        data.insert("ch".into(), Value::from("-"));
+        let ranged = parse_ranged(&Value::from(data)).ok().unwrap();
+        assert_eq!(ranged.range, 8);
+        assert_eq!(ranged.ammo, Some("ARROW".to_string()));
+        assert_eq!(ranged.glyph, '-' as Glyph);
+        assert_eq!(ranged.attack.effects().len(), 1);
+    }
+}
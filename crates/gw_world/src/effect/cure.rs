@@ -1,5 +1,5 @@
 use super::{BoxedEffect, Effect, EffectResult};
-use crate::log::Logger;
+use crate::status::{remove_status, StatusKind};
 use gw_ecs::prelude::{Entity, World};
 use gw_util::point::Point;
 use gw_util::value::Value;
@@ -7,23 +7,46 @@ use gw_util::value::Value;
 ////////////////////////
 
 #[derive(Debug, Clone)]
-pub struct Cure;
+pub struct Cure(Vec<StatusKind>);
+
+impl Cure {
+    pub fn new(kinds: Vec<StatusKind>) -> Self {
+        Cure(kinds)
+    }
+}
 
 impl Effect for Cure {
-    fn fire(&self, world: &mut World, _pos: Point, _entity: Option<Entity>) -> EffectResult {
-        // TODO - Logger is a global now!!!!
-        let mut logger = world.write_global::<Logger>();
-        logger.log(format!("Cured!"));
-        EffectResult::Success
+    fn fire(&self, world: &mut World, _pos: Point, entity: Option<Entity>) -> EffectResult {
+        let entity = match entity {
+            None => return EffectResult::Nothing,
+            Some(entity) => entity,
+        };
+
+        let mut result = EffectResult::Nothing;
+        for kind in self.0.iter() {
+            if remove_status(world, entity, *kind) {
+                result = EffectResult::Success;
+            }
+        }
+        result
     }
 }
 
+/// "cure": true (poison) | "blind" | ["blind", "confusion"]
 pub(super) fn parse_cure(value: &Value) -> Result<BoxedEffect, String> {
     if value.is_bool() {
-        Ok(Box::new(Cure))
+        Ok(Box::new(Cure(vec![StatusKind::POISON])))
+    } else if value.is_string() {
+        Ok(Box::new(Cure(vec![value.to_string().parse()?])))
+    } else if value.is_list() {
+        let mut kinds = Vec::new();
+        for v in value.as_list().unwrap().iter() {
+            kinds.push(v.to_string().parse()?);
+        }
+        Ok(Box::new(Cure(kinds)))
     } else {
         Err(format!(
-            "Cure effect can only receive bool values.  Received: {:?}",
+            "Cure effect can only receive bool, string, or list values.  Received: {:?}",
             value
         ))
     }
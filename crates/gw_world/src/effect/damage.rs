@@ -1,46 +1,149 @@
 use super::{BoxedEffect, Effect, EffectResult};
+use crate::being::{Stat, Stats};
+use crate::combat::{DamageInfo, DamageKind};
 use crate::log::Logger;
 use gw_ecs::prelude::{Entity, World};
-use gw_util::dice::Dice;
+use gw_util::dice::{roll, Dice};
 use gw_util::point::Point;
+use gw_util::rng::RandomNumberGenerator;
 use gw_util::value::Value;
 
 ////////////////////////
 
 #[derive(Debug, Clone)]
-pub struct Damage(Dice);
+pub struct Damage {
+    dice: Dice,
+    kind: DamageKind,
+}
 
 impl Damage {
     pub fn new(dice: Dice) -> Self {
-        Damage(dice)
+        Damage {
+            dice,
+            kind: DamageKind::BASH,
+        }
+    }
+
+    pub fn with_kind(mut self, kind: DamageKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn kind(&self) -> DamageKind {
+        self.kind
     }
 }
 
 impl Effect for Damage {
-    fn fire(&self, world: &mut World, _pos: Point, _entity: Option<Entity>) -> EffectResult {
+    fn fire(&self, world: &mut World, _pos: Point, entity: Option<Entity>) -> EffectResult {
+        let entity = match entity {
+            None => return EffectResult::Nothing,
+            Some(entity) => entity,
+        };
+
+        let amount = {
+            let mut rng = world.write_resource::<RandomNumberGenerator>();
+            roll(&self.dice, &mut rng).max(0)
+        };
+
+        {
+            let mut stats = world.write_component::<Stats>();
+            match stats.get_mut(entity) {
+                None => return EffectResult::Nothing,
+                Some(mut stats) => {
+                    stats.update(Stat::HEALTH, -amount);
+                }
+            }
+        }
+
+        let info = DamageInfo::new(self.kind, amount);
         let mut logger = world.write_global::<Logger>();
-        logger.log(format!("Damage - {}", self.0));
+        logger.log_combat(info, false);
         EffectResult::Success
     }
 }
 
+/// Damage is either dice - e.g. "1d6+1" - a constant - e.g. 6 - or a map with the kind of damage:
+/// { "damage": <STRING> | <INT>, "kind": <STRING> } - the kind defaults to BASH
 pub(crate) fn parse_damage(value: &Value) -> Result<BoxedEffect, String> {
+    Ok(Box::new(parse_damage_data(value)?))
+}
+
+fn parse_damage_data(value: &Value) -> Result<Damage, String> {
     if value.is_int() {
-        Ok(Box::new(Damage(Dice::simple(
+        Ok(Damage::new(Dice::simple(
             0,
             0,
             value.as_int().unwrap() as i32,
-        ))))
+        )))
     } else if value.is_string() {
         let dice: Dice = match value.to_string().parse() {
             Err(_) => return Err(format!("Failed to parse dice - {:?}", value)),
             Ok(d) => d,
         };
-        Ok(Box::new(Damage(dice)))
+        Ok(Damage::new(dice))
+    } else if value.is_map() {
+        let map = value.as_map().unwrap();
+        let damage = match map.get(&"damage".into()) {
+            None => return Err(format!("Damage map needs a damage field - {:?}", value)),
+            Some(v) if v.is_map() => return Err(format!("Invalid damage - {:?}", value)),
+            Some(v) => parse_damage_data(v)?,
+        };
+        match map.get(&"kind".into()) {
+            None => Ok(damage),
+            Some(kind) => Ok(damage.with_kind(kind.to_string().parse()?)),
+        }
     } else {
         Err(format!(
-            "Damage tile events can only receive int values.  Received: {:?}",
+            "Damage tile events can only receive int, string or map values.  Received: {:?}",
             value
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::make_ecs;
+
+    #[test]
+    fn parse_kind() {
+        let damage = parse_damage_data(&Value::from(3)).unwrap();
+        assert_eq!(damage.kind(), DamageKind::BASH);
+
+        let value =
+            gw_util::json::parse_string(r#"{ "damage": "1d4", "kind": "pierce" }"#).unwrap();
+        let damage = parse_damage_data(&value).unwrap();
+        assert_eq!(damage.kind(), DamageKind::PIERCE);
+
+        let value = gw_util::json::parse_string(r#"{ "damage": 2, "kind": "fire" }"#).unwrap();
+        assert!(parse_damage_data(&value).is_err());
+        let value = gw_util::json::parse_string(r#"{ "kind": "slash" }"#).unwrap();
+        assert!(parse_damage_data(&value).is_err());
+    }
+
+    #[test]
+    fn logs_kind() {
+        let mut ecs = make_ecs(3, 3);
+        let world = ecs.current_world_mut();
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 10);
+        let entity = world.spawn((stats,));
+
+        let damage = Damage::new(Dice::simple(0, 0, 4)).with_kind(DamageKind::SLASH);
+        damage.fire(world, Point::new(0, 0), Some(entity));
+
+        let health = world
+            .read_component::<Stats>()
+            .get(entity)
+            .unwrap()
+            .get(Stat::HEALTH);
+        assert_eq!(health, Some(6));
+        let msgs: Vec<String> = world
+            .write_global::<Logger>()
+            .iter()
+            .map(|m| m.msg.clone())
+            .collect();
+        assert_eq!(msgs[0], "#[red][4 slash]");
+    }
+}
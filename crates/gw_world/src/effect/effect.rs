@@ -1,7 +1,7 @@
 use super::{
-    parse_cure, parse_damage, parse_fixture, parse_heal, parse_message, parse_move_entity,
-    parse_move_region, parse_poison, parse_portal, parse_restore_items, parse_store_items,
-    parse_tile, parse_treasure,
+    parse_at_location, parse_cure, parse_damage, parse_fixture, parse_heal, parse_message,
+    parse_move_entity, parse_move_region, parse_poison, parse_portal, parse_restore_items,
+    parse_status, parse_store_items, parse_tile, parse_treasure,
 };
 use crate::map::Map;
 use gw_ecs::prelude::{Entity, World};
@@ -69,12 +69,14 @@ lazy_static! {
         map.insert("cure".to_string(), parse_cure);
         map.insert("heal".to_string(), parse_heal);
         map.insert("poison".to_string(), parse_poison);
+        map.insert("status".to_string(), parse_status);
         map.insert("tile".to_string(), parse_tile);
         map.insert("fixture".to_string(), parse_fixture);
         map.insert("store_items".to_string(), parse_store_items);
         map.insert("restore_items".to_string(), parse_restore_items);
         map.insert("treasure".to_string(), parse_treasure);
         map.insert("portal".to_string(), parse_portal);
+        map.insert("at_location".to_string(), parse_at_location);
 
         Mutex::new(map)
     };
@@ -87,9 +89,17 @@ pub fn register_effect_parser(id: &str, parser: EffectParseFn) {
         .insert(id.to_string().to_lowercase(), parser);
 }
 
+fn get_effect_parser(id: &str) -> Option<EffectParseFn> {
+    // copied out so that parsers can parse nested effects without deadlocking
+    EFFECT_PARSERS
+        .lock()
+        .unwrap()
+        .get(id.to_lowercase().as_str())
+        .copied()
+}
+
 pub fn parse_effect(id: &str, value: &Value) -> Result<BoxedEffect, String> {
-    let parsers = EFFECT_PARSERS.lock().unwrap();
-    match parsers.get(id.to_string().to_lowercase().as_str()) {
+    match get_effect_parser(id) {
         None => Err(format!("No parser found for effect: {}", id)),
         Some(parser) => parser(value),
     }
@@ -99,12 +109,11 @@ pub fn parse_effects(value: &Value) -> Result<Vec<BoxedEffect>, String> {
     if !value.is_map() {
         return Err(format!("Effects must be map.  Found: {:?}", value));
     }
-    let parsers = EFFECT_PARSERS.lock().unwrap();
     let map = value.as_map().unwrap();
     let mut output = Vec::new();
     for (key, val) in map.iter() {
         let id = key.to_string().to_lowercase();
-        match parsers.get(&id) {
+        match get_effect_parser(&id) {
             None => return Err(format!("No parser found for effect: {}", id)),
             Some(parser) => match parser(val) {
                 Err(e) => return Err(e),
@@ -1,18 +1,43 @@
 use super::{BoxedEffect, Effect, EffectResult};
-use crate::log::Logger;
+use crate::item::{lift_item, place_item, Inventory, Item};
+use crate::map::Map;
 use gw_ecs::prelude::{Entity, World};
 use gw_util::point::Point;
 use gw_util::value::Value;
 
 ////////////////////////
 
+/// Moves the entity's inventory (or the entity itself if it is an item) into the stash at the cell
 #[derive(Debug, Clone)]
 pub struct StoreItems;
 
 impl Effect for StoreItems {
-    fn fire(&self, world: &mut World, _pos: Point, _entity: Option<Entity>) -> EffectResult {
-        let mut logger = world.write_global::<Logger>();
-        logger.log(format!("StoreItems!"));
+    fn fire(&self, world: &mut World, pos: Point, entity: Option<Entity>) -> EffectResult {
+        let entity = match entity {
+            None => return EffectResult::Nothing,
+            Some(entity) => entity,
+        };
+        let idx = match world.read_resource::<Map>().get_wrapped_index(pos.x, pos.y) {
+            None => return EffectResult::Nothing,
+            Some(idx) => idx,
+        };
+
+        let items = match world.write_component::<Inventory>().get_mut(entity) {
+            Some(mut inventory) => inventory.take_all(),
+            None => match world.read_component::<Item>().contains(entity) {
+                false => return EffectResult::Nothing,
+                true => vec![entity],
+            },
+        };
+
+        if items.is_empty() {
+            return EffectResult::Nothing;
+        }
+
+        for item in items.iter() {
+            lift_item(world, *item);
+        }
+        world.write_resource::<Map>().stash_items(idx, items);
         EffectResult::Success
     }
 }
@@ -30,14 +55,34 @@ pub(super) fn parse_store_items(value: &Value) -> Result<BoxedEffect, String> {
 
 ////////////////////////
 
+/// Takes the items out of the stash at the cell - into the entity's inventory (if it has one) or onto the floor
 #[derive(Debug, Clone)]
 pub struct RestoreItems;
 
 impl Effect for RestoreItems {
-    fn fire(&self, world: &mut World, _pos: Point, _entity: Option<Entity>) -> EffectResult {
-        let mut logger = world.write_global::<Logger>();
+    fn fire(&self, world: &mut World, pos: Point, entity: Option<Entity>) -> EffectResult {
+        let idx = match world.read_resource::<Map>().get_wrapped_index(pos.x, pos.y) {
+            None => return EffectResult::Nothing,
+            Some(idx) => idx,
+        };
+
+        let items = world.write_resource::<Map>().take_stash(idx);
+        if items.is_empty() {
+            return EffectResult::Nothing;
+        }
 
-        logger.log(format!("RestoreItems!"));
+        for item in items {
+            let added = match entity {
+                None => false,
+                Some(entity) => match world.write_component::<Inventory>().get_mut(entity) {
+                    None => false,
+                    Some(mut inventory) => inventory.add(item),
+                },
+            };
+            if !added {
+                place_item(world, item, idx);
+            }
+        }
         EffectResult::Success
     }
 }
@@ -52,3 +97,79 @@ pub(super) fn parse_restore_items(value: &Value) -> Result<BoxedEffect, String>
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::item::{create_item, ItemKind};
+    use crate::position::Position;
+    use crate::test_util::make_ecs;
+
+    #[test]
+    fn store_and_restore() {
+        let mut ecs = make_ecs(5, 3);
+        let world = ecs.current_world_mut();
+        let kind = ItemKind::builder("ROCK").build();
+
+        let rock_a = create_item(&kind, world);
+        let rock_b = create_item(&kind, world);
+        let mut inventory = Inventory::default();
+        inventory.add(rock_a);
+        inventory.add(rock_b);
+        let being = world.spawn((Position::new(2, 1), inventory));
+
+        let pos = Point::new(2, 1);
+        let idx = world.read_resource::<Map>().get_index(2, 1).unwrap();
+        assert!(matches!(
+            StoreItems.fire(world, pos, Some(being)),
+            EffectResult::Success
+        ));
+        assert!(world
+            .read_component::<Inventory>()
+            .get(being)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            world
+                .read_resource::<Map>()
+                .iter_stash(idx)
+                .collect::<Vec<_>>(),
+            vec![rock_a, rock_b]
+        );
+
+        // back into the inventory
+        assert!(matches!(
+            RestoreItems.fire(world, pos, Some(being)),
+            EffectResult::Success
+        ));
+        assert_eq!(
+            world
+                .read_component::<Inventory>()
+                .get(being)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(world.read_resource::<Map>().iter_stash(idx).count(), 0);
+
+        // nobody to give them to - onto the floor
+        StoreItems.fire(world, pos, Some(being));
+        RestoreItems.fire(world, pos, None);
+        assert_eq!(world.read_resource::<Map>().iter_items(idx).count(), 2);
+        assert!(world.read_component::<Position>().contains(rock_a));
+    }
+
+    #[test]
+    fn store_dropped_item() {
+        let mut ecs = make_ecs(5, 3);
+        let world = ecs.current_world_mut();
+        let kind = ItemKind::builder("ROCK").build();
+        let rock = crate::item::spawn_item(&kind, world, Point::new(3, 2));
+        let idx = world.read_resource::<Map>().get_index(3, 2).unwrap();
+
+        StoreItems.fire(world, Point::new(3, 2), Some(rock));
+        assert_eq!(world.read_resource::<Map>().iter_items(idx).count(), 0);
+        assert_eq!(world.read_resource::<Map>().iter_stash(idx).count(), 1);
+        assert!(!world.read_component::<Position>().contains(rock));
+    }
+}
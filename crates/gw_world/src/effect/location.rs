@@ -0,0 +1,67 @@
+use super::{parse_effects, BoxedEffect, Effect, EffectResult};
+use crate::map::Map;
+use gw_ecs::prelude::{Entity, World};
+use gw_util::point::Point;
+use gw_util::value::Value;
+
+/// Fires the effects at a named location - e.g. dropping the key on the altar opens the vault door.
+/// Blueprints point these at their own (machine suffixed) locations, other machine locations are found by their base name (the closest one wins).
+#[derive(Debug, Clone)]
+pub struct AtLocation {
+    pub location: String,
+    pub effects: Vec<BoxedEffect>,
+}
+
+impl AtLocation {
+    pub fn new(location: &str, effects: Vec<BoxedEffect>) -> Self {
+        AtLocation {
+            location: location.to_uppercase(),
+            effects,
+        }
+    }
+}
+
+impl Effect for AtLocation {
+    fn fire(&self, world: &mut World, pos: Point, entity: Option<Entity>) -> EffectResult {
+        let target = {
+            let map = world.read_resource::<Map>();
+            match map.find_location(&self.location, pos) {
+                None => return EffectResult::Fail,
+                Some(idx) => map.to_point(idx),
+            }
+        };
+
+        for effect in self.effects.iter() {
+            match effect.fire(world, target, entity) {
+                EffectResult::Stop => return EffectResult::Stop,
+                EffectResult::Fail => return EffectResult::Fail,
+                _ => {}
+            }
+        }
+        EffectResult::Success
+    }
+}
+
+pub(super) fn parse_at_location(value: &Value) -> Result<BoxedEffect, String> {
+    let map = match value.as_map() {
+        None => {
+            return Err(format!(
+                "At Location effects must be objects.  Received: {:?}",
+                value
+            ))
+        }
+        Some(map) => map,
+    };
+
+    let location = match map.get(&"location".into()) {
+        None => return Err("At Location effects require 'location' field".to_string()),
+        Some(val) => val.to_string(),
+    };
+
+    let effects = match map.get(&"effects".into()) {
+        None => return Err("At Location effects require 'effects' field".to_string()),
+        Some(val) => parse_effects(val)?,
+    };
+
+    Ok(Box::new(AtLocation::new(&location, effects)))
+}
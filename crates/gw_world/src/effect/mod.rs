@@ -25,8 +25,14 @@ pub use heal::*;
 mod poison;
 pub use poison::*;
 
+mod status;
+pub use status::*;
+
 mod items;
 pub use items::*;
 
 mod treasure;
 pub use treasure::*;
+
+mod location;
+pub use location::*;
@@ -0,0 +1,130 @@
+use super::{BoxedEffect, Effect, EffectResult};
+use crate::status::{apply_status, StatusEffect, StatusKind};
+use gw_ecs::prelude::{Entity, World};
+use gw_util::point::Point;
+use gw_util::value::Value;
+
+/// Turns used when the data file does not give any
+pub const DEFAULT_STATUS_TURNS: u32 = 10;
+
+////////////////////////
+
+#[derive(Debug, Clone)]
+pub struct ApplyStatus(Vec<StatusEffect>);
+
+impl ApplyStatus {
+    pub fn new(effects: Vec<StatusEffect>) -> Self {
+        ApplyStatus(effects)
+    }
+}
+
+impl Effect for ApplyStatus {
+    fn fire(&self, world: &mut World, _pos: Point, entity: Option<Entity>) -> EffectResult {
+        let entity = match entity {
+            None => return EffectResult::Nothing,
+            Some(entity) => entity,
+        };
+
+        let mut result = EffectResult::Nothing;
+        for effect in self.0.iter() {
+            if apply_status(world, entity, *effect) {
+                result = EffectResult::Success;
+            }
+        }
+        result
+    }
+}
+
+/// Parses the amount of a status effect.
+/// Allowed values:
+/// - true - default turns and power
+/// - <INT> - turns
+/// - { turns: <INT>, power: <INT> }
+pub(crate) fn parse_status_effect(kind: StatusKind, value: &Value) -> Result<StatusEffect, String> {
+    if value.is_bool() {
+        Ok(StatusEffect::new(kind, DEFAULT_STATUS_TURNS, 1))
+    } else if value.is_int() {
+        let turns = value.as_int().unwrap().max(0) as u32;
+        Ok(StatusEffect::new(kind, turns, 1))
+    } else if value.is_map() {
+        let turns = match value.get_value("turns") {
+            None => DEFAULT_STATUS_TURNS,
+            Some(v) => match v.as_int() {
+                None => return Err(format!("Status turns must be int.  Received: {:?}", v)),
+                Some(v) => v.max(0) as u32,
+            },
+        };
+        let power = match value.get_value("power") {
+            None => 1,
+            Some(v) => match v.as_int() {
+                None => return Err(format!("Status power must be int.  Received: {:?}", v)),
+                Some(v) => v as i32,
+            },
+        };
+        Ok(StatusEffect::new(kind, turns, power))
+    } else {
+        Err(format!(
+            "Status effects can only receive bool, int, or map values.  Received: {:?}",
+            value
+        ))
+    }
+}
+
+/// "status": "haste" | { "haste": 10, "poison": { "turns": 5, "power": 2 } }
+pub(super) fn parse_status(value: &Value) -> Result<BoxedEffect, String> {
+    if value.is_string() {
+        let kind: StatusKind = value.to_string().parse()?;
+        Ok(Box::new(ApplyStatus(vec![StatusEffect::new(
+            kind,
+            DEFAULT_STATUS_TURNS,
+            1,
+        )])))
+    } else if value.is_map() {
+        let mut effects = Vec::new();
+        for (key, val) in value.as_map().unwrap().iter() {
+            let kind: StatusKind = key.to_string().parse()?;
+            effects.push(parse_status_effect(kind, val)?);
+        }
+        Ok(Box::new(ApplyStatus(effects)))
+    } else {
+        Err(format!(
+            "Status effects can only receive string or map values.  Received: {:?}",
+            value
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::effect::parse_effects;
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse_map() {
+        let mut poison = HashMap::new();
+        poison.insert("turns".into(), Value::from(5));
+        poison.insert("power".into(), Value::from(2));
+
+        let mut data = HashMap::new();
+        data.insert("poison".into(), Value::from(poison));
+        data.insert("haste".into(), Value::from(3));
+        let effects = parse_status(&Value::from(data)).unwrap();
+
+        let text = format!("{:?}", effects);
+        assert!(text.contains("kind: POISON, turns: 5, power: 2"));
+        assert!(text.contains("kind: HASTE, turns: 3, power: 1"));
+    }
+
+    #[test]
+    fn parse_poison_cure() {
+        let mut data = HashMap::new();
+        data.insert("poison".into(), Value::from(4));
+        data.insert("cure".into(), Value::from("blind"));
+        assert_eq!(parse_effects(&Value::from(data)).unwrap().len(), 2);
+
+        let mut data = HashMap::new();
+        data.insert("status".into(), Value::from("taco"));
+        assert!(parse_effects(&Value::from(data)).is_err());
+    }
+}
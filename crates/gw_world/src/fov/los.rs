@@ -0,0 +1,70 @@
+use super::FovSource;
+use crate::map::Map;
+use gw_ecs::prelude::World;
+use gw_util::point::{line::get_line, Point};
+use std::collections::HashMap;
+
+/// Remembers line of sight checks until the map tiles change (see `refresh_vision`)
+#[derive(Debug, Default)]
+pub struct LosCache {
+    cache: HashMap<(Point, Point), bool>,
+}
+
+impl LosCache {
+    pub fn new() -> Self {
+        LosCache::default()
+    }
+
+    pub fn get(&self, from: Point, to: Point) -> Option<bool> {
+        self.cache.get(&(from, to)).copied()
+    }
+
+    pub fn insert(&mut self, from: Point, to: Point, clear: bool) {
+        self.cache.insert((from, to), clear);
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+/// Is the line between the points free of opaque cells?  The end points themselves are not checked.
+pub fn is_line_clear<S: FovSource>(source: &S, from: Point, to: Point) -> bool {
+    let line = get_line(from, to);
+    if line.len() <= 2 {
+        return true;
+    }
+    line[1..line.len() - 1]
+        .iter()
+        .all(|pt| !source.is_opaque(pt.x, pt.y))
+}
+
+/// Checks (and caches) whether there is a clear line of sight between the points on the map
+pub fn has_line_of_sight(world: &World, from: Point, to: Point) -> bool {
+    if let Some(cache) = world.try_read_resource::<LosCache>() {
+        if let Some(clear) = cache.get(from, to) {
+            return clear;
+        }
+    }
+
+    let clear = {
+        let map = match world.try_read_resource::<Map>() {
+            None => return false,
+            Some(map) => map,
+        };
+        is_line_clear(&*map, from, to)
+    };
+
+    if let Some(mut cache) = world.try_write_resource::<LosCache>() {
+        cache.insert(from, to, clear);
+    }
+    clear
+}
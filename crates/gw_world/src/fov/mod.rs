@@ -1,7 +1,9 @@
 use crate::map::Cell;
 use crate::map::Map;
 use gw_util::point::Point;
+use serde::{Deserialize, Serialize};
 use std::cmp::min;
+use std::str::FromStr;
 
 // pub mod bracket;
 pub mod goblin;
@@ -13,14 +15,34 @@ pub use mask::*;
 mod fov;
 pub use fov::*;
 
+mod los;
+pub use los::*;
+
+mod vision;
+pub use vision::*;
+
 // CREDIT - This is adapted from: http://roguebasin.roguelikedevelopment.org/index.php?title=Improved_Shadowcasting_in_Java
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum FovCalc {
     // Bracket,
+    #[default]
     Goblin,
     Symmetric,
 }
 
+impl FromStr for FovCalc {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "GOBLIN" => Ok(FovCalc::Goblin),
+            "SYMMETRIC" => Ok(FovCalc::Symmetric),
+            _ => Err(format!("Unknown fov calculation - {}", s)),
+        }
+    }
+}
+
 impl FovCalc {
     pub fn calculate<S: FovSource, T: FovTarget>(
         &self,
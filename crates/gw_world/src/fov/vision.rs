@@ -0,0 +1,292 @@
+use super::{has_line_of_sight, FovCalc, FovSource, FovTarget, LosCache};
+use crate::light::LightMap;
+use crate::map::Map;
+use crate::position::Position;
+use gw_ecs::prelude::{Component, Entity, Join, World};
+use gw_util::point::Point;
+use gw_util::value::Value;
+use serde::{Deserialize, Serialize};
+
+/// What a being can see - recalculated when the being moves or the map tiles change.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Vision {
+    pub radius: u32,
+    #[serde(default)]
+    pub calc: FovCalc,
+    #[serde(skip)]
+    origin: Option<Point>,
+    #[serde(skip)]
+    width: u32,
+    #[serde(skip)]
+    visible: Vec<bool>,
+}
+
+impl Vision {
+    pub fn new(radius: u32) -> Self {
+        Vision {
+            radius,
+            calc: FovCalc::default(),
+            origin: None,
+            width: 0,
+            visible: Vec::new(),
+        }
+    }
+
+    pub fn with_calc(mut self, calc: FovCalc) -> Self {
+        self.calc = calc;
+        self
+    }
+
+    pub fn set_dirty(&mut self) {
+        self.origin = None;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.origin.is_none()
+    }
+
+    /// Is the point visible (as of the last calculation)?
+    pub fn can_see(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width as i32 {
+            return false;
+        }
+        let idx = (x + y * self.width as i32) as usize;
+        self.visible.get(idx).copied().unwrap_or(false)
+    }
+
+    /// Recalculates the visible cells if the origin has moved (or the vision is dirty)
+    pub fn update<S: FovSource>(&mut self, source: &S, origin: Point) {
+        if self.origin == Some(origin) {
+            return;
+        }
+        let (width, height) = source.get_size();
+        self.reset(width, height);
+        let calc = self.calc;
+        let radius = self.radius;
+        calc.calculate(source, origin, radius, self);
+        self.origin = Some(origin);
+    }
+}
+
+impl FovTarget for Vision {
+    fn reset(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.visible.clear();
+        self.visible.resize((width * height) as usize, false);
+    }
+
+    fn set_visible(&mut self, x: i32, y: i32, pct: f32) {
+        if pct <= 0.0 || x < 0 || y < 0 || x >= self.width as i32 {
+            return;
+        }
+        let idx = (x + y * self.width as i32) as usize;
+        if let Some(val) = self.visible.get_mut(idx) {
+            *val = true;
+        }
+    }
+}
+
+/// Parses a vision config - either the radius or {"radius": <INT>, "fov": "goblin" | "symmetric"}
+pub fn parse_vision(value: &Value) -> Result<Vision, String> {
+    if let Some(radius) = value.as_int() {
+        return Ok(Vision::new(radius as u32));
+    }
+    let map = match value.as_map() {
+        None => return Err(format!("Invalid vision value - {:?}", value)),
+        Some(map) => map,
+    };
+
+    let mut vision = Vision::new(0);
+    for (key, val) in map.iter() {
+        match key.to_string().as_str() {
+            "radius" => match val.as_int() {
+                None => return Err(format!("Invalid vision radius - {:?}", val)),
+                Some(radius) => vision.radius = radius as u32,
+            },
+            "fov" | "calc" => vision.calc = val.to_string().parse()?,
+            other => return Err(format!("Unknown vision field - {}", other)),
+        }
+    }
+    Ok(vision)
+}
+
+/// Recalculates the entity's vision (if it has one and needs it)
+pub fn update_vision(world: &World, entity: Entity) {
+    let origin = match world.read_component::<Position>().get(entity) {
+        None => return,
+        Some(pos) => pos.point(),
+    };
+    let mut visions = world.write_component::<Vision>();
+    if let Some(mut vision) = visions.get_mut(entity) {
+        let map = world.read_resource::<Map>();
+        vision.update(&*map, origin);
+    }
+}
+
+/// Can the viewer see the target?
+/// Beings with a Vision use it, everything else uses a line of sight check.
+pub fn can_see(world: &World, viewer: Entity, target: Entity) -> bool {
+    let (from, to) = {
+        let positions = world.read_component::<Position>();
+        match (positions.get(viewer), positions.get(target)) {
+            (Some(a), Some(b)) => (a.point(), b.point()),
+            _ => return false,
+        }
+    };
+
+    if world.read_component::<Vision>().contains(viewer) {
+        update_vision(world, viewer);
+        return world
+            .read_component::<Vision>()
+            .get(viewer)
+            .unwrap()
+            .can_see(to.x, to.y);
+    }
+
+    has_line_of_sight(world, from, to)
+}
+
+/// Drops all cached vision information if any map tiles have changed (TILE_CHANGED)
+pub fn refresh_vision(world: &mut World) {
+    let changed = match world.try_write_resource::<Map>() {
+        None => return,
+        Some(mut map) => !map.take_tile_changes().is_empty(),
+    };
+    if !changed {
+        return;
+    }
+
+    if let Some(mut cache) = world.try_write_resource::<LosCache>() {
+        cache.clear();
+    }
+    if let Some(mut light_map) = world.try_write_resource::<LightMap>() {
+        light_map.set_dirty();
+    }
+
+    let mut visions = world.write_component::<Vision>();
+    for mut vision in (&mut visions).join() {
+        vision.set_dirty();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tile::Tiles;
+    use gw_ecs::prelude::Builder;
+
+    fn make_world() -> World {
+        let mut world = World::empty(1);
+        world.register::<Position>();
+        world.register::<Vision>();
+
+        let mut map = Map::new(10, 3);
+        let tiles = Tiles::default();
+        map.fill(tiles.get("FLOOR").unwrap());
+        for y in 0..3 {
+            let idx = map.get_wrapped_index(4, y).unwrap();
+            map.reset_tiles(idx, tiles.get("WALL").unwrap());
+        }
+        world.insert_resource(map);
+        world.insert_resource(LosCache::new());
+        world
+    }
+
+    #[test]
+    fn radius_and_walls() {
+        let mut world = make_world();
+
+        let viewer = world
+            .create_entity()
+            .with(Position::new(1, 1))
+            .with(Vision::new(2))
+            .id();
+        let near = world.create_entity().with(Position::new(3, 1)).id();
+        let behind = world.create_entity().with(Position::new(6, 1)).id();
+
+        assert!(can_see(&world, viewer, near));
+        assert!(!can_see(&world, viewer, behind));
+
+        // No vision component - line of sight only
+        assert!(can_see(&world, near, viewer));
+        assert!(!can_see(&world, behind, viewer));
+        assert_eq!(world.read_resource::<LosCache>().len(), 2);
+    }
+
+    #[test]
+    fn asymmetric() {
+        let mut world = make_world();
+
+        let mole = world
+            .create_entity()
+            .with(Position::new(0, 1))
+            .with(Vision::new(1))
+            .id();
+        let hawk = world
+            .create_entity()
+            .with(Position::new(3, 1))
+            .with(Vision::new(8).with_calc(FovCalc::Symmetric))
+            .id();
+
+        assert!(can_see(&world, hawk, mole));
+        assert!(!can_see(&world, mole, hawk));
+    }
+
+    #[test]
+    fn tile_changes() {
+        let mut world = make_world();
+        world.write_resource::<Map>().take_tile_changes();
+
+        let viewer = world
+            .create_entity()
+            .with(Position::new(2, 1))
+            .with(Vision::new(5))
+            .id();
+        let other = world.create_entity().with(Position::new(6, 1)).id();
+
+        assert!(!can_see(&world, viewer, other));
+        assert!(!can_see(&world, other, viewer));
+
+        // Nothing changed
+        refresh_vision(&mut world);
+        assert!(!world
+            .read_component::<Vision>()
+            .get(viewer)
+            .unwrap()
+            .is_dirty());
+        assert_eq!(world.read_resource::<LosCache>().len(), 1);
+
+        {
+            let floor = Tiles::default().get("FLOOR").unwrap();
+            let mut map = world.write_resource::<Map>();
+            let idx = map.get_wrapped_index(4, 1).unwrap();
+            map.reset_tiles(idx, floor);
+        }
+        refresh_vision(&mut world);
+        assert!(world
+            .read_component::<Vision>()
+            .get(viewer)
+            .unwrap()
+            .is_dirty());
+        assert!(world.read_resource::<LosCache>().is_empty());
+
+        assert!(can_see(&world, viewer, other));
+        assert!(can_see(&world, other, viewer));
+    }
+
+    #[test]
+    fn parse() {
+        let vision = parse_vision(&Value::from(4)).unwrap();
+        assert_eq!(vision.radius, 4);
+        assert_eq!(vision.calc, FovCalc::Goblin);
+
+        let mut data = std::collections::HashMap::new();
+        data.insert("radius".into(), Value::from(6));
+        data.insert("fov".into(), Value::from("symmetric"));
+        let vision = parse_vision(&Value::from(data)).unwrap();
+        assert_eq!(vision.radius, 6);
+        assert_eq!(vision.calc, FovCalc::Symmetric);
+
+        assert!(parse_vision(&Value::from("far")).is_err());
+    }
+}
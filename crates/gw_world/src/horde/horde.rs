@@ -69,7 +69,7 @@ impl Debug for Horde {
     }
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct HordeRef(Arc<Horde>);
 
 impl HordeRef {
@@ -2,8 +2,6 @@ use gw_app::log;
 use gw_ecs::prelude::World;
 use gw_util::rng::RandomNumberGenerator;
 
-use crate::being::BeingKind;
-
 use super::{Horde, HordeFlags};
 use std::sync::Arc;
 
@@ -33,16 +31,34 @@ impl Hordes {
     }
 }
 
-/* , forbidden_flags: HordeFlags, required_flags: HordeFlags, summonerKind: String */
-pub fn pick_random_horde(world: &World, depth: u32) -> Option<Arc<Horde>> {
-    //   if (typeof summonerKind == 'string') {
-    //     summonerKind = RUT.Monsters.get(summonerKind);
-    //   }
+/// Picks a random horde for the depth, weighted by frequency
+pub fn pick_random_horde(
+    world: &World,
+    depth: u32,
+    forbidden_flags: HordeFlags,
+    required_flags: HordeFlags,
+) -> Option<Arc<Horde>> {
+    pick_horde(world, depth, |horde| {
+        !horde.flags.intersects(forbidden_flags) && horde.flags.contains(required_flags)
+    })
+}
 
-    let summoner_kind: Option<Arc<BeingKind>> = None;
-    let forbidden_flags = HordeFlags::empty();
-    let required_flags = HordeFlags::empty();
+/// Picks a random horde for a machine (blueprint) - the hordes with the machine id (if given) and the required flags
+pub fn pick_machine_horde(
+    world: &World,
+    depth: u32,
+    machine_id: u32,
+    required_flags: HordeFlags,
+) -> Option<Arc<Horde>> {
+    pick_horde(world, depth, |horde| {
+        (machine_id == 0 || horde.machine_id == machine_id) && horde.flags.contains(required_flags)
+    })
+}
 
+fn pick_horde<F>(world: &World, depth: u32, allowed: F) -> Option<Arc<Horde>>
+where
+    F: Fn(&Arc<Horde>) -> bool,
+{
     let hordes = match world.try_read_global::<Hordes>() {
         None => {
             log("No hordes configured.");
@@ -51,22 +67,11 @@ pub fn pick_random_horde(world: &World, depth: u32) -> Option<Arc<Horde>> {
         Some(h) => h,
     };
 
-    let mut poss_count = 0;
-    for horde in hordes.iter() {
-        if forbidden_flags.is_empty() == false && horde.flags.intersects(forbidden_flags) {
-            continue;
-        }
-        if required_flags.is_empty() == false && !horde.flags.contains(required_flags) {
-            continue;
-        }
-
-        let freq = horde.frequency(depth);
-        if summoner_kind.is_some() {
-            // Make sure summoner == horde.leader_kind
-        } else {
-            poss_count += freq;
-        }
-    }
+    let poss_count: u32 = hordes
+        .iter()
+        .filter(|h| allowed(h))
+        .map(|h| h.frequency(depth))
+        .sum();
 
     if poss_count == 0 {
         log(format!("No hordes found for depth={}", depth));
@@ -74,21 +79,10 @@ pub fn pick_random_horde(world: &World, depth: u32) -> Option<Arc<Horde>> {
     }
 
     let mut rng = world.write_resource::<RandomNumberGenerator>();
-    let mut index = rng.range(1, poss_count as i32) as u32;
-
-    for horde in hordes.iter() {
-        if horde.flags.intersects(forbidden_flags) {
-            continue;
-        }
-        if !horde.flags.contains(required_flags) {
-            continue;
-        }
+    let mut index = rng.range(1, poss_count as i32 + 1) as u32;
 
+    for horde in hordes.iter().filter(|h| allowed(h)) {
         let freq = horde.frequency(depth);
-        if summoner_kind.is_some() {
-            // Make sure summoner == horde.leader_kind
-        }
-
         if index <= freq {
             return Some(Arc::clone(horde));
         }
@@ -97,43 +91,3 @@ pub fn pick_random_horde(world: &World, depth: u32) -> Option<Arc<Horde>> {
 
     None
 }
-
-// // If hordeID is 0, it's randomly assigned based on the depth, with a 10% chance of an out-of-depth spawn from 1-5 levels deeper.
-// // If x is negative, location is random.
-// // Returns a pointer to the leader.
-// pub fn spawn_random(map, blockedFov, forbidden_flags=0, required_flags=0)
-// {
-//   let failsafe;
-//   let horde;
-//   let depth = map.level || 0;
-
-// 	if ((depth > 1) && (RUT.RNG.rollDie(100) < 10)) {
-// 		depth = map.level + RUT.RNG.inRange(1, Math.min(5, Math.round(map.level / 2)));
-// 		if (depth > DEEPEST_LEVEL) {
-// 			depth = DEEPEST_LEVEL; // Math.max(map.level, AMULET_LEVEL);
-// 		}
-//     forbidden_flags |= HordeFlags.HORDE_NEVER_OOD;
-// 	}
-
-// 	horde = RUT.Horde.pick(depth, forbidden_flags, required_flags);
-// 	if (!horde) {
-//     console.log('No qualifying hordes.', depth, forbidden_flags, required_flags);
-// 		return undefined;
-// 	}
-
-//   const xy = RUT.Map.randomXy(map, {
-//     tile: horde.spawnTile,
-//     test: (x, y) => {
-//       const inHallway = (RUT.Map.passableArcCount(map, x, y) > 1);
-//       if (inHallway) return false;
-//       // This is supposed to keep monsters from being spawned in front of the player or near the stairs (at generation time)
-//       const isBlocked = blockedFov && RUT.FOV.canSeeOrSense(blockedFov, x, y);
-//       if (isBlocked) return false;
-
-//       return true;
-//     }
-//   });
-
-//   if (!xy) return undefined;
-//   return RUT.Horde.spawn(horde, map, xy.x, xy.y);
-// }
@@ -0,0 +1,303 @@
+use super::HordeRef;
+use crate::action::dead::kill_being;
+use crate::being::{Being, BeingFlags};
+use crate::combat::being_name;
+use crate::hero::Hero;
+use crate::log::Logger;
+use crate::position::Position;
+use gw_ecs::entity::{EntityMap, MapEntities, MapEntitiesError};
+use gw_ecs::prelude::{Component, Entity, Join, World};
+use serde::{Deserialize, Serialize};
+
+/// How far a follower strays from its leader before heading back
+pub const FOLLOW_DISTANCE: i32 = 2;
+
+/// The leader of a group of followers (the members of a horde or the hero's allies)
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leader {
+    #[serde(skip)]
+    pub target: Option<Entity>, // who the leader is fighting - the followers join in
+}
+
+impl Leader {
+    pub fn new() -> Self {
+        Leader::default()
+    }
+}
+
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Follower {
+    pub leader: Entity,
+    pub bound: bool, // dies when the leader dies
+}
+
+impl Follower {
+    pub fn new(leader: Entity, bound: bool) -> Self {
+        Follower { leader, bound }
+    }
+}
+
+impl MapEntities for Follower {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        self.leader = entity_map.get(self.leader)?;
+        Ok(())
+    }
+}
+
+/// Makes the entity follow the leader
+pub fn join_leader(world: &mut World, follower: Entity, leader: Entity, bound: bool) {
+    let _ = world
+        .write_component::<Follower>()
+        .insert(follower, Follower::new(leader, bound));
+    let mut leaders = world.write_component::<Leader>();
+    if !leaders.contains(leader) {
+        let _ = leaders.insert(leader, Leader::new());
+    }
+}
+
+/// The (living) leader of the entity
+pub fn leader_of(world: &World, entity: Entity) -> Option<Entity> {
+    let leader = world.read_component::<Follower>().get(entity)?.leader;
+    match world.entities().is_alive(leader) {
+        false => None,
+        true => Some(leader),
+    }
+}
+
+pub fn followers_of(world: &World, leader: Entity) -> Vec<Entity> {
+    let followers = world.read_component::<Follower>();
+    (&world.entities(), &followers)
+        .join()
+        .filter(|(_, f)| f.leader == leader)
+        .map(|(e, _)| e)
+        .collect()
+}
+
+/// Remembers who the leader is fighting (does nothing if the entity is not a leader)
+pub fn set_leader_target(world: &World, leader: Entity, target: Entity) {
+    if let Some(mut info) = world.write_component::<Leader>().get_mut(leader) {
+        info.target = Some(target);
+    }
+}
+
+/// The enemy that the entity's leader is fighting (if any)
+pub fn leader_target(world: &World, entity: Entity) -> Option<Entity> {
+    let leader = leader_of(world, entity)?;
+    let target = world.read_component::<Leader>().get(leader)?.target?;
+    if !world.entities().is_alive(target) || !world.read_component::<Position>().contains(target) {
+        return None;
+    }
+    let destroyed = world
+        .read_component::<Being>()
+        .get(target)
+        .map(|b| b.has_flag(BeingFlags::DESTROYED))
+        .unwrap_or(true);
+    match !destroyed && are_enemies(world, entity, target) {
+        false => None,
+        true => Some(target),
+    }
+}
+
+/// Are the beings on opposite sides (the hero and allies vs everyone else)?  Captives do not fight.
+pub fn are_enemies(world: &World, a: Entity, b: Entity) -> bool {
+    let hero = world.read_resource::<Hero>().entity;
+    let beings = world.read_component::<Being>();
+    let side = |entity: Entity| {
+        let being = beings.get(entity)?;
+        match being.has_flag(BeingFlags::CAPTIVE) {
+            true => None,
+            false => Some(entity == hero || being.has_flag(BeingFlags::ALLY)),
+        }
+    };
+    match (side(a), side(b)) {
+        (Some(a), Some(b)) => a != b,
+        _ => false,
+    }
+}
+
+/// Handles the followers when their leader leaves (dies or is freed from captivity).
+/// Bound followers die with their leader, the rest elect the closest one as the new leader.
+pub fn release_followers(world: &mut World, leader: Entity, leader_died: bool) {
+    let followers = followers_of(world, leader);
+    let old_leader = world.write_component::<Leader>().remove(leader);
+    if followers.is_empty() {
+        return;
+    }
+
+    let mut survivors = Vec::new();
+    for entity in followers {
+        let bound = world
+            .read_component::<Follower>()
+            .get(entity)
+            .unwrap()
+            .bound;
+        if bound && leader_died {
+            let msg = format!("{} dies", being_name(world, entity));
+            world.write_global::<Logger>().log_combat(msg, true);
+            kill_being(world, entity);
+        } else {
+            survivors.push(entity);
+        }
+    }
+
+    let leader_point = world
+        .read_component::<Position>()
+        .get(leader)
+        .map(|p| p.point());
+    if let Some(leader_point) = leader_point {
+        let positions = world.read_component::<Position>();
+        survivors.sort_by_key(|e| match positions.get(*e) {
+            None => i32::MAX,
+            Some(pos) => {
+                let pt = pos.point();
+                (pt.x - leader_point.x)
+                    .abs()
+                    .max((pt.y - leader_point.y).abs())
+            }
+        });
+    }
+
+    let new_leader = match survivors.first() {
+        None => return,
+        Some(entity) => *entity,
+    };
+
+    // The new leader takes the old leader's place in the horde (and follows whoever it followed)
+    {
+        let mut followers = world.write_component::<Follower>();
+        followers.remove(new_leader);
+        if let Some(follows) = followers.get(leader).map(|f| f.clone()) {
+            let _ = followers.insert(new_leader, follows);
+        }
+        for entity in survivors.iter().skip(1) {
+            if let Some(mut follower) = followers.get_mut(*entity) {
+                follower.leader = new_leader;
+            }
+        }
+    }
+
+    if survivors.len() > 1 {
+        let info = old_leader.unwrap_or_default();
+        let _ = world.write_component::<Leader>().insert(new_leader, info);
+    }
+
+    let horde = world
+        .read_component::<HordeRef>()
+        .get(leader)
+        .map(|h| h.clone());
+    if let Some(horde) = horde {
+        let _ = world
+            .write_component::<HordeRef>()
+            .insert(new_leader, horde);
+    }
+}
+
+/// Frees the captive - it becomes an ally that follows the liberator.  Its guards pick a new leader.
+pub fn free_captive(world: &mut World, captive: Entity, liberator: Entity) -> bool {
+    {
+        let mut beings = world.write_component::<Being>();
+        let mut being = match beings.get_mut(captive) {
+            None => return false,
+            Some(being) => being,
+        };
+        if !being.has_flag(BeingFlags::CAPTIVE) {
+            return false;
+        }
+        being.clear_flag(BeingFlags::CAPTIVE);
+        being.set_flag(BeingFlags::ALLY);
+    }
+
+    release_followers(world, captive, false);
+    join_leader(world, captive, liberator, false);
+
+    let msg = format!(
+        "{} frees {}",
+        being_name(world, liberator),
+        being_name(world, captive)
+    );
+    world.write_global::<Logger>().log(msg);
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::action::move_step::MoveStepAction;
+    use crate::action::{Action, ActionResult};
+    use crate::being::{Stat, Stats};
+    use crate::test_util::make_ecs;
+
+    fn make_being(world: &mut World, name: &str, x: i32) -> Entity {
+        let mut being = Being::new(name.to_string());
+        being.name = Some(name.to_string());
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 5);
+
+        world.spawn((being, stats, Position::new(x, 1)))
+    }
+
+    #[test]
+    fn leader_succession() {
+        let mut ecs = make_ecs(7, 3);
+        let world = ecs.current_world_mut();
+
+        let leader = make_being(world, "leader", 1);
+        let guard = make_being(world, "guard", 2);
+        let near = make_being(world, "near", 3);
+        let scout = make_being(world, "scout", 5);
+        join_leader(world, guard, leader, true);
+        join_leader(world, near, leader, false);
+        join_leader(world, scout, leader, false);
+        assert_eq!(followers_of(world, leader).len(), 3);
+
+        kill_being(world, leader);
+
+        assert!(!world.entities().is_alive(guard));
+        assert!(world.read_component::<Leader>().contains(near));
+        assert!(leader_of(world, near).is_none());
+        assert_eq!(leader_of(world, scout), Some(near));
+    }
+
+    #[test]
+    fn freed_captive_becomes_ally() {
+        let mut ecs = make_ecs(7, 3);
+        let world = ecs.current_world_mut();
+
+        let hero = make_being(world, "hero", 0);
+        world.insert_resource(Hero::new(hero));
+        let captive = make_being(world, "captive", 1);
+        world
+            .write_component::<Being>()
+            .get_mut(captive)
+            .unwrap()
+            .set_flag(BeingFlags::CAPTIVE);
+        let guard = make_being(world, "guard", 2);
+        join_leader(world, guard, captive, true);
+
+        assert!(!are_enemies(world, hero, captive));
+        assert!(are_enemies(world, hero, guard));
+
+        let mut result = MoveStepAction::new(hero, 1, 0).execute(world);
+        while let ActionResult::Replace(mut next) = result {
+            result = next.execute(world);
+        }
+        assert!(matches!(result, ActionResult::Done(_)));
+
+        {
+            let beings = world.read_component::<Being>();
+            let being = beings.get(captive).unwrap();
+            assert!(being.has_flag(BeingFlags::ALLY));
+            assert!(!being.has_flag(BeingFlags::CAPTIVE));
+        }
+        assert_eq!(leader_of(world, captive), Some(hero));
+        assert!(world.read_component::<Leader>().contains(hero));
+        // The guard is on its own now (and did not die)
+        assert!(world.entities().is_alive(guard));
+        assert!(leader_of(world, guard).is_none());
+        assert!(are_enemies(world, captive, guard));
+
+        let mut logger = world.write_global::<Logger>();
+        let msgs: Vec<String> = logger.iter().map(|m| m.msg.clone()).collect();
+        assert!(msgs.contains(&"hero frees captive".to_string()));
+    }
+}
@@ -57,8 +57,15 @@ pub fn load_horde_data(dest: &mut Hordes, beings: &BeingKinds, data: Value) -> R
 
         let mut builder = HordeBuilder::new(leader_being);
 
+        // members first so that the counts apply to them
+        if let Some(members) = data_table.get(&"members".into()) {
+            if let Err(e) = set_field(&mut builder, "members", members, beings) {
+                return Err(format!("Error processing horde - {:?}", e));
+            }
+        }
+
         for (key, value) in data_table.iter() {
-            if key.as_str().unwrap() == "leader" {
+            if matches!(key.as_str().unwrap(), "leader" | "members") {
                 continue;
             }
             if let Err(e) = set_field(&mut builder, &key.to_string(), value, beings) {
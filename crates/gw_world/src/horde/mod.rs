@@ -2,6 +2,7 @@ mod builder;
 mod horde;
 mod horde_flags;
 mod hordes;
+mod leader;
 mod load;
 mod spawn;
 
@@ -9,5 +10,6 @@ pub use builder::*;
 pub use horde::*;
 pub use horde_flags::*;
 pub use hordes::*;
+pub use leader::*;
 pub use load::*;
 pub use spawn::*;
@@ -1,11 +1,18 @@
-use super::{Horde, HordeRef};
+use super::{join_leader, pick_random_horde, Horde, HordeRef};
+use crate::being::{spawn_being, Being, BeingFlags, Stat, Stats};
+use crate::fov::{has_line_of_sight, Vision, FOV};
+use crate::hero::Hero;
 use crate::horde::HordeFlags;
+use crate::map::{Cell, Map};
+use crate::position::Position;
+use crate::task::Executor;
 use gw_app::log;
-use gw_ecs::prelude::{Component, Entity, World};
+use gw_ecs::prelude::{Component, Entity, Join, World};
 use gw_util::point::Point;
+use gw_util::rng::RandomNumberGenerator;
 use gw_util::value::{Key, Value};
 use std::cmp::{Eq, PartialEq};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -188,150 +195,429 @@ impl Deref for SpawnRef {
 // B) Otherwise, the map is automatically created from the tiles around the horde and the attacker (the hero)
 //
 
-// pub fn spawn_horde(horde: &Arc<Horde>, world: &mut World, point: Point) -> Entity {
-//     // 	if (horde.machine) {
-//     // 		// Build the accompanying machine (e.g. a goblin encampment)
-//     // 		RUT.Map.Blueprint.build(horde.machine, map, x, y);
-//     // 	}
+/// The chance (out of 100) that a random horde comes from deeper in the dungeon
+pub const OUT_OF_DEPTH_CHANCE: u32 = 10;
 
-//     let leader_entity = spawn_being(&horde.leader, world, point);
+/// How far (in steps) from the leader the members can be placed
+const MEMBER_SPAWN_DISTANCE: i32 = 20;
 
-//     let _ = world
-//         .write_component()
-//         .insert(leader_entity, HordeRef::new(Arc::clone(horde)));
-
-//     if horde.flags.intersects(HordeFlags::SPAWN_AS_AVATAR) {
-//         log(format!(
-//             "Spawn Horde Avatar - {} @ {:?}",
-//             horde.leader.id, point
-//         ));
-//         return leader_entity;
-//     }
-
-//     // if horde.flags.intersects(HordeFlags.HORDE_LEADER_CAPTIVE) {
-
-//     // 	leader.state |= BeingState.BS_CAPTIVE;
-//     // 	leader.state |= BeingState.BS_WANDERING;
-//     // 	leader.stats.set('health', Math.round(leader.stats.max.health / 4) + 1);  // captives are injured
-
-//     // 	// Draw the manacles unless the horde spawns in weird terrain (e.g. cages).
-//     // 	if (!horde.spawnTile) {
-//     // 		RUT.Map.Decorators.manacles(map, x, y);
-//     // 	}
-//     // } else if (horde.flags & HordeFlags.HORDE_ALLIED_WITH_PLAYER) {
-//     // 	RUT.Monster.becomeAllyWith(leader);
-//     // }
-
-//     // 	if (RUT.Monster.canSubmergeNow(leader)) {
-//     // 		leader.state |= BeingState.BS_SUBMERGED;
-//     // 	}
-
-//     // 	spawn_minions(horde, leader_entity, point, false);
-
-//     panic!("Only support spawn as avatar right now");
-// }
-
-// pub fn populate_generic_spawn_map(map, spawnMap, originX, originY, maxDist, blockingTileFlags, blockingCellFlags)
-// {
-//   RUT.Grid.fill(spawnMap, 30000);
-
-//   maxDist = maxDist || spawnMap.width * spawnMap.height;
-
-//   function travelToCell(i, j, cost) {
-//     const xy = { x: i, y: j };
-
-//     if (cost > maxDist) return;
-
-//     if (!RUT.Map.makeValidXy(map, xy)) return;
-//     const cell = RUT.Map.getCell(map, xy.x, xy.y);
-//     if (!cell) return;
-//     if (spawnMap[xy.x][xy.y] <= cost) return;
-
-//     // console.log('travel to cell', i, j, cost);
-
-//     if (xy.x == originX && xy.y == originY) {
-//       spawnMap[xy.x][xy.y] = 0;
-//     }
-//     else if (RUT.Cell.hasTileFlag(cell, blockingTileFlags) || RUT.Cell.hasFlag(cell, blockingCellFlags)) {
-//       spawnMap[xy.x][xy.y] = PDS_FORBIDDEN;
-//       return;
-//     }
-
-//     spawnMap[xy.x][xy.y] = cost;
-//     travelToCell(xy.x + 1, xy.y, cost + 1);
-//     travelToCell(xy.x - 1, xy.y, cost + 1);
-//     travelToCell(xy.x, xy.y + 1, cost + 1);
-//     travelToCell(xy.x, xy.y - 1, cost + 1);
-//   }
-
-//   travelToCell(originX, originY, 0);
-//   RUT.Grid.findReplace(spawnMap, 30000, 30000, PDS_FORBIDDEN);
-// }
-
-// pub fn spawn_minions(horde, leader, leader_point, summoned=false) {
-// 	let atLeastOneMinion = false;
-
-//   const map = leader.map;
-// 	const x = leader.xy.x;
-// 	const y = leader.xy.y;
-
-//   const spawnMap = RUT.Grid.alloc(map.width, map.height);
-
-//   // console.log('spawnMinions', horde.members, horde.counts);
-
-// 	for (let iSpecies = 0; iSpecies < horde.members.length; iSpecies++) {
-// 		let count = RUT.Calc.calc(horde.counts[iSpecies]);
-//     const memberType = RUT.Monsters.get(horde.members[iSpecies]);
-
-// 		let forbiddenTileFlags = RUT.Monster.forbiddenTileFlags(memberType);
-// 		if (horde.spawnTile) {
-//       const tile = RUT.Tiles.get(horde.spawnTile);
-// 			forbiddenTileFlags &= ~(tile.flags);
-// 		}
-
-//     const spawnTile = RUT.Tiles.get(horde.spawnTile);
-//     RUT.Horde.populateGenericSpawnMap(map, spawnMap, x, y, 20, TileFlags.T_DIVIDES_LEVEL & forbiddenTileFlags, CellFlags.HAS_PLAYER | CellFlags.HAS_STAIRS);
-
-// 		for (let iMember = 0; iMember < count; iMember++) {
-// 			let failsafe = 0;
-//       let xy;
-// 			do {
-//         xy = RUT.Map.getQualifyingLocNear(map, x, y,
-//                 summoned, // hallwaysAllowed
-//                 spawnMap, // okMap
-//                 forbiddenTileFlags,   // forbiddenTileFlags
-//                 CellFlags.HAS_MONSTER,   // forbiddenCellFlags
-//                 false);  // deterministic
-// 			} while (spawnTile && (!RUT.Map.cellHasTile(map, xy.x, xy.y, spawnTile)) && failsafe++ < 20);
-// 			if (failsafe >= 20) {
-// 				// abort
-// 				break;
-// 			}
-
-//       const monst = RUT.Monster.create(memberType); // , true, !summoned);
-// 			if (RUT.Monster.canSubmergeNow(monst)) {
-// 				monst.state |= BeingState.BS_SUBMERGED;
-// 			}
-//       RUT.Map.addBeing(map, monst, xy.x, xy.y);
-
-// 			monst.state |= (BeingState.BS_FOLLOWER | BeingState.BS_JUST_SUMMONED);
-// 			monst.leader = leader;
-// 			monst.state |= (leader.state & BeingState.BS_AI_STATES);
-// 			// monst->mapToMe = NULL;
-// 			if (horde.flags & HordeFlags.HORDE_DIES_ON_LEADER_DEATH) {
-// 				monst.state |= BeingState.BS_BOUND_TO_LEADER;
-// 			}
-// 			if (horde.flags & HordeFlags.HORDE_ALLIED_WITH_PLAYER) {
-// 				RUT.Monster.becomeAllyWith(monst);
-// 			}
-// 			atLeastOneMinion = true;
-// 		}
-// 	}
-
-// 	if (atLeastOneMinion && !(horde.flags & HordeFlags.HORDE_DIES_ON_LEADER_DEATH)) {
-// 		leader.state |= BeingState.BS_LEADER;
-// 	}
-
-//   RUT.Grid.free(spawnMap);
-// 	return atLeastOneMinion;
-// }
+/// Spawns the horde leader at the point along with all of the members (unless it is an avatar horde).
+/// Returns the leader.
+pub fn spawn_horde(horde: &Arc<Horde>, world: &mut World, point: Point) -> Entity {
+    let leader_entity = spawn_being(&horde.leader, world, point);
+
+    let _ = world
+        .write_component()
+        .insert(leader_entity, HordeRef::new(Arc::clone(horde)));
+
+    if horde.flags.intersects(HordeFlags::SPAWN_AS_AVATAR) {
+        log(format!(
+            "Spawn Horde Avatar - {} @ {:?}",
+            horde.leader.id, point
+        ));
+        return leader_entity;
+    }
+
+    if horde.flags.contains(HordeFlags::LEADER_CAPTIVE) {
+        // captives are in chains and injured
+        if let Some(mut being) = world.write_component::<Being>().get_mut(leader_entity) {
+            being.set_flag(BeingFlags::CAPTIVE);
+        }
+        if let Some(mut stats) = world.write_component::<Stats>().get_mut(leader_entity) {
+            if let (Some(health), Some(max)) =
+                (stats.get(Stat::HEALTH), stats.get_max(Stat::HEALTH))
+            {
+                stats.update(Stat::HEALTH, max / 4 + 1 - health);
+            }
+        }
+    }
+
+    let members = spawn_members(horde, world, point);
+    let bound = horde.flags.contains(HordeFlags::DIES_ON_LEADER_DEATH);
+    for member in members.iter() {
+        join_leader(world, *member, leader_entity, bound);
+    }
+
+    if horde.flags.contains(HordeFlags::ALLIED_WITH_PLAYER) {
+        {
+            let mut beings = world.write_component::<Being>();
+            for entity in members.iter().chain(std::iter::once(&leader_entity)) {
+                if let Some(mut being) = beings.get_mut(*entity) {
+                    being.set_flag(BeingFlags::ALLY);
+                }
+            }
+        }
+        let hero = world.read_resource::<Hero>().entity;
+        if world.entities().is_alive(hero) {
+            join_leader(world, leader_entity, hero, false);
+        }
+    }
+
+    leader_entity
+}
+
+/// Places the members of the horde around the leader - using the MEMBERS_IN_FRONT, MEMBERS_BEHIND and MEMBERS_LOOSE flags
+pub fn spawn_members(horde: &Arc<Horde>, world: &mut World, leader_point: Point) -> Vec<Entity> {
+    let mut members = Vec::new();
+    if horde.members.is_empty() {
+        return members;
+    }
+
+    let distances = spawn_distances(&world.read_resource::<Map>(), leader_point);
+    let hero_point = hero_point(world);
+    let mut placed = vec![leader_point];
+
+    for (kind, count) in horde.members.iter() {
+        for _ in 0..*count {
+            let point = match pick_member_point(world, horde, &distances, hero_point, &placed) {
+                None => return members,
+                Some(point) => point,
+            };
+            let entity = spawn_being(kind, world, point);
+            placed.push(point);
+            members.push(entity);
+        }
+    }
+
+    members
+}
+
+/// Walking distance from the origin (up to MEMBER_SPAWN_DISTANCE) for all of the reachable cells
+fn spawn_distances(map: &Map, origin: Point) -> HashMap<usize, i32> {
+    let mut distances = HashMap::new();
+    let start = match map.get_index(origin.x, origin.y) {
+        None => return distances,
+        Some(idx) => idx,
+    };
+    distances.insert(start, 0);
+
+    let mut todo = VecDeque::new();
+    todo.push_back((origin, 0));
+    while let Some((point, dist)) = todo.pop_front() {
+        if dist >= MEMBER_SPAWN_DISTANCE {
+            continue;
+        }
+        for (x, y, _) in point.neighbors(true) {
+            let idx = match map.get_index(x, y) {
+                None => continue,
+                Some(idx) => idx,
+            };
+            if distances.contains_key(&idx) || map.get_cell(idx).unwrap().blocks() {
+                continue;
+            }
+            distances.insert(idx, dist + 1);
+            todo.push_back((Point::new(x, y), dist + 1));
+        }
+    }
+
+    distances
+}
+
+fn pick_member_point(
+    world: &World,
+    horde: &Horde,
+    distances: &HashMap<usize, i32>,
+    hero_point: Option<Point>,
+    placed: &[Point],
+) -> Option<Point> {
+    let map = world.read_resource::<Map>();
+    let leader_point = placed[0];
+
+    let mut open: Vec<(usize, i32)> = distances
+        .iter()
+        .filter(|(idx, dist)| {
+            **dist > 0
+                && !map.has_blocker(**idx)
+                && matches_spawn_tile(&map, **idx, &horde.spawn_tile)
+        })
+        .map(|(idx, dist)| (*idx, *dist))
+        .collect();
+    open.sort();
+
+    let leader_hero_dist = hero_point.map(|hero| leader_point.distance(&hero));
+    let fits = |idx: usize| {
+        let point = map.to_point(idx);
+        if horde.flags.contains(HordeFlags::MEMBERS_LOOSE)
+            && placed
+                .iter()
+                .any(|p| (p.x - point.x).abs() <= 1 && (p.y - point.y).abs() <= 1)
+        {
+            return false;
+        }
+        if let (Some(hero), Some(leader_dist)) = (hero_point, leader_hero_dist) {
+            let dist = point.distance(&hero);
+            if horde.flags.contains(HordeFlags::MEMBERS_IN_FRONT) && dist >= leader_dist {
+                return false;
+            }
+            if horde.flags.contains(HordeFlags::MEMBERS_BEHIND) && dist <= leader_dist {
+                return false;
+            }
+        }
+        true
+    };
+
+    // Closest spot that fits the flags (falling back to any open spot)
+    let mut choices: Vec<(usize, i32)> =
+        open.iter().filter(|(idx, _)| fits(*idx)).copied().collect();
+    if choices.is_empty() {
+        choices = open;
+    }
+    let closest = choices.iter().map(|(_, dist)| *dist).min()?;
+    choices.retain(|(_, dist)| *dist == closest);
+
+    let mut rng = world.write_resource::<RandomNumberGenerator>();
+    let (idx, _) = rng.random_slice_entry(&choices)?;
+    Some(map.to_point(*idx))
+}
+
+fn matches_spawn_tile(map: &Map, idx: usize, spawn_tile: &Option<String>) -> bool {
+    match spawn_tile {
+        None => true,
+        Some(id) => {
+            map.ground[idx].id == *id
+                || map.fixture[idx].id == *id
+                || (map.liquid_volume(idx) > 0 && map.liquid[idx].id == *id)
+        }
+    }
+}
+
+fn hero_point(world: &World) -> Option<Point> {
+    let hero = world.read_resource::<Hero>().entity;
+    world
+        .read_component::<Position>()
+        .get(hero)
+        .map(|pos| pos.point())
+}
+
+/// Can the hero see (or sense) the point?
+fn hero_can_see(world: &World, point: Point) -> bool {
+    if let Some(fov) = world.try_read_resource::<FOV>() {
+        return fov.is_visible(point.x, point.y);
+    }
+    let hero = world.read_resource::<Hero>().entity;
+    if let Some(vision) = world.read_component::<Vision>().get(hero) {
+        return vision.can_see(point.x, point.y);
+    }
+    match hero_point(world) {
+        None => false,
+        Some(hero_point) => has_line_of_sight(world, hero_point, point),
+    }
+}
+
+/// A random spot for the horde - on its spawn tile, out of hallways and (if blocked_fov) out of the hero's sight
+pub fn random_horde_point(world: &World, horde: &Horde, blocked_fov: bool) -> Option<Point> {
+    let choices: Vec<usize> = {
+        let map = world.read_resource::<Map>();
+        (0..map.ground.len())
+            .filter(|idx| {
+                let (x, y) = map.to_xy(*idx);
+                !map.has_blocker(*idx)
+                    && !map.get_cell(*idx).unwrap().blocks()
+                    && matches_spawn_tile(&map, *idx, &horde.spawn_tile)
+                    && map.passable_arc_count(x, y) <= 1
+            })
+            .collect()
+    };
+
+    let choices: Vec<usize> = match blocked_fov {
+        false => choices,
+        true => {
+            let map = world.read_resource::<Map>();
+            choices
+                .into_iter()
+                .filter(|idx| !hero_can_see(world, map.to_point(*idx)))
+                .collect()
+        }
+    };
+
+    let mut rng = world.write_resource::<RandomNumberGenerator>();
+    let idx = rng.random_slice_entry(&choices)?;
+    Some(world.read_resource::<Map>().to_point(*idx))
+}
+
+/// Picks and spawns a random horde for the depth (with a chance of an out of depth horde).
+/// If blocked_fov is set, the horde will not be placed where the hero can see it.
+/// Returns the leader.
+pub fn spawn_random_horde(
+    world: &mut World,
+    depth: u32,
+    blocked_fov: bool,
+    forbidden_flags: HordeFlags,
+    required_flags: HordeFlags,
+) -> Option<Entity> {
+    let mut depth = depth;
+    let mut forbidden_flags = forbidden_flags;
+    {
+        let mut rng = world.write_resource::<RandomNumberGenerator>();
+        if depth > 1 && rng.chance(OUT_OF_DEPTH_CHANCE) {
+            let max_delta = 5.min(depth.div_ceil(2)) as i32;
+            depth += rng.range(1, max_delta + 1) as u32;
+            forbidden_flags |= HordeFlags::NEVER_OOD;
+        }
+    }
+
+    let horde = match pick_random_horde(world, depth, forbidden_flags, required_flags) {
+        None => {
+            log(format!(
+                "No qualifying hordes - depth={}, forbidden={:?}, required={:?}",
+                depth, forbidden_flags, required_flags
+            ));
+            return None;
+        }
+        Some(horde) => horde,
+    };
+
+    let point = random_horde_point(world, &horde, blocked_fov)?;
+    Some(spawn_horde(&horde, world, point))
+}
+
+/// Periodically spawns a new horde (out of the hero's sight) if the level has a HordeSpawner and there are
+/// not already too many alive.  Returns the new leader.
+pub fn spawn_periodic_horde(world: &mut World, depth: u32) -> Option<Entity> {
+    let current_time = world.read_resource::<Executor>().time();
+
+    let (max_alive, spawn_id) = {
+        let mut info = world.try_write_resource::<HordeSpawner>()?;
+        if info.next_time > current_time {
+            return None;
+        }
+        info.next_time = current_time + info.check_delay;
+        (info.max_alive, info.id.clone())
+    };
+
+    let count = world
+        .read_component::<SpawnRef>()
+        .join()
+        .filter(|s| s.is(&spawn_id))
+        .count() as u32;
+    if count >= max_alive {
+        return None;
+    }
+
+    let leader = spawn_random_horde(
+        world,
+        depth,
+        true,
+        HordeFlags::AVOID_SPAWN,
+        HordeFlags::empty(),
+    )?;
+    let _ = world
+        .write_component()
+        .insert(leader, SpawnRef::new(spawn_id));
+    Some(leader)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::{BeingKind, BeingKinds};
+    use crate::horde::{load_horde_data, Hordes};
+    use crate::test_util::{add_test_world, make_empty_ecs};
+    use crate::tile::Tiles;
+    use gw_ecs::prelude::Ecs;
+
+    fn make_ecs(hordes: &str) -> Ecs {
+        let mut ecs = make_empty_ecs();
+        {
+            let mut kinds = ecs.write_global::<BeingKinds>();
+            kinds.insert(BeingKind::builder("ORC").build());
+            kinds.insert(BeingKind::builder("RAT").build());
+            let mut hero = BeingKind::builder("HERO");
+            hero.hero();
+            kinds.insert(hero.build());
+        }
+        let value = gw_util::json::parse_string(hordes).unwrap();
+        let mut all = Hordes::new();
+        load_horde_data(&mut all, &ecs.read_global::<BeingKinds>(), value).unwrap();
+        ecs.insert_global(all);
+
+        let world = add_test_world(&mut ecs, 9, 9);
+        let wall = world.read_global::<Tiles>().get("WALL").unwrap();
+        {
+            let mut map = world.write_resource::<Map>();
+            for y in 0..9 {
+                let idx = map.get_index(4, y).unwrap();
+                map.force_ground(idx, wall.clone());
+            }
+        }
+
+        let hero = world.read_global::<BeingKinds>().get("HERO").unwrap();
+        spawn_being(&hero, world, Point::new(1, 4));
+        ecs
+    }
+
+    #[test]
+    fn members_spawn_behind_leader() {
+        let mut ecs = make_ecs(
+            r#"[{ "leader": "ORC", "frequency": 100, "members": ["RAT"], "counts": 3, "flags": "MEMBERS_BEHIND" }]"#,
+        );
+        let world = ecs.current_world_mut();
+        let horde = world.read_global::<Hordes>().iter().next().unwrap().clone();
+
+        let leader = spawn_horde(&horde, world, Point::new(6, 4));
+        assert!(world
+            .read_component::<HordeRef>()
+            .get(leader)
+            .unwrap()
+            .is(&horde));
+
+        let hero_pt = Point::new(1, 4);
+        let leader_dist = Point::new(6, 4).distance(&hero_pt);
+        let positions = world.read_component::<Position>();
+        let rats: Vec<Point> = (&world.entities(), &positions)
+            .join()
+            .filter(|(e, _)| *e != leader && *e != world.read_resource::<Hero>().entity)
+            .map(|(_, pos)| pos.point())
+            .collect();
+        assert_eq!(rats.len(), 3);
+        assert!(rats.iter().all(|pt| pt.distance(&hero_pt) > leader_dist));
+        assert_eq!(world.read_resource::<Executor>().len(), 5);
+    }
+
+    #[test]
+    fn random_horde_out_of_sight() {
+        let mut ecs = make_ecs(r#"[{ "leader": "ORC", "frequency": 100 }]"#);
+        let world = ecs.current_world_mut();
+
+        for _ in 0..10 {
+            let leader =
+                spawn_random_horde(world, 1, true, HordeFlags::empty(), HordeFlags::empty())
+                    .unwrap();
+            let pos = world
+                .read_component::<Position>()
+                .get(leader)
+                .unwrap()
+                .point();
+            assert!(pos.x > 4);
+        }
+    }
+
+    #[test]
+    fn periodic_spawn() {
+        let mut ecs = make_ecs(
+            r#"[{ "leader": "ORC", "frequency": 100, "flags": "NO_PERIODIC_SPAWN" }, { "leader": "RAT", "frequency": 100 }]"#,
+        );
+        let world = ecs.current_world_mut();
+        let mut spawner = HordeSpawner::new();
+        spawner.max_alive = 1;
+        world.insert_resource(spawner);
+
+        let leader = spawn_periodic_horde(world, 1).unwrap();
+        assert_eq!(
+            world
+                .read_component::<HordeRef>()
+                .get(leader)
+                .unwrap()
+                .leader
+                .id,
+            "RAT"
+        );
+
+        // Not time yet
+        assert!(spawn_periodic_horde(world, 1).is_none());
+
+        // Too many alive
+        world.write_resource::<HordeSpawner>().next_time = 0;
+        assert!(spawn_periodic_horde(world, 1).is_none());
+    }
+}
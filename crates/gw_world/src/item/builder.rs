@@ -0,0 +1,209 @@
+use super::{EquipSlot, Item, ItemKind};
+use crate::being::BuilderError;
+use crate::combat::{parse_melee, Melee};
+use crate::effect::{parse_effects, BoxedEffect};
+use crate::sprite::{Sprite, SpriteParseError};
+use gw_app::{Glyph, RGBA};
+use gw_util::value::Value;
+use std::sync::Arc;
+
+pub struct ItemKindBuilder {
+    pub(super) id: String,
+    pub(super) sprite: Sprite,
+    pub(super) item: Item,
+    pub(super) use_effects: Vec<BoxedEffect>,
+    pub(super) melee: Option<Melee>,
+}
+
+impl ItemKindBuilder {
+    pub(super) fn new(id: &str) -> Self {
+        ItemKindBuilder {
+            id: id.to_string(),
+            sprite: Sprite::default(),
+            item: Item::new(id.to_string()),
+            use_effects: Vec::new(),
+            melee: None,
+        }
+    }
+
+    /// need to call this first
+    pub fn extend(&mut self, kind: &Arc<ItemKind>) -> &mut Self {
+        self.sprite = kind.sprite.clone();
+        self.item = kind.item.clone();
+        self.item.id = self.id.clone();
+        self.use_effects = kind.use_effects.clone();
+        self.melee = kind.melee.clone();
+        self
+    }
+
+    pub fn glyph(&mut self, glyph: Glyph) -> &mut Self {
+        self.sprite.glyph = glyph;
+        self
+    }
+
+    pub fn fg(&mut self, fg: RGBA) -> &mut Self {
+        self.sprite.fg = fg;
+        self
+    }
+
+    pub fn bg(&mut self, bg: RGBA) -> &mut Self {
+        self.sprite.bg = bg;
+        self
+    }
+
+    pub fn sprite(&mut self, sprite: Sprite) -> &mut Self {
+        self.sprite = sprite;
+        self
+    }
+
+    pub fn name(&mut self, name: &str) -> &mut Self {
+        self.item.name = Some(name.to_string());
+        self
+    }
+
+    pub fn flavor(&mut self, flavor: &str) -> &mut Self {
+        self.item.flavor = Some(flavor.to_string());
+        self
+    }
+
+    pub fn description(&mut self, description: &str) -> &mut Self {
+        self.item.description = Some(description.to_string());
+        self
+    }
+
+    pub fn slot(&mut self, slot: EquipSlot) -> &mut Self {
+        self.item.slot = Some(slot);
+        self
+    }
+
+    pub fn consumable(&mut self, consumable: bool) -> &mut Self {
+        self.item.consumable = consumable;
+        self
+    }
+
+    pub fn use_effect(&mut self, effect: BoxedEffect) -> &mut Self {
+        self.use_effects.push(effect);
+        self
+    }
+
+    pub fn melee(&mut self, melee: Melee) -> &mut Self {
+        self.melee = Some(melee);
+        self
+    }
+
+    pub fn build(self) -> Arc<ItemKind> {
+        Arc::new(ItemKind::new(self))
+    }
+}
+
+/*
+   JSON format:
+   "ID": {
+       "sprite": "<SPRITE_CONFIG>",
+       --or--
+       "glyph" | "ch": "ch" || ###,
+       "fg": "<RGBA_CONFIG>",
+       "bg": "<RGBA_CONFIG>",
+
+       "name": <STRING>,
+       "flavor": <STRING>,
+       "description": <STRING>,
+
+       "slot": "WEAPON" | "ARMOR" | ...,
+       "melee": <MELEE_CONFIG>,    // used when equipped as a weapon
+       "use": <EFFECTS_CONFIG>,
+       "consumable": <BOOL>        // used up when used
+   }
+*/
+
+pub fn set_field(
+    builder: &mut ItemKindBuilder,
+    field: &str,
+    value: &Value,
+) -> Result<(), BuilderError> {
+    match field {
+        "sprite" => {
+            let sprite: Sprite = match value.try_into() {
+                Err(e) => return Err(BuilderError::BadSprite(e)),
+                Ok(s) => s,
+            };
+            builder.sprite(sprite);
+            Ok(())
+        }
+        "glyph" | "ch" => {
+            if value.is_int() {
+                builder.glyph(value.as_int().unwrap() as Glyph);
+                Ok(())
+            } else {
+                let text = value.to_string();
+                match text.chars().next() {
+                    None => Err(BuilderError::BadSprite(SpriteParseError::BadGlyph(text))),
+                    Some(ch) => {
+                        builder.glyph(ch as Glyph);
+                        Ok(())
+                    }
+                }
+            }
+        }
+        "fg" => match value.try_into() {
+            Err(e) => Err(BuilderError::BadSprite(SpriteParseError::BadForeColor(e))),
+            Ok(c) => {
+                builder.fg(c);
+                Ok(())
+            }
+        },
+        "bg" => match value.try_into() {
+            Err(e) => Err(BuilderError::BadSprite(SpriteParseError::BadBackColor(e))),
+            Ok(c) => {
+                builder.bg(c);
+                Ok(())
+            }
+        },
+        "name" => {
+            builder.name(&value.to_string());
+            Ok(())
+        }
+        "flavor" => {
+            builder.flavor(&value.to_string());
+            Ok(())
+        }
+        "description" => {
+            builder.description(&value.to_string());
+            Ok(())
+        }
+        "slot" => match value.to_string().parse() {
+            Err(_) => Err(BuilderError::BadField("slot".to_string(), value.clone())),
+            Ok(slot) => {
+                builder.slot(slot);
+                Ok(())
+            }
+        },
+        "consumable" => match value.as_bool() {
+            None => Err(BuilderError::BadField(
+                "consumable".to_string(),
+                value.clone(),
+            )),
+            Some(v) => {
+                builder.consumable(v);
+                Ok(())
+            }
+        },
+        "use" => match parse_effects(value) {
+            Err(_) => Err(BuilderError::BadField("use".to_string(), value.clone())),
+            Ok(effects) => {
+                for effect in effects {
+                    builder.use_effect(effect);
+                }
+                Ok(())
+            }
+        },
+        "melee" => match parse_melee(value) {
+            Err(_) => Err(BuilderError::BadField("melee".to_string(), value.clone())),
+            Ok(melee) => {
+                builder.melee(melee);
+                Ok(())
+            }
+        },
+        _ => Err(BuilderError::UnknownField(field.to_string())),
+    }
+}
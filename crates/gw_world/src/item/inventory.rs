@@ -0,0 +1,156 @@
+use super::EquipSlot;
+use gw_ecs::entity::{EntityMap, MapEntities, MapEntitiesError};
+use gw_ecs::prelude::{Component, Entity};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_INVENTORY_SIZE: usize = 26;
+
+#[derive(Clone, Debug, Component, Serialize, Deserialize)]
+pub struct Inventory {
+    items: Vec<Entity>,
+    equipped: Vec<(EquipSlot, Entity)>,
+    pub max_items: usize,
+}
+
+impl Inventory {
+    pub fn new(max_items: usize) -> Self {
+        Inventory {
+            items: Vec::new(),
+            equipped: Vec::new(),
+            max_items,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= self.max_items
+    }
+
+    pub fn contains(&self, item: Entity) -> bool {
+        self.items.contains(&item)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.items.iter().copied()
+    }
+
+    /// Adds the item - returns false if the inventory is full
+    pub fn add(&mut self, item: Entity) -> bool {
+        if self.contains(item) {
+            return true;
+        }
+        if self.is_full() {
+            return false;
+        }
+        self.items.push(item);
+        true
+    }
+
+    /// Removes the item (unequipping it if necessary)
+    pub fn remove(&mut self, item: Entity) -> bool {
+        self.equipped.retain(|(_, e)| *e != item);
+        let len = self.items.len();
+        self.items.retain(|e| *e != item);
+        len != self.items.len()
+    }
+
+    /// Removes all of the items
+    pub fn take_all(&mut self) -> Vec<Entity> {
+        self.equipped.clear();
+        std::mem::take(&mut self.items)
+    }
+
+    pub fn equipped(&self, slot: EquipSlot) -> Option<Entity> {
+        self.equipped
+            .iter()
+            .find(|(s, _)| *s == slot)
+            .map(|(_, e)| *e)
+    }
+
+    pub fn is_equipped(&self, item: Entity) -> bool {
+        self.equipped.iter().any(|(_, e)| *e == item)
+    }
+
+    /// Equips the item (it must be in the inventory) - returns the item that was in the slot
+    pub fn equip(&mut self, slot: EquipSlot, item: Entity) -> Option<Entity> {
+        if !self.contains(item) {
+            return None;
+        }
+        let old = self.unequip(slot);
+        self.equipped.push((slot, item));
+        old
+    }
+
+    pub fn unequip(&mut self, slot: EquipSlot) -> Option<Entity> {
+        let old = self.equipped(slot);
+        self.equipped.retain(|(s, _)| *s != slot);
+        old
+    }
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory::new(DEFAULT_INVENTORY_SIZE)
+    }
+}
+
+impl MapEntities for Inventory {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for item in self.items.iter_mut() {
+            *item = entity_map.get(*item)?;
+        }
+        for (_, item) in self.equipped.iter_mut() {
+            *item = entity_map.get(*item)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gw_ecs::entity::Generation;
+
+    fn entity(id: u32) -> Entity {
+        Entity::new(id, Generation::new(1))
+    }
+
+    #[test]
+    fn equip_and_remove() {
+        let mut inv = Inventory::new(2);
+        assert!(inv.add(entity(1)));
+        assert!(inv.add(entity(2)));
+        assert!(!inv.add(entity(3)));
+        assert!(inv.is_full());
+
+        assert_eq!(inv.equip(EquipSlot::WEAPON, entity(1)), None);
+        assert_eq!(inv.equip(EquipSlot::WEAPON, entity(2)), Some(entity(1)));
+        assert_eq!(inv.equip(EquipSlot::ARMOR, entity(3)), None);
+        assert!(inv.is_equipped(entity(2)));
+
+        assert!(inv.remove(entity(2)));
+        assert_eq!(inv.equipped(EquipSlot::WEAPON), None);
+        assert_eq!(inv.len(), 1);
+    }
+
+    #[test]
+    fn serde() {
+        let mut inv = Inventory::default();
+        inv.add(entity(4));
+        inv.add(entity(7));
+        inv.equip(EquipSlot::RING, entity(7));
+
+        let text = serde_json::to_string(&inv).unwrap();
+        let back: Inventory = serde_json::from_str(&text).unwrap();
+        assert_eq!(back.iter().collect::<Vec<_>>(), vec![entity(4), entity(7)]);
+        assert_eq!(back.equipped(EquipSlot::RING), Some(entity(7)));
+        assert_eq!(back.max_items, DEFAULT_INVENTORY_SIZE);
+    }
+}
@@ -0,0 +1,73 @@
+use gw_ecs::prelude::Component;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum EquipSlot {
+    WEAPON,
+    ARMOR,
+    SHIELD,
+    HELM,
+    RING,
+    AMULET,
+    LIGHT,
+}
+
+impl fmt::Display for EquipSlot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for EquipSlot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "WEAPON" => Ok(EquipSlot::WEAPON),
+            "ARMOR" => Ok(EquipSlot::ARMOR),
+            "SHIELD" => Ok(EquipSlot::SHIELD),
+            "HELM" => Ok(EquipSlot::HELM),
+            "RING" => Ok(EquipSlot::RING),
+            "AMULET" => Ok(EquipSlot::AMULET),
+            "LIGHT" => Ok(EquipSlot::LIGHT),
+            _ => Err(format!("Unknown equip slot - {}", s)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Component)]
+pub struct Item {
+    pub id: String,
+
+    pub slot: Option<EquipSlot>,
+    pub consumable: bool,
+
+    pub name: Option<String>,
+    pub flavor: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Item {
+    pub fn new(id: String) -> Self {
+        Item {
+            id,
+            slot: None,
+            consumable: false,
+            name: None,
+            flavor: None,
+            description: None,
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        match self.name {
+            None => match self.flavor {
+                None => &self.id,
+                Some(ref flavor) => flavor,
+            },
+            Some(ref name) => name,
+        }
+    }
+}
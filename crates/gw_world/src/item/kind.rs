@@ -0,0 +1,86 @@
+use super::{Item, ItemKindBuilder};
+use crate::combat::Melee;
+use crate::effect::BoxedEffect;
+use crate::map::Map;
+use crate::position::Position;
+use crate::sprite::Sprite;
+use gw_ecs::prelude::{Entity, World};
+use gw_util::point::Point;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct ItemKind {
+    pub id: String,
+    pub sprite: Sprite,
+    pub item: Item,
+    pub use_effects: Vec<BoxedEffect>,
+    pub melee: Option<Melee>,
+}
+
+impl ItemKind {
+    pub fn builder(id: &str) -> ItemKindBuilder {
+        ItemKindBuilder::new(id)
+    }
+
+    pub(super) fn new(builder: ItemKindBuilder) -> Self {
+        ItemKind {
+            id: builder.id,
+            sprite: builder.sprite,
+            item: builder.item,
+            use_effects: builder.use_effects,
+            melee: builder.melee,
+        }
+    }
+}
+
+/// Creates an item that is not on the map (e.g. to put into an inventory)
+pub fn create_item(kind: &Arc<ItemKind>, world: &mut World) -> Entity {
+    world.spawn((kind.item.clone(), kind.sprite.clone()))
+}
+
+/// Creates an item on the map at the given point
+pub fn spawn_item(kind: &Arc<ItemKind>, world: &mut World, point: Point) -> Entity {
+    let idx = match world
+        .read_resource::<Map>()
+        .get_wrapped_index(point.x, point.y)
+    {
+        None => panic!(
+            "Trying to add item to position that does not exist! kind={}, pos={},{}",
+            kind.id, point.x, point.y
+        ),
+        Some(idx) => idx,
+    };
+
+    let entity = create_item(kind, world);
+    place_item(world, entity, idx);
+    entity
+}
+
+/// Puts the item on the map at the given index
+pub fn place_item(world: &mut World, entity: Entity, idx: usize) {
+    let mut map = world.write_resource::<Map>();
+    let (x, y) = map.to_xy(idx);
+    let _ = world
+        .write_component::<Position>()
+        .insert(entity, Position::new(x, y).with_blocking(false));
+    map.add_item(idx, entity, false);
+}
+
+/// Takes the item off of the map
+pub fn lift_item(world: &mut World, entity: Entity) {
+    let pos = world.write_component::<Position>().remove(entity);
+    if let Some(pos) = pos {
+        let mut map = world.write_resource::<Map>();
+        if let Some(idx) = map.get_wrapped_index(pos.x, pos.y) {
+            map.remove_item(idx, entity);
+        }
+    }
+}
+
+/// The name to use for the item in messages
+pub fn item_name(world: &World, entity: Entity) -> String {
+    match world.read_component::<Item>().get(entity) {
+        None => "something".to_string(),
+        Some(item) => item.name().clone(),
+    }
+}
@@ -0,0 +1,37 @@
+use super::ItemKind;
+use gw_app::log;
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(Debug, Default)]
+pub struct ItemKinds {
+    kinds: HashMap<String, Arc<ItemKind>>,
+}
+
+impl ItemKinds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<ItemKind>> {
+        self.kinds.get(name).cloned()
+    }
+
+    pub fn insert(&mut self, kind: Arc<ItemKind>) {
+        self.kinds.insert(kind.id.clone(), kind);
+    }
+
+    pub fn dump(&self) {
+        log("ItemKinds");
+        for (id, kind) in self.kinds.iter() {
+            log(format!("{} : {:?}", id, kind));
+        }
+    }
+}
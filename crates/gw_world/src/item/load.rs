@@ -0,0 +1,158 @@
+use super::set_field;
+use super::ItemKindBuilder;
+use super::ItemKinds;
+use gw_app::loader::{LoadError, LoadHandler};
+use gw_app::log;
+use gw_ecs::prelude::Ecs;
+use gw_util::value::Value;
+use std::fs::read_to_string;
+
+pub fn load_item_data(dest: &mut ItemKinds, data: Value) -> Result<u32, String> {
+    let map = match data.to_map() {
+        None => return Err("Item Kind data must be a map.".to_string()),
+        Some(v) => v,
+    };
+
+    let mut count: u32 = 0;
+
+    for (name, data) in map.iter() {
+        let data_table = match data.as_map() {
+            None => return Err(format!("Bad data format - {}", name)),
+            Some(v) => v,
+        };
+
+        let id = name.to_string().to_uppercase();
+        let mut builder = ItemKindBuilder::new(&id);
+
+        for (key, value) in data_table.iter() {
+            if let Err(e) = set_field(&mut builder, &key.to_string(), value) {
+                return Err(format!("Error processing item kind[{}] - {:?}", &name, e));
+            }
+        }
+        dest.insert(builder.build());
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn parse_file_data(path: &str, string: &str) -> Result<Value, String> {
+    if path.ends_with(".toml") {
+        gw_util::toml::parse_string(string)
+            .map_err(|e| format!("Failed to parse '{}' => {}", path, e))
+    } else if path.ends_with(".json") || path.ends_with(".jsonc") {
+        gw_util::json::parse_string(string)
+            .map_err(|e| format!("Failed to parse '{}' => {}", path, e))
+    } else {
+        Err(format!(
+            "Unsupported file extension - require '.toml' or '.json' or '.jsonc'.  found: {}",
+            path
+        ))
+    }
+}
+
+#[derive(Default)]
+pub struct ItemKindsLoader {
+    dump: bool,
+}
+
+impl ItemKindsLoader {
+    pub fn new() -> ItemKindsLoader {
+        ItemKindsLoader { dump: false }
+    }
+
+    pub fn with_dump(mut self) -> Self {
+        self.dump = true;
+        self
+    }
+}
+
+impl LoadHandler for ItemKindsLoader {
+    fn file_loaded(&mut self, path: &str, data: Vec<u8>, ecs: &mut Ecs) -> Result<(), LoadError> {
+        let string = match String::from_utf8(data) {
+            Err(e) => {
+                return Err(LoadError::ParseError(format!(
+                    "Malformed file data '{}' : {}",
+                    path, e
+                )))
+            }
+            Ok(v) => v,
+        };
+
+        let value = parse_file_data(path, &string).map_err(LoadError::ParseError)?;
+
+        ecs.ensure_global::<ItemKinds>();
+        let mut item_kinds = ecs.write_global::<ItemKinds>();
+
+        match load_item_data(&mut item_kinds, value) {
+            Err(e) => return Err(LoadError::ProcessError(e)),
+            Ok(count) => {
+                log(format!("Loaded {} item kinds", count));
+            }
+        }
+
+        if self.dump {
+            item_kinds.dump();
+        }
+
+        Ok(())
+    }
+}
+
+pub fn load_item_kinds_file(filename: &str) -> ItemKinds {
+    let file_text = match read_to_string(filename) {
+        Err(e) => panic!("Failed to open {} - {}", filename, e),
+        Ok(v) => v,
+    };
+
+    let value = match parse_file_data(filename, &file_text) {
+        Err(e) => panic!("{}", e),
+        Ok(v) => v,
+    };
+
+    let mut kinds = ItemKinds::default();
+
+    match load_item_data(&mut kinds, value) {
+        Err(e) => panic!("{}", e),
+        Ok(count) => {
+            log(format!("Loaded {} items", count));
+        }
+    }
+
+    kinds
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::item::EquipSlot;
+
+    #[test]
+    fn load_items() {
+        let data = r#"{
+            "DAGGER": { "ch": "|", "name": "dagger", "slot": "weapon", "melee": { "damage": "1d4" } },
+            "POTION": { "ch": "!", "flavor": "a red potion", "use": { "heal": true }, "consumable": true }
+        }"#;
+        let value = gw_util::json::parse_string(data).unwrap();
+
+        let mut kinds = ItemKinds::new();
+        assert_eq!(load_item_data(&mut kinds, value).unwrap(), 2);
+
+        let dagger = kinds.get("DAGGER").unwrap();
+        assert_eq!(dagger.item.slot, Some(EquipSlot::WEAPON));
+        assert_eq!(dagger.item.name(), "dagger");
+        assert!(dagger.melee.is_some());
+
+        let potion = kinds.get("POTION").unwrap();
+        assert!(potion.item.consumable);
+        assert_eq!(potion.use_effects.len(), 1);
+        assert_eq!(potion.item.name(), "a red potion");
+    }
+
+    #[test]
+    fn bad_field() {
+        let value = gw_util::json::parse_string(r#"{ "ROCK": { "taco": true } }"#).unwrap();
+        let mut kinds = ItemKinds::new();
+        assert!(load_item_data(&mut kinds, value).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+mod item;
+pub use item::*;
+
+mod builder;
+pub use builder::*;
+
+mod kind;
+pub use kind::*;
+
+mod kinds;
+pub use kinds::*;
+
+mod load;
+pub use load::*;
+
+mod inventory;
+pub use inventory::*;
@@ -2,6 +2,9 @@
 // use gw_ecs::prelude::World;
 // use gw_util::rng::RandomNumberGenerator;
 
+mod travel;
+pub use travel::*;
+
 pub struct NeedsDraw {
     needs: bool,
 }
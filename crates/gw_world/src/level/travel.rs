@@ -0,0 +1,486 @@
+use crate::being::{Being, BeingFlags};
+use crate::camera::Camera;
+use crate::fov::{Vision, FOV};
+use crate::hero::Hero;
+use crate::horde::{followers_of, join_leader, Follower, Leader};
+use crate::level::NeedsDraw;
+use crate::log::Logger;
+use crate::map::Map;
+use crate::position::Position;
+use crate::task::Executor;
+use gw_app::log;
+use gw_ecs::prelude::{Atom, Ecs, Entity, World};
+use gw_util::point::Point;
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+
+/// Builds (or loads) the level with the given id.  The builder must create the world in the ecs.
+pub type LevelBuilderFn = fn(&mut Ecs, Atom) -> Result<(), String>;
+
+lazy_static! {
+    static ref LEVEL_BUILDER: Mutex<Option<LevelBuilderFn>> = Mutex::new(None);
+}
+
+/// Sets the function used to create levels the first time they are visited
+pub fn register_level_builder(func: LevelBuilderFn) {
+    *LEVEL_BUILDER.lock().unwrap() = Some(func);
+}
+
+/// Sent when the hero leaves a level (to the level being left) and arrives at a level (to the new level)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LevelEvent {
+    Exit(Atom),
+    Enter(Atom),
+}
+
+/// A trip to another level
+#[derive(Debug, Clone)]
+pub struct Travel {
+    pub map_id: Atom,
+    pub location: String,
+    pub entity: Option<Entity>, // None = just change the current level
+    pub allies: bool,           // bring the adjacent allies along
+}
+
+/// The trip requested by a portal - it happens (in [`travel`]) once the current task is done
+#[derive(Debug, Default)]
+pub struct PendingTravel {
+    travel: Option<Travel>,
+}
+
+impl PendingTravel {
+    pub fn new() -> Self {
+        PendingTravel::default()
+    }
+
+    pub fn request(&mut self, travel: Travel) {
+        self.travel = Some(travel);
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.travel.is_some()
+    }
+
+    pub fn take(&mut self) -> Option<Travel> {
+        self.travel.take()
+    }
+}
+
+fn send_level_event(world: &mut World, event: LevelEvent) {
+    world.register_event::<LevelEvent>();
+    world.send_event(event);
+}
+
+/// Creates the level using the registered level builder (if it does not exist yet)
+pub fn ensure_level(ecs: &mut Ecs, id: Atom) -> Result<(), String> {
+    if !ecs.has_world(id) {
+        let builder = match *LEVEL_BUILDER.lock().unwrap() {
+            None => return Err(format!("Unknown level and no level builder - {}", id)),
+            Some(builder) => builder,
+        };
+        builder(ecs, id)?;
+    }
+
+    match ecs.get_world_mut(id) {
+        None => Err(format!("Level builder did not create level - {}", id)),
+        Some(world) => {
+            crate::setup_world(world);
+            Ok(())
+        }
+    }
+}
+
+/// The allies of the hero that are next to it
+fn adjacent_allies(world: &World, hero: Entity) -> Vec<Entity> {
+    let positions = world.read_component::<Position>();
+    let beings = world.read_component::<Being>();
+    let hero_point = match positions.get(hero) {
+        None => return Vec::new(),
+        Some(pos) => pos.point(),
+    };
+
+    followers_of(world, hero)
+        .into_iter()
+        .filter(|e| match beings.get(*e) {
+            None => false,
+            Some(being) => {
+                being.has_flag(BeingFlags::ALLY) && !being.has_flag(BeingFlags::DESTROYED)
+            }
+        })
+        .filter(|e| match positions.get(*e) {
+            None => false,
+            Some(pos) => {
+                let pt = pos.point();
+                (pt.x - hero_point.x).abs() <= 1 && (pt.y - hero_point.y).abs() <= 1
+            }
+        })
+        .collect()
+}
+
+/// The open cells around the point (for the allies that come along)
+fn open_neighbors(map: &Map, point: Point) -> Vec<usize> {
+    point
+        .neighbors(true)
+        .filter_map(|(x, y, _)| map.get_index(x, y))
+        .filter(|idx| !map.is_blocked(*idx) && map.iter_beings(*idx).next().is_none())
+        .collect()
+}
+
+/// Takes the entity off of the map and sets its position to where it will arrive.
+/// Moving it to the new level inserts the position there, which puts it on the new map.
+fn leave_level(world: &mut World, entity: Entity, dest: Point, dest_map_id: u32) {
+    let point = world
+        .read_component::<Position>()
+        .get(entity)
+        .map(|p| p.point());
+    if let Some(point) = point {
+        let mut map = world.write_resource::<Map>();
+        if let Some(idx) = map.get_wrapped_index(point.x, point.y) {
+            map.remove_being(idx, entity);
+        }
+    }
+    if let Some(mut pos) = world.write_component::<Position>().get_mut(entity) {
+        pos.set_level(dest.x, dest.y, dest_map_id);
+    }
+    world.write_resource::<Executor>().remove(entity);
+}
+
+fn arrive_at(world: &mut World, entity: Entity, delay: u64) {
+    if let Some(mut vision) = world.write_component::<Vision>().get_mut(entity) {
+        vision.set_dirty();
+    }
+    world.write_resource::<Executor>().insert(entity, delay);
+}
+
+/// Performs the trip requested by a portal in the current level (if any).
+/// The destination level is built if necessary and becomes the current level.
+/// Each level keeps its own map, memory and executor.  Returns true if the current level changed.
+pub fn travel(ecs: &mut Ecs) -> bool {
+    let source_id = ecs.current_world().id();
+    let request = match ecs.current_world().try_write_resource::<PendingTravel>() {
+        None => return false,
+        Some(mut pending) => match pending.take() {
+            None => return false,
+            Some(request) => request,
+        },
+    };
+
+    if let Err(e) = ensure_level(ecs, request.map_id) {
+        log(e);
+        return false;
+    }
+
+    let dest_id = request.map_id;
+    let (dest_point, dest_map_id) = {
+        let dest = ecs.get_world(dest_id).unwrap();
+        let map = dest.read_resource::<Map>();
+        match map
+            .get_location(&request.location)
+            .or_else(|| map.get_location("START"))
+        {
+            None => {
+                log(format!(
+                    "Unknown portal location - {}::{}",
+                    dest_id, request.location
+                ));
+                return false;
+            }
+            Some(idx) => (map.to_point(idx), map.id()),
+        }
+    };
+
+    let hero = {
+        let world = ecs.current_world();
+        let hero = world.read_resource::<Hero>().entity;
+        match request.entity {
+            Some(entity) if entity == hero && world.entities().is_alive(hero) => Some(hero),
+            _ => None,
+        }
+    };
+
+    if let Some(hero) = hero {
+        if dest_id == source_id {
+            log("Portal to the same level");
+            return false;
+        }
+
+        // Only the allies that have somewhere to stand come along
+        let allies = {
+            let world = ecs.current_world();
+            match request.allies {
+                false => Vec::new(),
+                true => {
+                    let dest = ecs.get_world(dest_id).unwrap();
+                    let map = dest.read_resource::<Map>();
+                    let spots = open_neighbors(&map, dest_point);
+                    let mut allies = adjacent_allies(world, hero);
+                    allies.truncate(spots.len());
+                    allies
+                        .into_iter()
+                        .zip(spots.into_iter().map(|idx| map.to_point(idx)))
+                        .collect::<Vec<_>>()
+                }
+            }
+        };
+
+        {
+            let world = ecs.current_world_mut();
+            leave_level(world, hero, dest_point, dest_map_id);
+            for (ally, point) in allies.iter() {
+                leave_level(world, *ally, *point, dest_map_id);
+            }
+            world.insert_resource(Hero::default());
+            send_level_event(world, LevelEvent::Exit(source_id));
+        }
+
+        let new_hero = ecs.move_entity(hero, source_id, dest_id);
+        let new_allies: Vec<Entity> = allies
+            .into_iter()
+            .map(|(ally, _)| ecs.move_entity(ally, source_id, dest_id))
+            .collect();
+
+        ecs.set_current_world(dest_id).unwrap();
+        let world = ecs.current_world_mut();
+
+        world.insert_resource(Hero::new(new_hero));
+        world.write_resource::<Camera>().set_follows(new_hero);
+        if let Some(mut leader) = world.write_component::<Leader>().get_mut(new_hero) {
+            leader.target = None;
+        }
+        arrive_at(world, new_hero, 0);
+
+        for ally in new_allies {
+            let (bound, act_time) = {
+                let bound = world
+                    .read_component::<Follower>()
+                    .get(ally)
+                    .map(|f| f.bound)
+                    .unwrap_or(false);
+                let act_time = world
+                    .read_component::<Being>()
+                    .get(ally)
+                    .map(|b| b.act_time as u64)
+                    .unwrap_or(100);
+                (bound, act_time)
+            };
+            join_leader(world, ally, new_hero, bound);
+            arrive_at(world, ally, act_time);
+        }
+    } else {
+        if ecs.set_current_world(dest_id).is_err() {
+            return false;
+        }
+        send_level_event(
+            ecs.get_world_mut(source_id).unwrap(),
+            LevelEvent::Exit(source_id),
+        );
+    }
+
+    let world = ecs.current_world_mut();
+    if let Some(mut fov) = world.try_write_resource::<FOV>() {
+        fov.set_dirty();
+    }
+    world.write_resource::<NeedsDraw>().set();
+
+    let welcome = world.read_resource::<Map>().welcome.clone();
+    if let Some(welcome) = welcome {
+        world.write_global::<Logger>().log(welcome);
+    }
+
+    send_level_event(world, LevelEvent::Enter(dest_id));
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::{Stat, Stats};
+    use crate::effect::{parse_effect, EffectResult};
+    use crate::test_util::make_empty_ecs;
+    use crate::tile::Tiles;
+    use gw_util::rng::RandomNumberGenerator;
+    use gw_util::value::Value;
+    use std::collections::HashMap;
+
+    fn make_level(ecs: &mut Ecs, id: Atom) -> Result<(), String> {
+        let world = ecs.create_world(id);
+        let floor = world.read_global::<Tiles>().get("FLOOR").unwrap();
+        let mut map = Map::new(5, 5);
+        map.fill(floor);
+        map.set_location("START", map.get_index(2, 2).unwrap());
+        map.set_location("STAIRS", map.get_index(4, 4).unwrap());
+        map.welcome = Some("Welcome!".to_string());
+        world.insert_resource(map);
+        world.insert_resource(RandomNumberGenerator::seeded(12345));
+        crate::setup_world(world);
+        Ok(())
+    }
+
+    fn make_ecs() -> Ecs {
+        let mut ecs = make_empty_ecs();
+        register_level_builder(make_level);
+        make_level(&mut ecs, "ONE".into()).unwrap();
+        ecs.set_current_world("ONE").unwrap();
+        ecs
+    }
+
+    fn make_being(world: &mut World, name: &str, x: i32, y: i32) -> Entity {
+        let mut being = Being::new(name.to_string());
+        being.name = Some(name.to_string());
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 5);
+
+        let entity = world.spawn((being, stats, Position::new(x, y)));
+        world.write_resource::<Executor>().insert(entity, 100);
+        entity
+    }
+
+    fn portal(map: &str, location: &str) -> crate::effect::BoxedEffect {
+        let mut data = HashMap::new();
+        data.insert("map".into(), Value::from(map));
+        data.insert("location".into(), Value::from(location));
+        data.insert("allies".into(), Value::from(true));
+        parse_effect("portal", &Value::from(data)).unwrap()
+    }
+
+    #[test]
+    fn hero_travels_with_allies() {
+        let mut ecs = make_ecs();
+        let (hero, ally) = {
+            let world = ecs.current_world_mut();
+            let hero = make_being(world, "hero", 1, 1);
+            world.insert_resource(Hero::new(hero));
+            let ally = make_being(world, "ally", 2, 1);
+            world
+                .write_component::<Being>()
+                .get_mut(ally)
+                .unwrap()
+                .set_flag(BeingFlags::ALLY);
+            join_leader(world, ally, hero, false);
+            make_being(world, "rat", 4, 4);
+            (hero, ally)
+        };
+
+        let effect = portal("two", "stairs");
+        let pos = Point::new(1, 1);
+        assert!(matches!(
+            effect.fire(ecs.current_world_mut(), pos, Some(hero)),
+            EffectResult::Success
+        ));
+        // Nothing happens until the task is over
+        assert_eq!(ecs.current_world().id(), Atom::from("ONE"));
+        assert!(!ecs.has_world("TWO"));
+
+        assert!(travel(&mut ecs));
+        assert!(!travel(&mut ecs));
+        assert_eq!(ecs.current_world().id(), Atom::from("TWO"));
+
+        {
+            let one = ecs.get_world("ONE").unwrap();
+            assert!(!one.entities().is_alive(hero));
+            assert!(!one.entities().is_alive(ally));
+            assert_eq!(one.read_resource::<Executor>().len(), 1);
+            assert_eq!(one.read_resource::<Map>().iter_beings(7).count(), 0);
+            let mut events = one.write_events::<LevelEvent>();
+            let events: Vec<LevelEvent> = events.drain().collect();
+            assert_eq!(events, vec![LevelEvent::Exit(Atom::from("ONE"))]);
+        }
+
+        let world = ecs.current_world();
+        let new_hero = world.read_resource::<Hero>().entity;
+        assert_eq!(world.read_resource::<Camera>().follows(), Some(new_hero));
+        assert_eq!(
+            world
+                .read_component::<Position>()
+                .get(new_hero)
+                .unwrap()
+                .point(),
+            Point::new(4, 4)
+        );
+        assert_eq!(world.read_resource::<Executor>().len(), 2);
+
+        let followers = followers_of(world, new_hero);
+        assert_eq!(followers.len(), 1);
+        let pt = world
+            .read_component::<Position>()
+            .get(followers[0])
+            .unwrap()
+            .point();
+        assert_eq!(pt.distance(&Point::new(4, 4)).round(), 1.0);
+
+        {
+            let mut events = world.write_events::<LevelEvent>();
+            let events: Vec<LevelEvent> = events.drain().collect();
+            assert_eq!(events, vec![LevelEvent::Enter(Atom::from("TWO"))]);
+        }
+
+        let mut logger = world.write_global::<Logger>();
+        let msgs: Vec<String> = logger.iter().map(|m| m.msg.clone()).collect();
+        assert!(msgs.contains(&"Welcome!".to_string()));
+    }
+
+    #[test]
+    fn only_arrives_at_destination() {
+        let mut ecs = make_ecs();
+        let hero = {
+            let world = ecs.current_world_mut();
+            let hero = make_being(world, "hero", 1, 1);
+            world.insert_resource(Hero::new(hero));
+            hero
+        };
+
+        portal("TWO", "STAIRS").fire(ecs.current_world_mut(), Point::new(1, 1), Some(hero));
+        assert!(travel(&mut ecs));
+
+        let world = ecs.current_world();
+        let hero = world.read_resource::<Hero>().entity;
+        let map = world.read_resource::<Map>();
+        let old_idx = map.get_index(1, 1).unwrap();
+        assert_eq!(map.iter_beings(old_idx).count(), 0);
+        assert!(!map.is_blocked(old_idx));
+
+        let idx = map.get_index(4, 4).unwrap();
+        assert_eq!(map.iter_beings(idx).collect::<Vec<_>>(), vec![hero]);
+        assert_eq!(
+            world.read_component::<Position>().get(hero).unwrap().map_id,
+            map.id()
+        );
+    }
+
+    #[test]
+    fn hero_returns() {
+        let mut ecs = make_ecs();
+        let hero = {
+            let world = ecs.current_world_mut();
+            let hero = make_being(world, "hero", 1, 1);
+            world.insert_resource(Hero::new(hero));
+            hero
+        };
+        let time = ecs.current_world().read_resource::<Executor>().time();
+
+        portal("TWO", "START").fire(ecs.current_world_mut(), Point::new(1, 1), Some(hero));
+        assert!(travel(&mut ecs));
+
+        let hero = ecs.current_world().read_resource::<Hero>().entity;
+        portal("ONE", "UNKNOWN").fire(ecs.current_world_mut(), Point::new(2, 2), Some(hero));
+        assert!(travel(&mut ecs));
+
+        // Back where we started (at the START location), with the level's executor intact
+        let world = ecs.current_world();
+        assert_eq!(world.id(), Atom::from("ONE"));
+        let hero = world.read_resource::<Hero>().entity;
+        assert_eq!(
+            world
+                .read_component::<Position>()
+                .get(hero)
+                .unwrap()
+                .point(),
+            Point::new(2, 2)
+        );
+        assert_eq!(world.read_resource::<Executor>().time(), time);
+        assert_eq!(
+            ecs.get_world("TWO").unwrap().read_resource::<Hero>().entity,
+            Entity::dead()
+        );
+    }
+}
@@ -2,41 +2,62 @@ use gw_ecs::prelude::{Ecs, World};
 use gw_util::rng::RandomNumberGenerator;
 
 pub mod action;
+pub mod animation;
 pub mod being;
+pub mod blueprint;
 pub mod camera;
 pub mod combat;
 pub mod effect;
 pub mod fov;
 pub mod hero;
 pub mod horde;
+pub mod item;
 pub mod level;
+pub mod light;
 pub mod log;
 pub mod map;
 pub mod memory;
 pub mod position;
+pub mod save;
 pub mod sprite;
+pub mod status;
 pub mod task;
+#[cfg(test)]
+mod test_util;
 pub mod tile;
 pub mod treasure;
 pub mod widget;
 
 /// Register all the standard components for gw_world
 pub fn register_components(ecs: &mut Ecs) {
-    ecs.register::<position::Position>();
+    ecs.register_with_hooks(position::position_hooks());
     ecs.register::<sprite::Sprite>();
     ecs.register::<task::Task>();
-    ecs.register::<being::Being>();
+    ecs.register_with_hooks(being::being_hooks());
     ecs.register::<combat::Melee>();
+    ecs.register::<combat::Ranged>();
     ecs.register::<being::Stats>();
+    ecs.register::<status::StatusEffects>();
+    ecs.register::<item::Item>();
+    ecs.register::<item::Inventory>();
+    ecs.register::<fov::Vision>();
+    ecs.register::<light::Light>();
     ecs.register::<horde::HordeRef>();
     ecs.register::<horde::SpawnRef>();
+    ecs.register::<horde::Leader>();
+    ecs.register::<horde::Follower>();
 }
 
 pub fn setup_ecs(ecs: &mut Ecs) {
     ecs.ensure_global::<tile::Tiles>();
     ecs.ensure_global::<being::BeingKinds>();
+    ecs.ensure_global::<item::ItemKinds>();
     ecs.ensure_global::<horde::Hordes>();
+    ecs.ensure_global::<blueprint::Blueprints>();
     ecs.ensure_global::<log::Logger>();
+    ecs.ensure_global::<being::Progression>();
+    ecs.ensure_global_with(save::standard_type_registry);
+    ecs.ensure_global_with(save::standard_saved_components);
 }
 
 /// Ensure all the standard resources for gw_world
@@ -45,6 +66,14 @@ pub fn setup_world(world: &mut World) {
     world.ensure_resource::<task::Executor>();
     world.ensure_resource::<level::NeedsDraw>();
     world.ensure_resource::<task::UserAction>();
+    world.ensure_resource::<task::AutoMove>();
     world.ensure_resource::<hero::Hero>();
     world.ensure_resource::<RandomNumberGenerator>();
+    world.ensure_resource::<fov::LosCache>();
+    world.ensure_resource::<light::LightMap>();
+    world.ensure_resource::<animation::Animations>();
+    world.ensure_resource::<being::LevelUps>();
+    world.ensure_resource::<map::LayerClock>();
+    world.ensure_resource::<level::PendingTravel>();
+    world.register_event::<level::LevelEvent>();
 }
@@ -0,0 +1,80 @@
+use gw_app::color::get_color;
+use gw_app::RGBA;
+use gw_ecs::prelude::Component;
+use gw_util::value::Value;
+use serde::{Deserialize, Serialize};
+
+/// A light source - on an entity (a torch carrying hero) or a tile (a brazier)
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Light {
+    pub color: RGBA,
+    pub radius: u32,
+}
+
+impl Light {
+    pub fn new(color: RGBA, radius: u32) -> Self {
+        Light { color, radius }
+    }
+
+    /// How much of the light reaches the given distance (1.0 at the source, 0.0 at the radius)
+    pub fn falloff(&self, distance: f32) -> f32 {
+        if self.radius == 0 {
+            return 0.0;
+        }
+        // Same curve as the doryen-fov light demo
+        let radius = self.radius as f32;
+        let radius_coef = 1.0 / (1.0 + radius * radius / 20.0);
+        let coef = 1.0 / (1.0 + distance * distance / 20.0);
+        ((coef - radius_coef) / (1.0 - radius_coef)).clamp(0.0, 1.0)
+    }
+}
+
+/// Parses a light config - either {"color": <COLOR>, "radius": <INT>} or [<COLOR>, <INT>]
+pub fn parse_light(value: &Value) -> Result<Light, String> {
+    let (color, radius) = if let Some(list) = value.as_list() {
+        match list.as_slice() {
+            [color, radius] => (color, radius),
+            _ => return Err(format!("Invalid light value - {:?}", value)),
+        }
+    } else if let Some(map) = value.as_map() {
+        match (map.get(&"color".into()), map.get(&"radius".into())) {
+            (Some(color), Some(radius)) => (color, radius),
+            _ => return Err(format!("Light requires color and radius - {:?}", value)),
+        }
+    } else {
+        return Err(format!("Invalid light value - {:?}", value));
+    };
+
+    let color = match get_color(&color.to_string()) {
+        Err(_) => return Err(format!("Invalid light color - {:?}", color)),
+        Ok(color) => color,
+    };
+    let radius = match radius.as_int() {
+        None => return Err(format!("Invalid light radius - {:?}", radius)),
+        Some(radius) => radius as u32,
+    };
+    Ok(Light::new(color, radius))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let light =
+            parse_light(&Value::from(vec![Value::from("#ff0000"), Value::from(4)])).unwrap();
+        assert_eq!(light, Light::new(RGBA::rgb(255, 0, 0), 4));
+
+        let mut data = std::collections::HashMap::new();
+        data.insert("color".into(), Value::from("#00ff00"));
+        data.insert("radius".into(), Value::from(6));
+        let light = parse_light(&Value::from(data)).unwrap();
+        assert_eq!(light.radius, 6);
+        assert_eq!(light.color, RGBA::rgb(0, 255, 0));
+
+        assert!(parse_light(&Value::from(3)).is_err());
+        assert!(light.falloff(0.0) > light.falloff(3.0));
+        assert_eq!(light.falloff(6.0), 0.0);
+    }
+}
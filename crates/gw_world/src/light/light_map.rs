@@ -0,0 +1,238 @@
+use super::Light;
+use crate::fov::FovCalc;
+use crate::map::{Cell, Map};
+use crate::position::Position;
+use gw_app::color::WHITE;
+use gw_app::RGBA;
+use gw_ecs::prelude::{Join, World};
+use gw_util::point::Point;
+
+/// Cells with less light than this (the sum of r+g+b) are dark - the beings in them can not be seen
+pub const DARKNESS_LEVEL: u32 = 60;
+
+/// The light that reaches each cell of the level - the ambient light plus all of the light sources
+#[derive(Debug)]
+pub struct LightMap {
+    ambient: RGBA,
+    width: u32,
+    light: Vec<RGBA>,
+    sources: Vec<(Point, Light)>,
+    dirty: bool,
+}
+
+impl LightMap {
+    pub fn new(ambient: RGBA) -> Self {
+        LightMap {
+            ambient,
+            width: 0,
+            light: Vec::new(),
+            sources: Vec::new(),
+            dirty: true,
+        }
+    }
+
+    pub fn ambient(&self) -> RGBA {
+        self.ambient
+    }
+
+    pub fn set_ambient(&mut self, ambient: RGBA) {
+        self.ambient = ambient;
+        self.dirty = true;
+    }
+
+    pub fn set_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// The light at the cell (the ambient light if it has not been calculated)
+    pub fn get(&self, idx: usize) -> RGBA {
+        self.light.get(idx).copied().unwrap_or(self.ambient)
+    }
+
+    pub fn get_xy(&self, x: i32, y: i32) -> RGBA {
+        if x < 0 || y < 0 || x >= self.width as i32 {
+            return self.ambient;
+        }
+        self.get((x + y * self.width as i32) as usize)
+    }
+
+    pub fn is_dark(&self, idx: usize) -> bool {
+        let light = self.get(idx);
+        (light.0 as u32 + light.1 as u32 + light.2 as u32) < DARKNESS_LEVEL
+    }
+
+    fn calculate(&mut self, map: &Map, sources: Vec<(Point, Light)>) {
+        let (width, height) = map.full_size();
+        self.width = width;
+        self.light.clear();
+        self.light.resize((width * height) as usize, self.ambient);
+
+        for (origin, light) in sources.iter() {
+            let mask = FovCalc::default().get_mask(map, *origin, light.radius);
+            for pt in mask.iter().chain(std::iter::once(*origin)) {
+                let idx = match map.get_wrapped_index(pt.x, pt.y) {
+                    None => continue,
+                    Some(idx) => idx,
+                };
+                let coef = light.falloff(pt.distance(origin));
+                if coef <= 0.0 {
+                    continue;
+                }
+                self.light[idx] = add_light(&self.light[idx], &light.color, coef);
+            }
+        }
+
+        self.sources = sources;
+        self.dirty = false;
+    }
+}
+
+impl Default for LightMap {
+    fn default() -> Self {
+        LightMap::new(WHITE)
+    }
+}
+
+fn add_light(base: &RGBA, light: &RGBA, coef: f32) -> RGBA {
+    let add = |a: u8, b: u8| (a as f32 + b as f32 * coef).min(255.0) as u8;
+    RGBA::rgba(
+        add(base.0, light.0),
+        add(base.1, light.1),
+        add(base.2, light.2),
+        255,
+    )
+}
+
+/// Shades the color with the light (keeps the alpha of the color)
+pub fn apply_light(color: &RGBA, light: &RGBA) -> RGBA {
+    let shade = |c: u8, l: u8| (c as u32 * l as u32 / 255) as u8;
+    RGBA::rgba(
+        shade(color.0, light.0),
+        shade(color.1, light.1),
+        shade(color.2, light.2),
+        color.3,
+    )
+}
+
+/// The light sources on the level - the tiles with lights and the entities with a Light component
+fn light_sources(world: &World, map: &Map) -> Vec<(Point, Light)> {
+    let mut sources = Vec::new();
+    for idx in 0..map.ground.len() {
+        let cell = map.get_cell(idx).unwrap();
+        for tile in [cell.ground(), cell.fixture(), cell.liquid(), cell.gas()] {
+            if let Some(light) = tile.light {
+                sources.push((map.to_point(idx), light));
+            }
+        }
+    }
+
+    let positions = world.read_component::<Position>();
+    let lights = world.read_component::<Light>();
+    for (pos, light) in (&positions, &lights).join() {
+        sources.push((pos.point(), *light));
+    }
+    sources
+}
+
+/// Recalculates the level's LightMap if any light sources moved or changed (or it is dirty).
+/// Returns true if it was recalculated.
+pub fn update_lights(world: &World) -> bool {
+    let mut light_map = match world.try_write_resource::<LightMap>() {
+        None => return false,
+        Some(light_map) => light_map,
+    };
+    let map = world.read_resource::<Map>();
+    let sources = light_sources(world, &map);
+    if !light_map.dirty && light_map.sources == sources {
+        return false;
+    }
+    light_map.calculate(&map, sources);
+    true
+}
+
+/// Is the point too dark to see beings in it?
+pub fn is_dark(world: &World, point: Point) -> bool {
+    let light_map = match world.try_read_resource::<LightMap>() {
+        None => return false,
+        Some(light_map) => light_map,
+    };
+    let map = world.read_resource::<Map>();
+    match map.get_wrapped_index(point.x, point.y) {
+        None => false,
+        Some(idx) => light_map.is_dark(idx),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tile::Tiles;
+    use gw_app::color::BLACK;
+    use gw_ecs::prelude::Builder;
+
+    fn make_world() -> World {
+        let mut world = World::empty(1);
+        world.register::<Position>();
+        world.register::<Light>();
+
+        let mut map = Map::new(10, 3);
+        let tiles = Tiles::default();
+        map.fill(tiles.get("FLOOR").unwrap());
+        for y in 0..3 {
+            let idx = map.get_wrapped_index(4, y).unwrap();
+            map.reset_tiles(idx, tiles.get("WALL").unwrap());
+        }
+        world.insert_resource(map);
+        world.insert_resource(LightMap::new(BLACK));
+        world
+    }
+
+    #[test]
+    fn falloff_and_walls() {
+        let mut world = make_world();
+        let torch = world
+            .create_entity()
+            .with(Position::new(1, 1))
+            .with(Light::new(WHITE, 3))
+            .id();
+
+        assert!(update_lights(&world));
+        assert!(!update_lights(&world));
+
+        {
+            let lights = world.read_resource::<LightMap>();
+            let source = lights.get_xy(1, 1);
+            let near = lights.get_xy(2, 1);
+            assert_eq!(source, WHITE);
+            assert!(near.0 < source.0 && near.0 > 0);
+            assert!(!lights.is_dark(world.read_resource::<Map>().get_index(2, 1).unwrap()));
+        }
+        // Beyond the wall and the radius
+        assert!(is_dark(&world, Point::new(5, 1)));
+
+        // Moving the light recalculates
+        world
+            .write_component::<Position>()
+            .get_mut(torch)
+            .unwrap()
+            .set(6, 1);
+        assert!(update_lights(&world));
+        assert!(!is_dark(&world, Point::new(5, 1)));
+        assert!(is_dark(&world, Point::new(1, 1)));
+    }
+
+    #[test]
+    fn shading() {
+        let color = RGBA::rgba(200, 100, 50, 128);
+        assert_eq!(apply_light(&color, &WHITE), color);
+        assert_eq!(apply_light(&color, &BLACK), RGBA::rgba(0, 0, 0, 128));
+        assert_eq!(
+            apply_light(&color, &RGBA::rgb(255, 0, 255)),
+            RGBA::rgba(200, 0, 50, 128)
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod light;
+mod light_map;
+
+pub use light::*;
+pub use light_map::*;
@@ -2,22 +2,72 @@ use std::collections::VecDeque;
 
 use gw_app::log;
 
+/// The number of messages kept in the history (by default)
+pub const DEFAULT_LOG_HISTORY: usize = 500;
+
+/// Time units per turn - used to turn the executor time into turn numbers
+pub const LOG_TURN_TIME: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    System,
+    Combat,
+    Debug,
+}
+
+impl MessageKind {
+    /// The color the messages of this kind are drawn in (unless they have their own markup)
+    pub fn color(&self) -> Option<&'static str> {
+        match self {
+            MessageKind::System => None,
+            MessageKind::Combat => Some("light_coral"),
+            MessageKind::Debug => Some("gray"),
+        }
+    }
+}
+
 pub struct MessageInfo {
     pub msg: String,
     pub acked: bool,
+    pub kind: MessageKind,
+    pub turn: u64,
+    pub repeat: u32,
 }
 
 impl MessageInfo {
     pub fn new(msg: String) -> MessageInfo {
-        MessageInfo { msg, acked: false }
+        MessageInfo {
+            msg,
+            acked: false,
+            kind: MessageKind::System,
+            turn: 0,
+            repeat: 1,
+        }
+    }
+
+    /// The message with the repeats folded in - e.g. "The rat bites you x3"
+    pub fn text(&self) -> String {
+        match self.repeat {
+            0 | 1 => self.msg.clone(),
+            n => format!("{} x{}", self.msg, n),
+        }
+    }
+
+    /// The text wrapped in the color markup for the kind
+    pub fn markup(&self) -> String {
+        match self.kind.color() {
+            None => self.text(),
+            Some(color) => format!("#[{}]{}#[]", color, self.text()),
+        }
     }
 }
 
 // TODO - Location recording/filtering
-#[derive(Default)]
 pub struct Logger {
     msgs: VecDeque<MessageInfo>,
     count: usize,
+    total: usize,
+    turn: u64,
     pub debug: bool,
     combat: Option<String>,
 }
@@ -27,19 +77,30 @@ impl Logger {
         Logger {
             msgs: VecDeque::new(),
             debug: false,
-            count: 10,
+            count: DEFAULT_LOG_HISTORY,
+            total: 0,
+            turn: 0,
             combat: None,
         }
     }
 
+    /// Iterates the messages - newest first
     pub fn iter(&mut self) -> impl Iterator<Item = &MessageInfo> {
-        if self.combat.is_some() {
-            let combat = self.combat.take().unwrap();
-            self.log(combat);
-        }
+        self.flush();
         self.msgs.iter()
     }
 
+    /// Logs the pending combat message (if any)
+    pub fn flush(&mut self) {
+        if let Some(combat) = self.combat.take() {
+            self.push(MessageKind::Combat, combat);
+        }
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.count
+    }
+
     pub fn set_max_len(&mut self, count: usize) {
         self.count = count;
         self.trim();
@@ -49,6 +110,45 @@ impl Logger {
         self.msgs.len()
     }
 
+    /// The number of messages ever logged (even the ones that were trimmed) - including the pending combat message
+    pub fn total(&self) -> usize {
+        match self.combat {
+            None => self.total,
+            Some(_) => self.total + 1,
+        }
+    }
+
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Sets the turn that new messages are logged on from the executor time
+    pub fn set_time(&mut self, time: u64) {
+        self.turn = time / LOG_TURN_TIME;
+    }
+
+    /// The number of messages that the player has not acknowledged yet
+    pub fn unacked(&mut self) -> usize {
+        self.flush();
+        self.msgs.iter().take_while(|msg| !msg.acked).count()
+    }
+
+    /// Acknowledges the oldest unacknowledged messages
+    pub fn ack(&mut self, count: usize) {
+        let unacked = self.unacked();
+        let first = unacked.saturating_sub(count);
+        for msg in self.msgs.range_mut(first..unacked) {
+            msg.acked = true;
+        }
+    }
+
+    pub fn ack_all(&mut self) {
+        self.flush();
+        for msg in self.msgs.iter_mut() {
+            msg.acked = true;
+        }
+    }
+
     fn trim(&mut self) {
         while self.msgs.len() > self.count {
             self.msgs.pop_back();
@@ -60,13 +160,30 @@ impl Logger {
     }
 
     pub fn log<S: ToString>(&mut self, msg: S) {
-        if let Some(combat) = self.combat.take() {
-            self.log(combat);
-        }
+        self.log_as(MessageKind::System, msg);
+    }
+
+    pub fn log_as<S: ToString>(&mut self, kind: MessageKind, msg: S) {
+        self.flush();
+        self.push(kind, msg.to_string());
+    }
 
-        let msg = msg.to_string();
+    /// Adds the message - the same message repeated (and not acknowledged yet) is folded into the last one
+    fn push(&mut self, kind: MessageKind, msg: String) {
         println!(":: {}", msg);
-        self.msgs.push_front(MessageInfo::new(msg));
+        self.total += 1;
+        if let Some(last) = self.msgs.front_mut() {
+            if !last.acked && last.kind == kind && last.msg == msg {
+                last.repeat += 1;
+                last.turn = self.turn;
+                return;
+            }
+        }
+
+        let mut info = MessageInfo::new(msg);
+        info.kind = kind;
+        info.turn = self.turn;
+        self.msgs.push_front(info);
         self.trim();
     }
 
@@ -86,13 +203,19 @@ impl Logger {
 
     pub fn debug<S: ToString>(&mut self, msg: S) {
         if self.debug {
-            self.log(msg);
+            self.log_as(MessageKind::Debug, msg);
         } else {
             log(&msg.to_string());
         }
     }
 }
 
+impl Default for Logger {
+    fn default() -> Self {
+        Logger::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,7 +230,54 @@ mod tests {
         logger.log("testing");
         logger.log("testing");
 
-        assert_eq!(logger.iter().count(), 3);
+        assert_eq!(logger.iter().count(), 1);
+        assert_eq!(logger.iter().next().unwrap().text(), "testing x3");
+        assert_eq!(logger.total(), 3);
+    }
+
+    #[test]
+    fn kinds_and_turns() {
+        let mut logger = Logger::new();
+        logger.debug = true;
+
+        logger.set_time(250);
+        logger.log_combat("You hit the rat", false);
+        logger.debug("rat hp = 2");
+        logger.log("The rat flees");
+
+        let msgs: Vec<(MessageKind, u64)> = logger.iter().map(|m| (m.kind, m.turn)).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                (MessageKind::System, 2),
+                (MessageKind::Debug, 2),
+                (MessageKind::Combat, 2)
+            ]
+        );
+        assert_eq!(
+            logger.iter().nth(1).unwrap().markup(),
+            "#[gray]rat hp = 2#[]"
+        );
+    }
+
+    #[test]
+    fn ack_oldest_first() {
+        let mut logger = Logger::new();
+
+        logger.log("testing 1");
+        logger.log("testing 2");
+        logger.log("testing 3");
+        assert_eq!(logger.unacked(), 3);
+
+        logger.ack(2);
+        assert_eq!(logger.unacked(), 1);
+        assert!(!logger.iter().next().unwrap().acked);
+
+        // An acked message is not folded into
+        logger.ack_all();
+        logger.log("testing 3");
+        assert_eq!(logger.unacked(), 1);
+        assert_eq!(logger.len(), 4);
     }
 
     #[test]
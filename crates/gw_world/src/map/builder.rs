@@ -85,6 +85,10 @@ impl<'t> Builder<'t> {
         &mut self.rng
     }
 
+    pub fn tiles(&self) -> &'t Tiles {
+        self.tiles
+    }
+
     // pub fn set_portal(&mut self, point: Point, map_id: &str, location: &str) -> &mut Self {
     //     let idx = match self.map.get_wrapped_index(point.x, point.y) {
     //         None => return self,
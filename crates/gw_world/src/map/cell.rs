@@ -4,11 +4,11 @@ use crate::{
     sprite::Sprite,
     tile::{tile_is_none, Tile, TileFlags, TileMove, TileSet},
 };
-use gw_app::log;
+use gw_app::{log, RGBA};
 use gw_ecs::prelude::{ReadComp, World};
 use std::sync::Arc;
 
-use super::{CellFlags, Map};
+use super::{CellFlags, Map, LAYER_VOLUME};
 
 pub trait Cell {
     fn ground(&self) -> &Arc<Tile>;
@@ -16,6 +16,22 @@ pub trait Cell {
     fn index(&self) -> usize;
     fn map(&self) -> &Map;
 
+    fn liquid(&self) -> &Arc<Tile> {
+        &self.map().liquid[self.index()]
+    }
+
+    fn gas(&self) -> &Arc<Tile> {
+        &self.map().gas[self.index()]
+    }
+
+    fn liquid_volume(&self) -> u32 {
+        self.map().liquid_volume(self.index())
+    }
+
+    fn gas_volume(&self) -> u32 {
+        self.map().gas_volume(self.index())
+    }
+
     fn get_tiles(&self) -> TileSet {
         TileSet::new(self.ground().clone(), self.fixture().clone())
     }
@@ -23,11 +39,15 @@ pub trait Cell {
     // Flags
 
     fn has_tile_flag(&self, flag: TileFlags) -> bool {
-        self.ground().flags.contains(flag) || self.fixture().flags.contains(flag)
+        self.ground().flags.contains(flag)
+            || self.fixture().flags.contains(flag)
+            || self.liquid().flags.contains(flag)
     }
 
     fn has_any_tile_flag(&self, flag: TileFlags) -> bool {
-        self.ground().flags.intersects(flag) || self.fixture().flags.intersects(flag)
+        self.ground().flags.intersects(flag)
+            || self.fixture().flags.intersects(flag)
+            || self.liquid().flags.intersects(flag)
     }
 
     fn has_cell_flag(&self, flag: CellFlags) -> bool {
@@ -41,11 +61,17 @@ pub trait Cell {
     // Move Flags
 
     fn has_move_flag(&self, flag: TileMove) -> bool {
-        self.ground().move_flags.contains(flag) || self.fixture().move_flags.contains(flag)
+        self.ground().move_flags.contains(flag)
+            || self.fixture().move_flags.contains(flag)
+            || self.liquid().move_flags.contains(flag)
+            || self.gas().move_flags.contains(flag)
     }
 
     fn has_any_move_flag(&self, flag: TileMove) -> bool {
-        self.ground().move_flags.intersects(flag) || self.fixture().move_flags.intersects(flag)
+        self.ground().move_flags.intersects(flag)
+            || self.fixture().move_flags.intersects(flag)
+            || self.liquid().move_flags.intersects(flag)
+            || self.gas().move_flags.intersects(flag)
     }
 
     fn blocks(&self) -> bool {
@@ -68,11 +94,26 @@ pub trait Cell {
     // Sprite
     fn sprite(&self) -> Sprite {
         let mut sprite = Sprite::default();
-        let ground = self.ground();
+        // Secret tiles look like the tile they mimic
+        let look = |tile: &Arc<Tile>| tile.mimic.clone().unwrap_or_else(|| tile.clone());
+        let ground = look(self.ground());
         sprite.mix(ground.glyph, ground.fg, ground.bg);
-        let feature = self.fixture();
+        let liquid = self.liquid();
+        if self.liquid_volume() > 0 {
+            sprite.mix(liquid.glyph, liquid.fg, liquid.bg);
+        }
+        let feature = look(self.fixture());
         sprite.mix(feature.glyph, feature.fg, feature.bg);
 
+        // Gas tints the cell - thicker gas tints more
+        let volume = self.gas_volume();
+        if volume > 0 {
+            let gas = self.gas();
+            let pct = volume.min(LAYER_VOLUME) as f32 / LAYER_VOLUME as f32;
+            sprite.bg = RGBA::blend(&sprite.bg, &gas.bg, pct);
+            sprite.fg = RGBA::blend(&sprite.fg, &gas.fg, pct);
+        }
+
         // for tile in self.tiles.iter() {
         //     if tile.glyph > 0 {
         //         sprite.glyph = tile.glyph;
@@ -0,0 +1,302 @@
+use gw_util::blob::{Blob, BlobConfig};
+use gw_util::grid::Grid;
+use gw_util::point::{Point, DIRS4};
+use gw_util::rng::RandomNumberGenerator;
+use std::fmt::Debug;
+
+/// What is in a cell of a level (or room) while it is being designed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Site {
+    #[default]
+    Nothing, // solid rock
+    Floor,
+    Door,
+    SecretDoor,
+    Lake,
+    Bridge,
+}
+
+impl Site {
+    pub fn is_passable(&self) -> bool {
+        matches!(
+            self,
+            Site::Floor | Site::Door | Site::SecretDoor | Site::Bridge
+        )
+    }
+}
+
+/// A designed room - the floor cells and a door site (if any) for each of the 4 directions (up, right, down, left)
+#[derive(Debug, Clone)]
+pub struct Room {
+    pub grid: Grid<Site>,
+    pub doors: [Option<Point>; 4],
+}
+
+impl Room {
+    pub fn new(grid: Grid<Site>, rng: &mut RandomNumberGenerator) -> Self {
+        let doors = choose_door_sites(&grid, rng);
+        Room { grid, doors }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        self.grid.size()
+    }
+
+    /// The floor cells of the room
+    pub fn floor(&self) -> impl Iterator<Item = Point> + '_ {
+        self.grid
+            .iter()
+            .filter(|(_, _, site)| **site == Site::Floor)
+            .map(|(x, y, _)| Point::new(x, y))
+    }
+}
+
+pub fn opposite_dir(dir: usize) -> usize {
+    (dir + 2) % 4
+}
+
+/// Picks a door site for each direction - an empty cell next to the floor with nothing beyond it in that direction
+fn choose_door_sites(grid: &Grid<Site>, rng: &mut RandomNumberGenerator) -> [Option<Point>; 4] {
+    let mut doors = [None; 4];
+    for (i, dir) in DIRS4.iter().enumerate() {
+        let mut sites = Vec::new();
+        for (x, y, site) in grid.iter() {
+            if *site != Site::Nothing {
+                continue;
+            }
+            if grid.get(x - dir.x, y - dir.y) != Some(&Site::Floor) {
+                continue;
+            }
+            let mut pt = Point::new(x, y);
+            let mut clear = true;
+            while grid.has_xy(pt.x, pt.y) {
+                if *grid.get_unchecked(pt.x, pt.y) != Site::Nothing {
+                    clear = false;
+                    break;
+                }
+                pt = Point::new(pt.x + dir.x, pt.y + dir.y);
+            }
+            if clear {
+                sites.push(Point::new(x, y));
+            }
+        }
+        doors[i] = rng.random_slice_entry(&sites).copied();
+    }
+    doors
+}
+
+/// Designs a room for the dungeon generator
+pub trait RoomDesigner: Send + Sync + Debug {
+    /// The floor of the room - the grid should leave a 1 cell border around the floor for the door sites
+    fn design(&self, rng: &mut RandomNumberGenerator) -> Grid<Site>;
+}
+
+/// Two overlapping rectangles
+#[derive(Debug, Clone)]
+pub struct CrossRoom {
+    pub max_width: u32,
+    pub max_height: u32,
+}
+
+impl Default for CrossRoom {
+    fn default() -> Self {
+        CrossRoom {
+            max_width: 12,
+            max_height: 7,
+        }
+    }
+}
+
+impl RoomDesigner for CrossRoom {
+    fn design(&self, rng: &mut RandomNumberGenerator) -> Grid<Site> {
+        let max_width = self.max_width.max(4) as i32;
+        let max_height = self.max_height.max(4) as i32;
+
+        let wide_w = rng.range(max_width / 2 + 1, max_width + 1);
+        let wide_h = rng.range(2, max_height / 2 + 1);
+        let tall_w = rng.range(2, (wide_w / 2).max(2) + 1);
+        let tall_h = rng.range(wide_h + 2, max_height + 1);
+
+        let mut grid = Grid::new(wide_w as usize + 2, tall_h as usize + 2, Site::Nothing);
+        let wide_top = 1 + rng.range(0, tall_h - wide_h + 1);
+        let tall_left = 1 + rng.range(0, wide_w - tall_w + 1);
+
+        for y in wide_top..wide_top + wide_h {
+            for x in 1..=wide_w {
+                grid.set(x, y, Site::Floor);
+            }
+        }
+        for y in 1..=tall_h {
+            for x in tall_left..tall_left + tall_w {
+                grid.set(x, y, Site::Floor);
+            }
+        }
+        grid
+    }
+}
+
+/// A round room
+#[derive(Debug, Clone)]
+pub struct CircularRoom {
+    pub min_radius: u32,
+    pub max_radius: u32,
+}
+
+impl Default for CircularRoom {
+    fn default() -> Self {
+        CircularRoom {
+            min_radius: 2,
+            max_radius: 5,
+        }
+    }
+}
+
+impl RoomDesigner for CircularRoom {
+    fn design(&self, rng: &mut RandomNumberGenerator) -> Grid<Site> {
+        let min = self.min_radius.max(1) as i32;
+        let radius = rng.range(min, self.max_radius.max(self.min_radius) as i32 + 1);
+        let size = (radius * 2 + 3) as usize;
+        let center = Point::new(radius + 1, radius + 1);
+
+        let mut grid = Grid::new(size, size, Site::Nothing);
+        let limit = radius as f32 + 0.5;
+        for y in 1..size as i32 - 1 {
+            for x in 1..size as i32 - 1 {
+                if Point::new(x, y).distance(&center) <= limit {
+                    grid.set(x, y, Site::Floor);
+                }
+            }
+        }
+        grid
+    }
+}
+
+/// An irregular cave (a cellular automata blob)
+#[derive(Debug, Clone)]
+pub struct CavernRoom {
+    pub min_width: u32,
+    pub min_height: u32,
+    pub max_width: u32,
+    pub max_height: u32,
+}
+
+impl Default for CavernRoom {
+    fn default() -> Self {
+        CavernRoom {
+            min_width: 6,
+            min_height: 4,
+            max_width: 18,
+            max_height: 10,
+        }
+    }
+}
+
+impl RoomDesigner for CavernRoom {
+    fn design(&self, rng: &mut RandomNumberGenerator) -> Grid<Site> {
+        let width = self.max_width.max(3);
+        let height = self.max_height.max(3);
+
+        let mut blob = Blob::new(BlobConfig {
+            rng: RandomNumberGenerator::seeded(rng.range(0, i32::MAX) as u64),
+            min_width: self.min_width.min(width - 1),
+            min_height: self.min_height.min(height - 1),
+            max_width: width,
+            max_height: height,
+            ..BlobConfig::default()
+        });
+
+        let mut grid = Grid::new(width as usize + 2, height as usize + 2, Site::Nothing);
+        blob.carve(width, height, |x, y| {
+            grid.set(x + 1, y + 1, Site::Floor);
+        });
+        grid
+    }
+}
+
+/// Adds a hallway to the room at one of its door sites.  The end of the hallway becomes the only door site.
+pub fn attach_hallway(
+    room: &Room,
+    min_length: u32,
+    max_length: u32,
+    rng: &mut RandomNumberGenerator,
+) -> Room {
+    let dirs: Vec<usize> = (0..4).filter(|i| room.doors[*i].is_some()).collect();
+    let dir = match rng.random_slice_entry(&dirs) {
+        None => return room.clone(),
+        Some(dir) => *dir,
+    };
+    let start = room.doors[dir].unwrap();
+    let step = DIRS4[dir];
+    let length = rng.range(
+        min_length.max(1) as i32,
+        max_length.max(min_length) as i32 + 1,
+    );
+
+    let (width, height) = room.size();
+    let (extra_x, extra_y) = (step.x.abs() * length, step.y.abs() * length);
+    let (offset_x, offset_y) = (
+        if step.x < 0 { extra_x } else { 0 },
+        if step.y < 0 { extra_y } else { 0 },
+    );
+
+    let mut grid = Grid::new(
+        width + extra_x as usize,
+        height + extra_y as usize,
+        Site::Nothing,
+    );
+    for (x, y, site) in room.grid.iter() {
+        grid.set(x + offset_x, y + offset_y, *site);
+    }
+
+    let mut pt = Point::new(start.x + offset_x, start.y + offset_y);
+    for _ in 0..length {
+        grid.set(pt.x, pt.y, Site::Floor);
+        pt = Point::new(pt.x + step.x, pt.y + step.y);
+    }
+
+    let mut doors = [None; 4];
+    doors[dir] = Some(pt);
+    Room { grid, doors }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn door_sites() {
+        let mut rng = RandomNumberGenerator::seeded(12345);
+        for designer in [
+            Box::new(CrossRoom::default()) as Box<dyn RoomDesigner>,
+            Box::new(CircularRoom::default()),
+            Box::new(CavernRoom::default()),
+        ] {
+            let room = Room::new(designer.design(&mut rng), &mut rng);
+            assert!(room.floor().count() > 0);
+            for (i, door) in room.doors.iter().enumerate() {
+                let door = door.unwrap();
+                let dir = DIRS4[i];
+                assert_eq!(room.grid.get(door.x, door.y), Some(&Site::Nothing));
+                assert_eq!(
+                    room.grid.get(door.x - dir.x, door.y - dir.y),
+                    Some(&Site::Floor)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hallway() {
+        let mut rng = RandomNumberGenerator::seeded(12345);
+        let room = Room::new(CircularRoom::default().design(&mut rng), &mut rng);
+        let floor = room.floor().count();
+        let with_hall = attach_hallway(&room, 4, 4, &mut rng);
+        assert_eq!(with_hall.floor().count(), floor + 4);
+        let doors: Vec<&Point> = with_hall.doors.iter().flatten().collect();
+        assert_eq!(doors.len(), 1);
+        assert_eq!(
+            with_hall.grid.get(doors[0].x, doors[0].y),
+            Some(&Site::Nothing)
+        );
+    }
+}
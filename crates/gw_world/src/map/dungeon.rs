@@ -0,0 +1,629 @@
+use super::{
+    attach_hallway, opposite_dir, Builder, CavernRoom, CircularRoom, CrossRoom, Room, RoomDesigner,
+    Site,
+};
+use gw_util::blob::{Blob, BlobConfig};
+use gw_util::grid::Grid;
+use gw_util::mask::get_area_mask;
+use gw_util::path::{BlockedSource, PathfindingSource};
+use gw_util::point::{Point, DIRS4};
+use gw_util::rng::RandomNumberGenerator;
+use std::collections::{HashSet, VecDeque};
+
+/// The longest bridge that will be built across a lake
+pub const MAX_BRIDGE_LENGTH: i32 = 10;
+const LAKE_TRIES: u32 = 20;
+
+/// The tiles used for each kind of site in the dungeon
+#[derive(Debug, Clone)]
+pub struct DungeonTiles {
+    pub floor: String,
+    pub wall: String,
+    pub door: String,
+    pub secret_door: String,
+    pub lake: String,
+    pub bridge: String,
+    pub up_stairs: String,
+    pub down_stairs: String,
+}
+
+impl Default for DungeonTiles {
+    fn default() -> Self {
+        DungeonTiles {
+            floor: "FLOOR".to_string(),
+            wall: "WALL".to_string(),
+            door: "DOOR".to_string(),
+            secret_door: "SECRET_DOOR".to_string(),
+            lake: "DEEP_WATER".to_string(),
+            bridge: "BRIDGE".to_string(),
+            up_stairs: "UP_STAIRS".to_string(),
+            down_stairs: "DOWN_STAIRS".to_string(),
+        }
+    }
+}
+
+/// A dungeon level generator - the room designers (with their frequencies) and the settings for each pass.
+/// Set a count/distance/chance to 0 to skip that pass.
+#[derive(Debug)]
+pub struct Dungeon {
+    pub tiles: DungeonTiles,
+    designers: Vec<(u32, Box<dyn RoomDesigner>)>,
+    pub room_attempts: u32,
+    pub hallway_chance: u32,
+    pub loop_distance: u32, // add a doorway between areas that are farther apart than this
+    pub lake_attempts: u32,
+    pub lake_max_width: u32,
+    pub lake_max_height: u32,
+    pub bridge_distance: u32, // add a bridge between shores that are farther apart than this
+    pub secret_door_chance: u32,
+    pub stairs: bool,
+}
+
+impl Dungeon {
+    /// A generator without any room designers
+    pub fn new() -> Self {
+        Dungeon {
+            tiles: DungeonTiles::default(),
+            designers: Vec::new(),
+            room_attempts: 100,
+            hallway_chance: 15,
+            loop_distance: 20,
+            lake_attempts: 4,
+            lake_max_width: 20,
+            lake_max_height: 12,
+            bridge_distance: 20,
+            secret_door_chance: 10,
+            stairs: true,
+        }
+    }
+
+    pub fn add_designer(&mut self, frequency: u32, designer: Box<dyn RoomDesigner>) -> &mut Self {
+        self.designers.push((frequency, designer));
+        self
+    }
+
+    fn pick_designer(&self, rng: &mut RandomNumberGenerator) -> Option<&dyn RoomDesigner> {
+        let total: u32 = self.designers.iter().map(|(f, _)| *f).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rng.rand(total);
+        for (frequency, designer) in self.designers.iter() {
+            if roll < *frequency {
+                return Some(designer.as_ref());
+            }
+            roll -= *frequency;
+        }
+        None
+    }
+
+    fn design_room(&self, rng: &mut RandomNumberGenerator, hallway: bool) -> Option<Room> {
+        let designer = self.pick_designer(rng)?;
+        let room = Room::new(designer.design(rng), rng);
+        match hallway && rng.chance(self.hallway_chance) {
+            false => Some(room),
+            true => Some(attach_hallway(&room, 2, 8, rng)),
+        }
+    }
+
+    /// Lays out the level - rooms, loops, lakes, bridges and secret doors
+    pub fn design(
+        &self,
+        width: u32,
+        height: u32,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Grid<Site>, String> {
+        let mut grid = Grid::new(width as usize, height as usize, Site::Nothing);
+
+        self.add_rooms(&mut grid, rng)?;
+        if self.loop_distance > 0 {
+            add_loops(&mut grid, self.loop_distance, rng);
+        }
+        for _ in 0..self.lake_attempts {
+            add_lake(&mut grid, self.lake_max_width, self.lake_max_height, rng);
+        }
+        if self.bridge_distance > 0 {
+            add_bridges(&mut grid, self.bridge_distance, rng);
+        }
+        if self.secret_door_chance > 0 {
+            add_secret_doors(&mut grid, self.secret_door_chance, rng);
+        }
+
+        match is_connected(&grid) {
+            false => Err("Dungeon level is not connected".to_string()),
+            true => Ok(grid),
+        }
+    }
+
+    fn add_rooms(
+        &self,
+        grid: &mut Grid<Site>,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<(), String> {
+        // The first room goes in the middle of the level
+        let first = match self.design_room(rng, false) {
+            None => return Err("Dungeon has no room designers".to_string()),
+            Some(room) => room,
+        };
+        let (room_w, room_h) = first.size();
+        let offset = Point::new(
+            (grid.width() as i32 - room_w as i32) / 2,
+            (grid.height() as i32 - room_h as i32) / 2,
+        );
+        if !room_fits(grid, &first, offset) {
+            return Err("Dungeon level is too small for the first room".to_string());
+        }
+        insert_room(grid, &first, offset);
+
+        for _ in 0..self.room_attempts {
+            let room = match self.design_room(rng, true) {
+                None => continue,
+                Some(room) => room,
+            };
+            attach_room(grid, &room, rng);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Dungeon {
+    /// The standard designers - mostly cross rooms with some round rooms and caverns
+    fn default() -> Self {
+        let mut dungeon = Dungeon::new();
+        dungeon
+            .add_designer(10, Box::new(CrossRoom::default()))
+            .add_designer(5, Box::new(CircularRoom::default()))
+            .add_designer(3, Box::new(CavernRoom::default()));
+        dungeon
+    }
+}
+
+fn is_inside(grid: &Grid<Site>, x: i32, y: i32) -> bool {
+    x > 0 && y > 0 && x < grid.width() as i32 - 1 && y < grid.height() as i32 - 1
+}
+
+fn room_fits(grid: &Grid<Site>, room: &Room, offset: Point) -> bool {
+    room.floor().all(|pt| {
+        let (x, y) = (pt.x + offset.x, pt.y + offset.y);
+        if !is_inside(grid, x, y) {
+            return false;
+        }
+        (-1..=1).all(|dy| {
+            (-1..=1)
+                .all(|dx| grid.get(x + dx, y + dy).copied().unwrap_or_default() == Site::Nothing)
+        })
+    })
+}
+
+fn insert_room(grid: &mut Grid<Site>, room: &Room, offset: Point) {
+    for pt in room.floor() {
+        grid.set(pt.x + offset.x, pt.y + offset.y, Site::Floor);
+    }
+}
+
+/// Is the cell a place for a door leading out of the level's floor in the direction?
+fn is_door_site(grid: &Grid<Site>, x: i32, y: i32, dir: &Point) -> bool {
+    is_inside(grid, x, y)
+        && *grid.get_unchecked(x, y) == Site::Nothing
+        && grid.get(x - dir.x, y - dir.y) == Some(&Site::Floor)
+}
+
+/// Tries to attach the room to the level at a door site
+fn attach_room(grid: &mut Grid<Site>, room: &Room, rng: &mut RandomNumberGenerator) -> bool {
+    let width = grid.width();
+    for idx in rng.sequence(width * grid.height()) {
+        let (x, y) = ((idx % width) as i32, (idx / width) as i32);
+        for (dir_idx, dir) in DIRS4.iter().enumerate() {
+            let room_door = match room.doors[opposite_dir(dir_idx)] {
+                None => continue,
+                Some(pt) => pt,
+            };
+            if !is_door_site(grid, x, y, dir) {
+                continue;
+            }
+            let offset = Point::new(x - room_door.x, y - room_door.y);
+            if room_fits(grid, room, offset) {
+                insert_room(grid, room, offset);
+                grid.set(x, y, Site::Door);
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Walks the level (4 directions) to find the distance between the points.  None if it is farther than max_distance.
+pub fn walk_distance(grid: &Grid<Site>, from: Point, to: Point, max_distance: u32) -> Option<u32> {
+    let mut seen = HashSet::new();
+    let mut todo = VecDeque::new();
+    seen.insert(from);
+    todo.push_back((from, 0));
+
+    while let Some((pt, distance)) = todo.pop_front() {
+        if pt == to {
+            return Some(distance);
+        }
+        if distance >= max_distance {
+            continue;
+        }
+        for dir in DIRS4.iter() {
+            let next = Point::new(pt.x + dir.x, pt.y + dir.y);
+            if seen.contains(&next)
+                || !grid
+                    .get(next.x, next.y)
+                    .map(|s| s.is_passable())
+                    .unwrap_or(false)
+            {
+                continue;
+            }
+            seen.insert(next);
+            todo.push_back((next, distance + 1));
+        }
+    }
+    None
+}
+
+/// The walking distance (4 directions) from the point to every cell - u32::MAX for the cells that cannot be reached
+pub fn walk_distances(grid: &Grid<Site>, from: Point) -> Grid<u32> {
+    let mut distances = Grid::new(grid.width(), grid.height(), u32::MAX);
+    let mut todo = VecDeque::new();
+    distances.set(from.x, from.y, 0);
+    todo.push_back(from);
+
+    while let Some(pt) = todo.pop_front() {
+        let distance = *distances.get_unchecked(pt.x, pt.y);
+        for dir in DIRS4.iter() {
+            let (x, y) = (pt.x + dir.x, pt.y + dir.y);
+            if !grid.get(x, y).map(|s| s.is_passable()).unwrap_or(false) {
+                continue;
+            }
+            if *distances.get_unchecked(x, y) != u32::MAX {
+                continue;
+            }
+            distances.set(x, y, distance + 1);
+            todo.push_back(Point::new(x, y));
+        }
+    }
+    distances
+}
+
+/// Opens doorways in the walls that separate areas which are a long walk apart
+fn add_loops(grid: &mut Grid<Site>, min_distance: u32, rng: &mut RandomNumberGenerator) {
+    let width = grid.width();
+    for idx in rng.sequence(width * grid.height()) {
+        let (x, y) = ((idx % width) as i32, (idx / width) as i32);
+        if !is_inside(grid, x, y) || *grid.get_unchecked(x, y) != Site::Nothing {
+            continue;
+        }
+        for dir in DIRS4.iter().take(2) {
+            let a = Point::new(x - dir.x, y - dir.y);
+            let b = Point::new(x + dir.x, y + dir.y);
+            let side = |pt: Point| *grid.get_unchecked(pt.x, pt.y);
+            if side(a) != Site::Floor || side(b) != Site::Floor {
+                continue;
+            }
+            // Only through a straight wall
+            if side(Point::new(x + dir.y, y + dir.x)) != Site::Nothing
+                || side(Point::new(x - dir.y, y - dir.x)) != Site::Nothing
+            {
+                continue;
+            }
+            if walk_distance(grid, a, b, min_distance).is_none() {
+                grid.set(x, y, Site::Door);
+                break;
+            }
+        }
+    }
+}
+
+/// Tries to add a lake (a blob) that does not cut the level into pieces
+fn add_lake(
+    grid: &mut Grid<Site>,
+    max_width: u32,
+    max_height: u32,
+    rng: &mut RandomNumberGenerator,
+) -> bool {
+    let width = max_width.min(grid.width() as u32 - 2).max(3);
+    let height = max_height.min(grid.height() as u32 - 2).max(3);
+
+    let mut blob = Blob::new(BlobConfig {
+        rng: RandomNumberGenerator::seeded(rng.range(0, i32::MAX) as u64),
+        min_width: 3,
+        min_height: 3,
+        max_width: width,
+        max_height: height,
+        ..BlobConfig::default()
+    });
+    let mut lake = Vec::new();
+    blob.carve(width, height, |x, y| lake.push(Point::new(x, y)));
+
+    for _ in 0..LAKE_TRIES {
+        let left = rng.range(1, grid.width() as i32 - width as i32);
+        let top = rng.range(1, grid.height() as i32 - height as i32);
+
+        let mut next = grid.clone();
+        let mut covered = 0;
+        let mut ok = true;
+        for pt in lake.iter() {
+            let (x, y) = (pt.x + left, pt.y + top);
+            if !is_inside(grid, x, y) {
+                continue;
+            }
+            match *grid.get_unchecked(x, y) {
+                Site::Door | Site::SecretDoor | Site::Bridge => {
+                    ok = false;
+                    break;
+                }
+                Site::Floor => covered += 1,
+                _ => {}
+            }
+            next.set(x, y, Site::Lake);
+        }
+
+        if ok && covered > 0 && is_connected(&next) {
+            *grid = next;
+            return true;
+        }
+    }
+    false
+}
+
+/// Builds bridges across the lakes where walking around is a long way
+fn add_bridges(grid: &mut Grid<Site>, min_distance: u32, rng: &mut RandomNumberGenerator) {
+    let width = grid.width();
+    for idx in rng.sequence(width * grid.height()) {
+        let (x, y) = ((idx % width) as i32, (idx / width) as i32);
+        if *grid.get_unchecked(x, y) != Site::Floor {
+            continue;
+        }
+        for dir in DIRS4.iter().skip(1).take(2) {
+            let is_lake = |pt: Point| grid.get(pt.x, pt.y) == Some(&Site::Lake);
+            let mut length = 1;
+            while length <= MAX_BRIDGE_LENGTH {
+                let pt = Point::new(x + dir.x * length, y + dir.y * length);
+                // Over open water
+                if !is_lake(pt)
+                    || !is_lake(Point::new(pt.x + dir.y, pt.y + dir.x))
+                    || !is_lake(Point::new(pt.x - dir.y, pt.y - dir.x))
+                {
+                    break;
+                }
+                length += 1;
+            }
+            if !(3..=MAX_BRIDGE_LENGTH).contains(&length) {
+                continue;
+            }
+            let end = Point::new(x + dir.x * length, y + dir.y * length);
+            if grid.get(end.x, end.y) != Some(&Site::Floor) {
+                continue;
+            }
+            if walk_distance(grid, Point::new(x, y), end, min_distance).is_some() {
+                continue;
+            }
+            for i in 1..length {
+                grid.set(x + dir.x * i, y + dir.y * i, Site::Bridge);
+            }
+        }
+    }
+}
+
+fn add_secret_doors(grid: &mut Grid<Site>, chance: u32, rng: &mut RandomNumberGenerator) {
+    let doors: Vec<(i32, i32)> = grid
+        .iter()
+        .filter(|(_, _, site)| **site == Site::Door)
+        .map(|(x, y, _)| (x, y))
+        .collect();
+    for (x, y) in doors {
+        if rng.chance(chance) {
+            grid.set(x, y, Site::SecretDoor);
+        }
+    }
+}
+
+/// Picks the up stairs at random and the down stairs as far away (walking) as possible
+pub fn place_stairs(grid: &Grid<Site>, rng: &mut RandomNumberGenerator) -> Option<(Point, Point)> {
+    let floor: Vec<Point> = grid
+        .iter()
+        .filter(|(_, _, site)| **site == Site::Floor)
+        .map(|(x, y, _)| Point::new(x, y))
+        .collect();
+    let up = *rng.random_slice_entry(&floor)?;
+
+    let distances = walk_distances(grid, up);
+    let down = floor
+        .iter()
+        .filter(|pt| *distances.get_unchecked(pt.x, pt.y) != u32::MAX)
+        .max_by_key(|pt| *distances.get_unchecked(pt.x, pt.y))?;
+    match *down == up {
+        true => None,
+        false => Some((up, *down)),
+    }
+}
+
+struct SiteSource<'a>(&'a Grid<Site>);
+
+impl<'a> PathfindingSource for SiteSource<'a> {
+    fn move_cost(&self, x: i32, y: i32) -> Option<f32> {
+        match self.is_blocked(x, y) {
+            true => None,
+            false => Some(1.0),
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.0.width() as u32, self.0.height() as u32)
+    }
+}
+
+impl<'a> BlockedSource for SiteSource<'a> {
+    fn is_blocked(&self, x: i32, y: i32) -> bool {
+        !self.0.get(x, y).map(|s| s.is_passable()).unwrap_or(false)
+    }
+}
+
+/// The number of separate (walkable) areas in the level
+pub fn area_count(grid: &Grid<Site>) -> usize {
+    let mask = get_area_mask(&SiteSource(grid));
+    let areas: HashSet<u8> = mask.iter().map(|(_, _, v)| *v).filter(|v| *v > 0).collect();
+    areas.len()
+}
+
+pub fn is_connected(grid: &Grid<Site>) -> bool {
+    area_count(grid) == 1
+}
+
+impl<'t> Builder<'t> {
+    /// Digs a dungeon level with the generator (using the builder's rng - see `with_seed`).
+    /// Sets the UP_STAIRS and DOWN_STAIRS (and START) locations.
+    pub fn dig_dungeon(&mut self, dungeon: &Dungeon) -> Result<(), String> {
+        let names = &dungeon.tiles;
+        let (width, height) = self.size();
+        let grid = dungeon.design(width, height, self.rng_mut())?;
+        let stairs = match dungeon.stairs {
+            false => None,
+            true => match place_stairs(&grid, self.rng_mut()) {
+                None => return Err("No room for the stairs".to_string()),
+                Some(stairs) => Some(stairs),
+            },
+        };
+
+        let mut used = vec![&names.floor, &names.wall];
+        for (_, _, site) in grid.iter() {
+            match site {
+                Site::Door => used.push(&names.door),
+                Site::SecretDoor => used.push(&names.secret_door),
+                Site::Lake => used.push(&names.lake),
+                Site::Bridge => used.push(&names.bridge),
+                _ => {}
+            }
+        }
+        if stairs.is_some() {
+            used.push(&names.up_stairs);
+            used.push(&names.down_stairs);
+        }
+        if let Some(name) = used.iter().find(|name| self.tiles().get(name).is_none()) {
+            return Err(format!("Unknown dungeon tile - {}", name));
+        }
+
+        self.fill(&names.wall);
+        for (x, y, site) in grid.iter() {
+            if *site == Site::Nothing {
+                continue;
+            }
+            self.set_tile(x, y, &names.floor);
+            match site {
+                Site::Door => self.place_tile(x, y, &names.door),
+                Site::SecretDoor => self.place_tile(x, y, &names.secret_door),
+                Site::Lake => self.place_tile(x, y, &names.lake),
+                Site::Bridge => {
+                    self.place_tile(x, y, &names.lake);
+                    self.place_tile(x, y, &names.bridge);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some((up, down)) = stairs {
+            self.place_tile(up.x, up.y, &names.up_stairs);
+            self.place_tile(down.x, down.y, &names.down_stairs);
+            self.set_location("UP_STAIRS", up)
+                .set_location("DOWN_STAIRS", down)
+                .set_location("START", up);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::map::Map;
+    use crate::tile::{TileBuilder, TileLayer, Tiles};
+
+    fn make_tiles() -> Tiles {
+        let mut tiles = Tiles::default();
+        for id in ["DOOR", "SECRET_DOOR", "BRIDGE", "UP_STAIRS", "DOWN_STAIRS"] {
+            tiles.insert(TileBuilder::new(id).layer(TileLayer::FIXTURE).build());
+        }
+        tiles.insert(TileBuilder::new("DEEP_WATER").build());
+        tiles
+    }
+
+    fn dig(tiles: &Tiles, seed: u64) -> Map {
+        let mut builder = Builder::new(tiles, 80, 40);
+        builder.with_seed(seed);
+        builder.dig_dungeon(&Dungeon::default()).unwrap();
+        builder.build()
+    }
+
+    #[test]
+    fn design_is_connected() {
+        let mut rng = RandomNumberGenerator::seeded(12345);
+        let dungeon = Dungeon::default();
+        for _ in 0..5 {
+            let grid = dungeon.design(80, 40, &mut rng).unwrap();
+            assert!(is_connected(&grid));
+            assert!(grid.count(Site::Door) + grid.count(Site::SecretDoor) > 5);
+            for x in 0..80 {
+                assert_eq!(grid.get(x, 0), Some(&Site::Nothing));
+                assert_eq!(grid.get(x, 39), Some(&Site::Nothing));
+            }
+        }
+    }
+
+    #[test]
+    fn area_count_finds_separate_areas() {
+        let mut grid = Grid::new(7, 3, Site::Nothing);
+        for x in 1..6 {
+            grid.set(x, 1, Site::Floor);
+        }
+        assert!(is_connected(&grid));
+        grid.set(3, 1, Site::Lake);
+        assert_eq!(area_count(&grid), 2);
+        grid.set(3, 1, Site::Bridge);
+        assert!(is_connected(&grid));
+    }
+
+    #[test]
+    fn lake_keeps_level_connected() {
+        let mut rng = RandomNumberGenerator::seeded(12345);
+        let mut grid = Grid::new(40, 20, Site::Nothing);
+        for y in 1..19 {
+            for x in 1..39 {
+                grid.set(x, y, Site::Floor);
+            }
+        }
+        assert!(add_lake(&mut grid, 12, 8, &mut rng));
+        assert!(grid.count(Site::Lake) > 0);
+        assert!(is_connected(&grid));
+    }
+
+    #[test]
+    fn stairs_far_apart() {
+        let tiles = make_tiles();
+        let map = dig(&tiles, 1234);
+
+        let up = map.get_location("UP_STAIRS").unwrap();
+        let down = map.get_location("DOWN_STAIRS").unwrap();
+        assert_eq!(map.get_location("START"), Some(up));
+        assert_eq!(map.fixture[up].id, "UP_STAIRS");
+        assert_eq!(map.fixture[down].id, "DOWN_STAIRS");
+        assert!(map.to_point(up).distance(&map.to_point(down)) > 20.0);
+    }
+
+    #[test]
+    fn same_seed_same_level() {
+        let tiles = make_tiles();
+        let a = dig(&tiles, 42);
+        let b = dig(&tiles, 42);
+        let c = dig(&tiles, 43);
+
+        let ids = |map: &Map| -> Vec<String> {
+            (0..map.ground.len())
+                .map(|i| format!("{}/{}", map.ground[i].id, map.fixture[i].id))
+                .collect()
+        };
+        assert_eq!(ids(&a), ids(&b));
+        assert_ne!(ids(&a), ids(&c));
+    }
+}
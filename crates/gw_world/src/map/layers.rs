@@ -0,0 +1,271 @@
+use super::{update_promotions, Cell, Map};
+use crate::action::dead::kill_if_dead;
+use crate::effect::{fire_cell_action, EffectResult};
+use crate::position::Position;
+use crate::task::Executor;
+use crate::tile::{Tile, Tiles, NO_TILE};
+use gw_ecs::prelude::{Entity, World};
+use gw_util::rng::RandomNumberGenerator;
+use std::sync::Arc;
+
+/// The executor time between liquid/gas updates
+pub const LAYER_TURN_TIME: u64 = 100;
+
+/// Tracks the last time the liquid and gas layers were updated
+#[derive(Debug, Default)]
+pub struct LayerClock {
+    last_tick: Option<u64>,
+}
+
+#[derive(Clone, Copy)]
+enum Layer {
+    Liquid,
+    Gas,
+}
+
+impl Layer {
+    fn get(&self, map: &Map, idx: usize) -> (Arc<Tile>, u32) {
+        match self {
+            Layer::Liquid => (map.liquid[idx].clone(), map.liquid_volume(idx)),
+            Layer::Gas => (map.gas[idx].clone(), map.gas_volume(idx)),
+        }
+    }
+
+    fn set(&self, map: &mut Map, idx: usize, tile: Arc<Tile>, volume: u32) {
+        match self {
+            Layer::Liquid => map.set_liquid(idx, tile, volume),
+            Layer::Gas => map.set_gas(idx, tile, volume),
+        }
+    }
+}
+
+/// Runs one turn of spreading and dissipating for the liquids and gases on the map
+pub fn update_layers(map: &mut Map) {
+    update_layer(map, Layer::Liquid);
+    update_layer(map, Layer::Gas);
+}
+
+fn update_layer(map: &mut Map, layer: Layer) {
+    let count = map.ground.len();
+    let mut tiles: Vec<Arc<Tile>> = Vec::with_capacity(count);
+    let mut volumes: Vec<u32> = Vec::with_capacity(count);
+    for idx in 0..count {
+        let (tile, volume) = layer.get(map, idx);
+        tiles.push(tile);
+        volumes.push(volume);
+    }
+
+    if volumes.iter().all(|v| *v == 0) {
+        return;
+    }
+
+    // Spread - each open neighbor gets an even share (as if averaging over the 3x3 block)
+    let mut next_tiles = tiles.clone();
+    let mut next = volumes.clone();
+    for idx in 0..count {
+        let volume = volumes[idx];
+        let tile = &tiles[idx];
+        if volume == 0 || tile.spread == 0 {
+            continue;
+        }
+        let share = volume * tile.spread / 100 / 9;
+        if share == 0 {
+            continue;
+        }
+
+        let (x, y) = map.to_xy(idx);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let n_idx = match map.get_index(x + dx, y + dy) {
+                    None => continue,
+                    Some(n) => n,
+                };
+                if map.get_cell(n_idx).unwrap().blocks() {
+                    continue;
+                }
+                // Different liquids/gases do not mix
+                if next[n_idx] > 0 && !Arc::ptr_eq(&next_tiles[n_idx], tile) {
+                    continue;
+                }
+                next_tiles[n_idx] = tile.clone();
+                next[n_idx] += share;
+                next[idx] -= share;
+            }
+        }
+    }
+
+    // Dissipate
+    for idx in 0..count {
+        let volume = next[idx];
+        let dissipate = next_tiles[idx].dissipate;
+        if volume == 0 || dissipate == 0 {
+            continue;
+        }
+        let loss = (volume * dissipate).div_ceil(100);
+        next[idx] = volume.saturating_sub(loss);
+    }
+
+    for idx in 0..count {
+        if next[idx] == volumes[idx] && Arc::ptr_eq(&next_tiles[idx], &tiles[idx]) {
+            continue;
+        }
+        let tile = match next[idx] {
+            0 => NO_TILE.clone(),
+            _ => next_tiles[idx].clone(),
+        };
+        layer.set(map, idx, tile, next[idx]);
+    }
+}
+
+/// Runs the layer and promotion (fire) updates for all of the turns that have passed since the last update
+pub fn update_environment(world: &mut World) {
+    if !world.has_resource::<Map>() {
+        return;
+    }
+
+    let now = world.read_resource::<Executor>().time();
+    let turns = {
+        let mut clock = world.write_resource::<LayerClock>();
+        let last = clock.last_tick.unwrap_or(now);
+        let turns = now.saturating_sub(last) / LAYER_TURN_TIME;
+        clock.last_tick = Some(last + turns * LAYER_TURN_TIME);
+        turns
+    };
+
+    if turns == 0 {
+        return;
+    }
+
+    let tiles = world.read_global::<Tiles>();
+    let mut rng = world.write_resource::<RandomNumberGenerator>();
+    let mut map = world.write_resource::<Map>();
+    for _ in 0..turns {
+        update_layers(&mut map);
+        update_promotions(&mut map, &tiles, &mut rng);
+    }
+}
+
+/// Fires the "stand" effects of the cell the entity is in (e.g. burning or poison gas).
+/// Returns false if the entity died.
+pub fn fire_stand_effects(world: &mut World, entity: Entity) -> bool {
+    let pos = match world.read_component::<Position>().get(entity) {
+        None => return true,
+        Some(pos) => pos.point(),
+    };
+
+    let has_effects = {
+        let map = world.read_resource::<Map>();
+        match map.get_index(pos.x, pos.y) {
+            None => false,
+            Some(idx) => map.has_cell_effects(idx, "stand"),
+        }
+    };
+    if !has_effects {
+        return true;
+    }
+
+    if let EffectResult::Fail = fire_cell_action(world, pos, "stand", Some(entity)) {
+        return true;
+    }
+    !kill_if_dead(world, entity, None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::{Being, Stat, Stats};
+    use crate::map::LAYER_VOLUME;
+    use crate::task::do_next_task;
+    use crate::test_util::make_ecs;
+    use crate::tile::{TileBuilder, TileLayer};
+    use gw_util::value::Value;
+    use std::collections::HashMap;
+
+    fn gas_tile(spread: u32, dissipate: u32) -> Arc<Tile> {
+        TileBuilder::new("GAS")
+            .layer(TileLayer::GAS)
+            .spread(spread)
+            .dissipate(dissipate)
+            .build()
+    }
+
+    #[test]
+    fn gas_spreads() {
+        let mut map = Map::new(5, 5);
+        let gas = gas_tile(100, 0);
+        let idx = map.get_index(2, 2).unwrap();
+        map.add_gas(idx, gas, 900);
+
+        update_layers(&mut map);
+        assert_eq!(map.gas_volume(idx), 100);
+        assert_eq!(map.gas_volume(map.get_index(1, 1).unwrap()), 100);
+        assert_eq!(map.gas_volume(map.get_index(0, 0).unwrap()), 0);
+
+        let total: u32 = (0..25).map(|i| map.gas_volume(i)).sum();
+        assert_eq!(total, 900);
+    }
+
+    #[test]
+    fn liquid_evaporates() {
+        let mut map = Map::new(3, 3);
+        let blood = TileBuilder::new("BLOOD")
+            .layer(TileLayer::LIQUID)
+            .dissipate(50)
+            .build();
+        map.place_tile(4, blood);
+        assert_eq!(map.liquid_volume(4), LAYER_VOLUME);
+
+        update_layers(&mut map);
+        assert_eq!(map.liquid_volume(4), 50);
+        for _ in 0..10 {
+            update_layers(&mut map);
+        }
+        assert_eq!(map.liquid_volume(4), 0);
+        assert!(crate::tile::tile_is_none(&map.liquid[4]));
+        // No spread
+        assert_eq!(map.liquid_volume(3), 0);
+    }
+
+    #[test]
+    fn gas_hurts_beings() {
+        let mut ecs = make_ecs(7, 7);
+        let world = ecs.current_world_mut();
+
+        let mut builder = TileBuilder::new("BURNING_GAS").layer(TileLayer::GAS);
+        let mut effects = HashMap::new();
+        effects.insert("damage".into(), Value::from(2));
+        builder.set(&"stand".into(), &Value::from(effects)).unwrap();
+        let gas = builder.build();
+
+        let mut being = Being::new("RAT".to_string());
+        being.name = Some("rat".to_string());
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 5);
+        let rat = world.spawn((being, stats, Position::new(3, 3)));
+        {
+            let mut map = world.write_resource::<Map>();
+            let idx = map.get_index(3, 3).unwrap();
+            map.add_gas(idx, gas, 50);
+        }
+
+        world.write_resource::<Executor>().insert(rat, 100);
+        let _ = do_next_task(world);
+        assert_eq!(
+            world
+                .read_component::<Stats>()
+                .get(rat)
+                .unwrap()
+                .get(Stat::HEALTH),
+            Some(3)
+        );
+
+        world.write_resource::<Executor>().insert(rat, 100);
+        let _ = do_next_task(world);
+        world.write_resource::<Executor>().insert(rat, 100);
+        let _ = do_next_task(world);
+        assert!(!world.entities().is_alive(rat));
+    }
+}
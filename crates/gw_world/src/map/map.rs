@@ -1,6 +1,6 @@
 use super::{Cell, CellMut, CellRef};
 use super::{CellFlags, MapFlags};
-use crate::effect::BoxedEffect;
+use crate::effect::{parse_effects, BoxedEffect};
 use crate::tile::Tile;
 use crate::tile::TileLayer;
 use crate::tile::NO_TILE;
@@ -10,11 +10,15 @@ use gw_util::point::distance;
 use gw_util::point::Point;
 use gw_util::rect::Rect;
 use gw_util::rng::RandomNumberGenerator;
+use gw_util::value::Value;
 use gw_util::xy::Lock;
 use gw_util::xy::Wrap;
 use std::collections::HashMap;
 use std::sync::Arc; // For FOV Calc
 
+/// The volume used when a liquid or gas tile is placed without one
+pub const LAYER_VOLUME: u32 = 100;
+
 #[derive(Default)]
 pub struct Map {
     pub id: u32,
@@ -31,11 +35,17 @@ pub struct Map {
 
     pub locations: HashMap<String, usize>,
     pub cell_effects: HashMap<usize, HashMap<String, Vec<BoxedEffect>>>,
+    pub cell_effect_data: HashMap<usize, HashMap<String, Vec<Value>>>, // what the cell effects were parsed from (for saving)
     pub flavors: HashMap<usize, String>,
+    pub stashes: HashMap<usize, Vec<Entity>>, // hidden items (e.g. in a chest)
 
     // per cell information
     pub ground: Vec<Arc<Tile>>,
     pub fixture: Vec<Arc<Tile>>,
+    pub liquid: Vec<Arc<Tile>>,
+    pub gas: Vec<Arc<Tile>>,
+    liquid_volume: Vec<u32>,
+    gas_volume: Vec<u32>,
     pub blocked: Vec<bool>, // TODO - Move to flag
     pub beings: Vec<Vec<(Entity, bool)>>,
     pub items: Vec<Vec<(Entity, bool)>>,
@@ -67,8 +77,14 @@ impl Map {
 
             ground: vec![fill_tile.clone(); count],
             fixture: vec![fill_tile.clone(); count],
+            liquid: vec![fill_tile.clone(); count],
+            gas: vec![fill_tile.clone(); count],
+            liquid_volume: vec![0; count],
+            gas_volume: vec![0; count],
             cell_effects: HashMap::new(),
+            cell_effect_data: HashMap::new(),
             flavors: HashMap::new(),
+            stashes: HashMap::new(),
 
             blocked: vec![false; count],
             beings: vec![Vec::new(); count],
@@ -171,6 +187,26 @@ impl Map {
         self.locations.insert(id.to_string(), index);
     }
 
+    /// The location with the id - or the closest one with that id and a machine suffix (e.g. "ALTAR#123")
+    pub fn find_location(&self, id: &str, near: Point) -> Option<usize> {
+        if let Some(idx) = self.get_location(id) {
+            return Some(idx);
+        }
+        let prefix = format!("{}#", id);
+        self.locations
+            .iter()
+            .filter(|(name, _)| name.starts_with(&prefix))
+            .map(|(_, idx)| *idx)
+            .min_by_key(|idx| {
+                let pt = self.to_point(*idx);
+                ((pt.x - near.x).abs().max((pt.y - near.y).abs()), *idx)
+            })
+    }
+
+    pub fn flags(&self) -> MapFlags {
+        self.flags
+    }
+
     pub fn reveal_all(&mut self) {
         self.flags.insert(MapFlags::ALL_REVEALED);
     }
@@ -210,6 +246,10 @@ impl Map {
         }
         self.ground[idx] = ground;
         self.fixture[idx] = NO_TILE.clone();
+        self.liquid[idx] = NO_TILE.clone();
+        self.gas[idx] = NO_TILE.clone();
+        self.liquid_volume[idx] = 0;
+        self.gas_volume[idx] = 0;
 
         self.cell_flags[idx]
             .insert(CellFlags::NEEDS_DRAW | CellFlags::TILE_CHANGED | CellFlags::NEEDS_SNAPSHOT);
@@ -220,6 +260,8 @@ impl Map {
         match tile.layer {
             TileLayer::GROUND => self.place_ground(index, tile),
             TileLayer::FIXTURE => self.place_fixture(index, tile),
+            TileLayer::LIQUID => self.add_liquid(index, tile, LAYER_VOLUME),
+            TileLayer::GAS => self.add_gas(index, tile, LAYER_VOLUME),
             _ => false,
         }
     }
@@ -228,6 +270,8 @@ impl Map {
         match tile.layer {
             TileLayer::GROUND => self.force_ground(index, tile),
             TileLayer::FIXTURE => self.force_fixture(index, tile),
+            TileLayer::LIQUID => self.set_liquid(index, tile, LAYER_VOLUME),
+            TileLayer::GAS => self.set_gas(index, tile, LAYER_VOLUME),
             _ => {}
         }
     }
@@ -281,6 +325,82 @@ impl Map {
         self.any_tile_change = true;
     }
 
+    pub fn liquid_volume(&self, index: usize) -> u32 {
+        self.liquid_volume.get(index).copied().unwrap_or(0)
+    }
+
+    pub fn gas_volume(&self, index: usize) -> u32 {
+        self.gas_volume.get(index).copied().unwrap_or(0)
+    }
+
+    /// Adds liquid to the cell.  A different liquid is only replaced by a larger volume.
+    pub fn add_liquid(&mut self, index: usize, liquid: Arc<Tile>, volume: u32) -> bool {
+        if !self.has_index(index) || volume == 0 {
+            return false;
+        }
+        let current = self.liquid_volume[index];
+        if current > 0 && !Arc::ptr_eq(&self.liquid[index], &liquid) {
+            if current >= volume {
+                return false;
+            }
+            self.set_liquid(index, liquid, volume);
+        } else {
+            self.set_liquid(index, liquid, current.saturating_add(volume));
+        }
+        true
+    }
+
+    /// Sets the liquid and volume of the cell (volume 0 = no liquid)
+    pub fn set_liquid(&mut self, index: usize, liquid: Arc<Tile>, volume: u32) {
+        if !self.has_index(index) {
+            return;
+        }
+        match volume {
+            0 => self.liquid[index] = NO_TILE.clone(),
+            _ => self.liquid[index] = liquid,
+        }
+        self.liquid_volume[index] = volume;
+        self.cell_flags[index].insert(CellFlags::NEEDS_DRAW | CellFlags::NEEDS_SNAPSHOT);
+    }
+
+    pub fn clear_liquid(&mut self, index: usize) {
+        self.set_liquid(index, NO_TILE.clone(), 0);
+    }
+
+    /// Adds gas to the cell.  A different gas is only replaced by a larger volume.
+    pub fn add_gas(&mut self, index: usize, gas: Arc<Tile>, volume: u32) -> bool {
+        if !self.has_index(index) || volume == 0 {
+            return false;
+        }
+        let current = self.gas_volume[index];
+        if current > 0 && !Arc::ptr_eq(&self.gas[index], &gas) {
+            if current >= volume {
+                return false;
+            }
+            self.set_gas(index, gas, volume);
+        } else {
+            self.set_gas(index, gas, current.saturating_add(volume));
+        }
+        true
+    }
+
+    /// Sets the gas and volume of the cell (volume 0 = no gas)
+    pub fn set_gas(&mut self, index: usize, gas: Arc<Tile>, volume: u32) {
+        if !self.has_index(index) {
+            return;
+        }
+        match volume {
+            0 => self.gas[index] = NO_TILE.clone(),
+            _ => self.gas[index] = gas,
+        }
+        self.gas_volume[index] = volume;
+        self.cell_flags[index].insert(CellFlags::NEEDS_DRAW | CellFlags::NEEDS_SNAPSHOT);
+    }
+
+    pub fn clear_gas(&mut self, index: usize) {
+        self.set_gas(index, NO_TILE.clone(), 0);
+    }
+
     pub fn add_effect(&mut self, index: usize, action: &str, effect: BoxedEffect) {
         match self.cell_effects.get_mut(&index) {
             None => {
@@ -299,7 +419,25 @@ impl Map {
         }
     }
 
+    /// Parses the effects ({ <ID>: <CONFIG>, ... }) and adds them to the cell.
+    /// The data is kept so that the effects can be saved.
+    pub fn add_effects(&mut self, index: usize, action: &str, data: Value) -> Result<(), String> {
+        for effect in parse_effects(&data)? {
+            self.add_effect(index, action, effect);
+        }
+        self.cell_effect_data
+            .entry(index)
+            .or_default()
+            .entry(action.to_string())
+            .or_default()
+            .push(data);
+        Ok(())
+    }
+
     pub fn set_effects(&mut self, index: usize, action: &str, effects: Vec<BoxedEffect>) {
+        if let Some(data) = self.cell_effect_data.get_mut(&index) {
+            data.remove(action);
+        }
         match self.cell_effects.get_mut(&index) {
             None => {
                 let mut map = HashMap::new();
@@ -335,9 +473,48 @@ impl Map {
             }
         }
 
+        if !self.has_flag(index, CellFlags::NO_LIQUID_EVENTS) {
+            if let Some(tile) = self.liquid.get(index) {
+                if let Some(liquid_effects) = tile.effects.get(action) {
+                    effects.extend_from_slice(liquid_effects);
+                }
+            }
+        }
+
+        if !self.has_flag(index, CellFlags::NO_GAS_EVENTS) {
+            if let Some(tile) = self.gas.get(index) {
+                if let Some(gas_effects) = tile.effects.get(action) {
+                    effects.extend_from_slice(gas_effects);
+                }
+            }
+        }
+
         Some(effects)
     }
 
+    /// Whether or not any effects are registered for the action at the cell
+    pub fn has_cell_effects(&self, index: usize, action: &str) -> bool {
+        let action = action.to_uppercase();
+        if let Some(all) = self.cell_effects.get(&index) {
+            if all.get(&action).map(|v| !v.is_empty()).unwrap_or(false) {
+                return true;
+            }
+        }
+        let tile_has = |tile: Option<&Arc<Tile>>| match tile {
+            None => false,
+            Some(tile) => tile
+                .effects
+                .get(&action)
+                .map(|v| !v.is_empty())
+                .unwrap_or(false),
+        };
+        tile_has(self.ground.get(index))
+            || tile_has(self.fixture.get(index))
+            || (!self.has_flag(index, CellFlags::NO_LIQUID_EVENTS)
+                && tile_has(self.liquid.get(index)))
+            || (!self.has_flag(index, CellFlags::NO_GAS_EVENTS) && tile_has(self.gas.get(index)))
+    }
+
     pub fn set_flavor(&mut self, index: usize, text: String) {
         self.flavors.insert(index, text);
     }
@@ -359,6 +536,27 @@ impl Map {
         self.ground[idx].blocks()
     }
 
+    /// The number of separate passable arcs around the cell - more than 1 means the cell is a chokepoint (e.g. a hallway)
+    pub fn passable_arc_count(&self, x: i32, y: i32) -> u32 {
+        const CLOCK: [(i32, i32); 8] = [
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ];
+        let passable = |(dx, dy): &(i32, i32)| match self.get_index(x + dx, y + dy) {
+            None => false,
+            Some(idx) => !self.get_cell(idx).unwrap().blocks(),
+        };
+        let ring: Vec<bool> = CLOCK.iter().map(passable).collect();
+        let changes = (0..8).filter(|i| ring[*i] != ring[(i + 1) % 8]).count() as u32;
+        changes / 2
+    }
+
     pub fn iter_beings(&self, idx: usize) -> impl Iterator<Item = Entity> + '_ {
         if !self.has_index(idx) {
             panic!("asked for actors at invalid index: {}", idx);
@@ -366,6 +564,15 @@ impl Map {
         self.beings[idx].iter().map(|(a, _)| *a)
     }
 
+    /// The first being at the index that blocks movement
+    pub fn blocking_being(&self, idx: usize) -> Option<Entity> {
+        self.beings
+            .get(idx)?
+            .iter()
+            .find(|(_, blocks)| *blocks)
+            .map(|(entity, _)| *entity)
+    }
+
     pub fn remove_being(&mut self, idx: usize, entity: Entity) {
         if !self.has_index(idx) {
             return;
@@ -443,6 +650,23 @@ impl Map {
         }
     }
 
+    /// Adds the items to the hidden stash at the index
+    pub fn stash_items(&mut self, idx: usize, items: Vec<Entity>) {
+        if !self.has_index(idx) {
+            panic!("Invalid map index for stash_items: {}", idx);
+        }
+        self.stashes.entry(idx).or_default().extend(items);
+    }
+
+    pub fn iter_stash(&self, idx: usize) -> impl Iterator<Item = Entity> + '_ {
+        self.stashes.get(&idx).into_iter().flatten().copied()
+    }
+
+    /// Removes all of the items from the stash at the index
+    pub fn take_stash(&mut self, idx: usize) -> Vec<Entity> {
+        self.stashes.remove(&idx).unwrap_or_default()
+    }
+
     pub fn is_opaque(&self, idx: usize) -> bool {
         if !self.has_index(idx) {
             return false;
@@ -533,6 +757,22 @@ impl Map {
         self.cell_flags[idx].remove(CellFlags::TILE_CHANGED)
     }
 
+    /// Clears the TILE_CHANGED flags, returning the indexes that had changed
+    pub fn take_tile_changes(&mut self) -> Vec<usize> {
+        if !self.any_tile_change {
+            return Vec::new();
+        }
+        self.any_tile_change = false;
+        let mut changed = Vec::new();
+        for (idx, flags) in self.cell_flags.iter_mut().enumerate() {
+            if flags.contains(CellFlags::TILE_CHANGED) {
+                flags.remove(CellFlags::TILE_CHANGED);
+                changed.push(idx);
+            }
+        }
+        changed
+    }
+
     pub fn clear_change_flags(&mut self) {
         self.any_tile_change = false;
         self.any_entity_change = false;
@@ -553,6 +793,8 @@ impl Map {
         for content in self.beings.iter_mut() {
             content.clear();
         }
+
+        self.stashes.clear();
     }
 
     pub fn set_flag(&mut self, index: usize, flag: CellFlags) {
@@ -1,15 +1,23 @@
 mod builder;
 mod cell;
 mod cell_flags;
+mod designer;
+mod dungeon;
 mod flavor;
+mod layers;
 mod map;
 mod map_flags;
 mod path;
+mod promote;
 
 pub use builder::*;
 pub use cell::*;
 pub use cell_flags::*;
+pub use designer::*;
+pub use dungeon::*;
 pub use flavor::*;
+pub use layers::*;
 pub use map::*;
 pub use map_flags::*;
 pub use path::*;
+pub use promote::*;
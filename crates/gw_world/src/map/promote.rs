@@ -0,0 +1,246 @@
+use super::Map;
+use crate::tile::{tile_is_none, Tile, TileFlags, TileLayer, Tiles};
+use gw_ecs::prelude::World;
+use gw_util::point::Point;
+use gw_util::rng::RandomNumberGenerator;
+use std::sync::Arc;
+
+const FIRE_DIRS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The tiles in the cell (ground, fixture and any liquid or gas)
+fn cell_tiles(map: &Map, idx: usize) -> Vec<Arc<Tile>> {
+    let mut all = vec![map.ground[idx].clone(), map.fixture[idx].clone()];
+    if map.liquid_volume(idx) > 0 {
+        all.push(map.liquid[idx].clone());
+    }
+    if map.gas_volume(idx) > 0 {
+        all.push(map.gas[idx].clone());
+    }
+    all.retain(|t| !tile_is_none(t));
+    all
+}
+
+fn layer_tile<'m>(map: &'m Map, idx: usize, layer: &TileLayer) -> Option<&'m Arc<Tile>> {
+    match layer {
+        TileLayer::GROUND => map.ground.get(idx),
+        TileLayer::FIXTURE => map.fixture.get(idx),
+        TileLayer::LIQUID => map.liquid.get(idx),
+        TileLayer::GAS => map.gas.get(idx),
+        _ => None,
+    }
+}
+
+fn clear_layer(map: &mut Map, idx: usize, layer: &TileLayer) {
+    match layer {
+        TileLayer::FIXTURE => map.clear_fixture(idx),
+        TileLayer::LIQUID => map.clear_liquid(idx),
+        TileLayer::GAS => map.clear_gas(idx),
+        _ => {}
+    }
+}
+
+/// Replaces the tile with the given one (clearing its layer if the new tile is on another layer)
+fn replace_tile(map: &mut Map, idx: usize, tile: &Arc<Tile>, with: Option<Arc<Tile>>) -> bool {
+    match with {
+        None => {
+            clear_layer(map, idx, &tile.layer);
+            true
+        }
+        Some(with) => {
+            if with.layer != tile.layer {
+                clear_layer(map, idx, &tile.layer);
+            }
+            map.place_tile(idx, with)
+        }
+    }
+}
+
+/// Turns the tile into its `promotes_to` tile (or removes it)
+pub fn promote_tile(map: &mut Map, idx: usize, tile: &Arc<Tile>, tiles: &Tiles) -> bool {
+    let next = match tile.promotes_to {
+        None => None,
+        Some(ref id) => match tiles.get(id) {
+            None => return false,
+            Some(t) => Some(t),
+        },
+    };
+    replace_tile(map, idx, tile, next)
+}
+
+/// Sets the flammable tiles in the cell on fire.  Returns true if anything caught.
+pub fn ignite_cell(
+    map: &mut Map,
+    idx: usize,
+    tiles: &Tiles,
+    rng: Option<&mut RandomNumberGenerator>,
+) -> bool {
+    let mut rng = rng;
+    let mut lit = false;
+    for tile in cell_tiles(map, idx) {
+        if !tile.is_flammable() {
+            continue;
+        }
+        if let Some(ref mut rng) = rng {
+            if !rng.chance(tile.burn) {
+                continue;
+            }
+        }
+        let fire = match tiles.get(tile.on_fire.as_ref().unwrap()) {
+            None => continue,
+            Some(fire) => fire,
+        };
+        lit |= replace_tile(map, idx, &tile, Some(fire));
+    }
+    lit
+}
+
+fn is_vacant(map: &Map, idx: usize) -> bool {
+    map.iter_beings(idx).next().is_none() && map.iter_items(idx).next().is_none()
+}
+
+/// Runs one turn of fire spreading and tile promotion
+pub fn update_promotions(map: &mut Map, tiles: &Tiles, rng: &mut RandomNumberGenerator) {
+    let count = map.ground.len();
+    let mut fires = Vec::new();
+    let mut promotions = Vec::new();
+
+    for idx in 0..count {
+        for tile in cell_tiles(map, idx) {
+            if tile.is_fire() {
+                fires.push(idx);
+            }
+            if tile.promote_chance == 0 {
+                continue;
+            }
+            if tile.flags.contains(TileFlags::PROMOTE_IF_VACANT) && !is_vacant(map, idx) {
+                continue;
+            }
+            if rng.chance(tile.promote_chance) {
+                promotions.push((idx, tile));
+            }
+        }
+    }
+    fires.dedup();
+
+    for idx in fires {
+        let (x, y) = map.to_xy(idx);
+        for (dx, dy) in FIRE_DIRS.iter() {
+            if let Some(n_idx) = map.get_index(x + dx, y + dy) {
+                ignite_cell(map, n_idx, tiles, Some(rng));
+            }
+        }
+    }
+
+    for (idx, tile) in promotions {
+        // The tile might have burned (or changed) already
+        let current = match layer_tile(map, idx, &tile.layer) {
+            None => continue,
+            Some(current) => current,
+        };
+        if !Arc::ptr_eq(current, &tile) {
+            continue;
+        }
+        promote_tile(map, idx, &tile, tiles);
+    }
+}
+
+/// Promotes the PROMOTE_ON_STEP tiles at the point (e.g. a door opening)
+pub fn promote_on_step(world: &mut World, pos: Point) {
+    let tiles = world.read_global::<Tiles>();
+    let mut map = world.write_resource::<Map>();
+    let idx = match map.get_wrapped_index(pos.x, pos.y) {
+        None => return,
+        Some(idx) => idx,
+    };
+    for tile in cell_tiles(&map, idx) {
+        if tile.flags.contains(TileFlags::PROMOTE_ON_STEP) {
+            promote_tile(&mut map, idx, &tile, &tiles);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::Being;
+    use crate::position::Position;
+    use crate::tile::TileBuilder;
+
+    fn make_tiles() -> Tiles {
+        let mut tiles = Tiles::default();
+        tiles.insert(TileBuilder::new("FLOOR").build());
+        tiles.insert(TileBuilder::new("ASH").build());
+        tiles.insert(TileBuilder::new("GRASS").burn(100, "BURNING_GRASS").build());
+        tiles.insert(
+            TileBuilder::new("BURNING_GRASS")
+                .flags(TileFlags::IS_FIRE)
+                .promotes(100, Some("ASH"))
+                .build(),
+        );
+        tiles.insert(
+            TileBuilder::new("DOOR")
+                .layer(TileLayer::FIXTURE)
+                .flags(TileFlags::PROMOTE_ON_STEP)
+                .promotes(0, Some("DOOR_OPEN"))
+                .build(),
+        );
+        tiles.insert(
+            TileBuilder::new("DOOR_OPEN")
+                .layer(TileLayer::FIXTURE)
+                .flags(TileFlags::PROMOTE_IF_VACANT)
+                .promotes(100, Some("DOOR"))
+                .build(),
+        );
+        tiles
+    }
+
+    #[test]
+    fn fire_spreads_and_burns_out() {
+        let tiles = make_tiles();
+        let mut rng = RandomNumberGenerator::seeded(12345);
+        let mut map = Map::new(5, 1);
+        map.fill(tiles.get("GRASS").unwrap());
+        map.force_ground(4, tiles.get("FLOOR").unwrap());
+
+        assert!(ignite_cell(&mut map, 0, &tiles, None));
+        assert_eq!(map.ground[0].id, "BURNING_GRASS");
+
+        update_promotions(&mut map, &tiles, &mut rng);
+        assert_eq!(map.ground[0].id, "ASH");
+        assert_eq!(map.ground[1].id, "BURNING_GRASS");
+        assert_eq!(map.ground[2].id, "GRASS");
+
+        for _ in 0..5 {
+            update_promotions(&mut map, &tiles, &mut rng);
+        }
+        assert_eq!(map.ground[3].id, "ASH");
+        assert_eq!(map.ground[4].id, "FLOOR");
+        assert!(map.has_tile_changed(3));
+    }
+
+    #[test]
+    fn doors_open_and_close() {
+        let tiles = make_tiles();
+        let mut rng = RandomNumberGenerator::seeded(12345);
+        let mut map = Map::new(3, 1);
+        map.fill(tiles.get("FLOOR").unwrap());
+        let door = tiles.get("DOOR").unwrap();
+        map.place_tile(1, door.clone());
+
+        assert!(promote_tile(&mut map, 1, &door, &tiles));
+        assert_eq!(map.fixture[1].id, "DOOR_OPEN");
+
+        // Stays open while someone is in the doorway
+        let mut ecs = gw_ecs::prelude::Ecs::empty();
+        crate::register_components(&mut ecs);
+        let world = ecs.create_world("TEST");
+        world.insert_resource(map);
+        let entity = world.spawn((Being::new("rat".to_string()), Position::new(1, 0)));
+        update_promotions(&mut world.write_resource::<Map>(), &tiles, &mut rng);
+        assert_eq!(world.read_resource::<Map>().fixture[1].id, "DOOR_OPEN");
+
+        world.delete_entity(entity);
+        update_promotions(&mut world.write_resource::<Map>(), &tiles, &mut rng);
+        assert_eq!(world.read_resource::<Map>().fixture[1].id, "DOOR");
+    }
+}
@@ -1,11 +1,60 @@
 use crate::fov::{FovFlags, FOV};
+use crate::map::{Cell, Map};
 use crate::sprite::Sprite;
+use crate::tile::{tile_is_none, Tile};
 use gw_app::Buffer;
 use gw_app::{Glyph, RGBA};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The flavor and description of a tile as the hero saw it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RememberedTile {
+    pub flavor: String,
+    pub description: String,
+}
+
+/// The tiles the hero sees in the cell - fixture, gas, liquid, ground (or the flavor text of the cell)
+pub fn seen_tiles(map: &Map, idx: usize) -> Vec<RememberedTile> {
+    if let Some(flavor) = map.flavors.get(&idx) {
+        return vec![RememberedTile {
+            flavor: flavor.clone(),
+            description: String::new(),
+        }];
+    }
+
+    let cell = match map.get_cell(idx) {
+        None => return Vec::new(),
+        Some(cell) => cell,
+    };
+    let mut layers = vec![cell.fixture()];
+    if cell.gas_volume() > 0 {
+        layers.push(cell.gas());
+    }
+    if cell.liquid_volume() > 0 {
+        layers.push(cell.liquid());
+    }
+    layers.push(cell.ground());
+
+    // Secret tiles are seen as the tile they mimic
+    let look = |tile: &Arc<Tile>| tile.mimic.clone().unwrap_or_else(|| tile.clone());
+    layers
+        .into_iter()
+        .filter(|tile| !tile_is_none(tile))
+        .map(|tile| {
+            let tile = look(tile);
+            RememberedTile {
+                flavor: tile.flavor.clone(),
+                description: tile.description.clone(),
+            }
+        })
+        .collect()
+}
 
 pub struct MapMemory {
     buffer: Buffer,
     flags: Vec<FovFlags>,
+    tiles: Vec<Vec<RememberedTile>>,
 }
 
 impl MapMemory {
@@ -13,9 +62,14 @@ impl MapMemory {
         MapMemory {
             buffer: Buffer::new(width, height),
             flags: vec![FovFlags::empty(); (width * height) as usize],
+            tiles: vec![Vec::new(); (width * height) as usize],
         }
     }
 
+    pub fn size(&self) -> (u32, u32) {
+        (self.buffer.width(), self.buffer.height())
+    }
+
     pub fn set_sprite(&mut self, x: i32, y: i32, fg: RGBA, bg: RGBA, glyph: Glyph) {
         self.buffer.draw_opt(x, y, Some(glyph), Some(fg), Some(bg));
     }
@@ -41,6 +95,32 @@ impl MapMemory {
         Some(Sprite::new(g, f, b))
     }
 
+    pub fn set_tiles(&mut self, x: i32, y: i32, tiles: Vec<RememberedTile>) {
+        if let Some(idx) = self.index(x, y) {
+            self.tiles[idx] = tiles;
+        }
+    }
+
+    /// The tiles that were in the cell when it was last seen
+    pub fn get_tiles(&self, x: i32, y: i32) -> Option<&Vec<RememberedTile>> {
+        self.index(x, y).map(|idx| &self.tiles[idx])
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        match self.buffer.has_xy(x, y) {
+            false => None,
+            true => Some((x + y * self.buffer.width() as i32) as usize),
+        }
+    }
+
+    pub(crate) fn flags(&self) -> &[FovFlags] {
+        &self.flags
+    }
+
+    pub(crate) fn flags_mut(&mut self) -> &mut [FovFlags] {
+        &mut self.flags
+    }
+
     pub fn store_flags(&mut self, fov: &FOV) {
         self.flags.copy_from_slice(&fov.flags);
     }
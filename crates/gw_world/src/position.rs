@@ -1,3 +1,6 @@
+use crate::being::Being;
+use crate::map::Map;
+use gw_ecs::components::ComponentHooks;
 use gw_ecs::prelude::*;
 use gw_util::point::Point;
 use serde::{Deserialize, Serialize};
@@ -62,6 +65,42 @@ impl Position {
     }
 }
 
+/// Keeps the beings in the `Map` in sync with their `Position` -
+/// inserting a `Position` on a `Being` adds it to the map and removing it (or deleting the entity) takes it out.
+/// Moving a being by changing its `Position` in place still needs to update the map.
+/// See also `being_hooks`.
+pub fn position_hooks() -> ComponentHooks<Position> {
+    ComponentHooks::<Position>::new()
+        .on_insert(|commands, entity, pos| {
+            let pos = *pos;
+            commands.add(move |world: &mut World| {
+                if world.read_component::<Being>().contains(entity) {
+                    add_to_map(world, entity, pos);
+                }
+            });
+        })
+        .on_remove(|commands, entity, pos| {
+            let pos = *pos;
+            commands.add(move |world: &mut World| remove_from_map(world, entity, pos));
+        })
+}
+
+pub(crate) fn add_to_map(world: &World, entity: Entity, pos: Position) {
+    if let Some(mut map) = world.try_write_resource::<Map>() {
+        if let Some(idx) = map.get_index(pos.x, pos.y) {
+            map.add_being(idx, entity, pos.blocks_move);
+        }
+    }
+}
+
+pub(crate) fn remove_from_map(world: &World, entity: Entity, pos: Position) {
+    if let Some(mut map) = world.try_write_resource::<Map>() {
+        if let Some(idx) = map.get_index(pos.x, pos.y) {
+            map.remove_being(idx, entity);
+        }
+    }
+}
+
 impl Into<Point> for Position {
     fn into(self) -> Point {
         Point::new(self.x, self.y)
@@ -111,3 +150,47 @@ impl PartialEq<Position> for Position {
         self.x == other.x && self.y == other.y
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::item::Item;
+    use crate::test_util::make_ecs;
+
+    #[test]
+    fn beings_follow_position() {
+        let mut ecs = make_ecs(5, 3);
+        let world = ecs.current_world_mut();
+
+        let rat = world.spawn((Being::default(), Position::new(1, 1)));
+        let sword = world.spawn((Item::default(), Position::new(2, 1)));
+        {
+            let map = world.read_resource::<Map>();
+            assert_eq!(map.iter_beings(6).collect::<Vec<_>>(), vec![rat]);
+            assert!(map.is_blocked(6));
+            assert_eq!(map.iter_beings(7).count(), 0);
+        }
+
+        world
+            .create_entity()
+            .with(Position::new(3, 1))
+            .with(Being::default())
+            .id();
+        assert_eq!(world.read_resource::<Map>().iter_beings(8).count(), 1);
+
+        world
+            .write_component::<Position>()
+            .insert(rat, Position::new(1, 2))
+            .unwrap();
+        world.apply_hooks();
+        {
+            let map = world.read_resource::<Map>();
+            assert_eq!(map.iter_beings(6).count(), 0);
+            assert_eq!(map.iter_beings(11).collect::<Vec<_>>(), vec![rat]);
+        }
+
+        world.delete_entity(rat);
+        world.delete_entity(sword);
+        assert_eq!(world.read_resource::<Map>().iter_beings(11).count(), 0);
+    }
+}
@@ -0,0 +1,5 @@
+mod registry;
+pub use registry::*;
+
+mod save;
+pub use save::*;
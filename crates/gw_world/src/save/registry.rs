@@ -0,0 +1,214 @@
+use crate::being::{Being, Stats};
+use crate::fov::Vision;
+use crate::horde::{Follower, Leader};
+use crate::item::{Inventory, Item};
+use crate::light::Light;
+use crate::position::Position;
+use crate::sprite::Sprite;
+use crate::status::StatusEffects;
+use crate::task::Task;
+use gw_ecs::entity::MapEntities;
+use gw_ecs::prelude::{Component, Ecs};
+use gw_ecs::registry::TypeRegistry;
+use gw_util::value::{RegisterValue, Value};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value as JsonValue;
+
+/// The components written into save files - stored as json
+pub type SavedComponents = TypeRegistry<JsonValue>;
+
+/// Registers serializable components with a registry.
+/// Lets the `Value` and save file registries be built from the same list.
+pub trait RegisterSaved {
+    fn saved<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+        T::Storage: Default;
+
+    /// A component that holds entity references (they are remapped on load)
+    fn mapped<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned + MapEntities,
+        T::Storage: Default;
+}
+
+impl RegisterSaved for TypeRegistry<Value> {
+    fn saved<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+        T::Storage: Default,
+    {
+        self.register_component::<T>(id);
+    }
+
+    fn mapped<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned + MapEntities,
+        T::Storage: Default,
+    {
+        self.register_mapped_component::<T>(id);
+    }
+}
+
+impl RegisterSaved for SavedComponents {
+    fn saved<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned,
+        T::Storage: Default,
+    {
+        self.register_component_with::<T>(id, from_json::<T>, to_json::<T>);
+    }
+
+    fn mapped<T>(&mut self, id: &str)
+    where
+        T: Component + Clone + Serialize + DeserializeOwned + MapEntities,
+        T::Storage: Default,
+    {
+        self.register_mapped_component_with::<T>(id, from_json::<T>, to_json::<T>);
+    }
+}
+
+/// The standard components by their save name.
+/// The name is stored in the file, so it must not change between versions.
+pub fn register_standard_components<R: RegisterSaved>(registry: &mut R) {
+    registry.saved::<Position>("POSITION");
+    registry.saved::<Sprite>("SPRITE");
+    registry.saved::<Task>("TASK");
+    registry.saved::<Being>("BEING");
+    registry.saved::<Stats>("STATS");
+    registry.saved::<StatusEffects>("STATUS");
+    registry.saved::<Item>("ITEM");
+    registry.mapped::<Inventory>("INVENTORY");
+    registry.saved::<Vision>("VISION");
+    registry.saved::<Light>("LIGHT");
+    registry.saved::<Leader>("LEADER");
+    registry.mapped::<Follower>("FOLLOWER");
+}
+
+/// The standard components converted to and from `Value` - for data driven templates and inspecting entities.
+pub fn standard_type_registry() -> TypeRegistry<Value> {
+    let mut registry = TypeRegistry::new();
+    register_standard_components(&mut registry);
+    registry
+}
+
+/// The standard components that are written into save files
+pub fn standard_saved_components() -> SavedComponents {
+    let mut registry = SavedComponents::new();
+    register_standard_components(&mut registry);
+    registry
+}
+
+/// Registers a component to be written into save files (and built from values).
+/// The name is stored in the file, so it must not change between versions.
+pub fn register_saved_component<T, S: ToString>(ecs: &mut Ecs, name: S)
+where
+    T: Component + Clone + Serialize + DeserializeOwned,
+    T::Storage: Default,
+{
+    let name = name.to_string().to_uppercase();
+    ecs.ensure_global_with(standard_type_registry);
+    ecs.ensure_global_with(standard_saved_components);
+    ecs.write_global::<TypeRegistry<Value>>().saved::<T>(&name);
+    ecs.write_global::<SavedComponents>().saved::<T>(&name);
+}
+
+/// Registers a component that holds entity references (they are remapped on load)
+pub fn register_mapped_component<T, S: ToString>(ecs: &mut Ecs, name: S)
+where
+    T: Component + Clone + Serialize + DeserializeOwned + MapEntities,
+    T::Storage: Default,
+{
+    let name = name.to_string().to_uppercase();
+    ecs.ensure_global_with(standard_type_registry);
+    ecs.ensure_global_with(standard_saved_components);
+    ecs.write_global::<TypeRegistry<Value>>().mapped::<T>(&name);
+    ecs.write_global::<SavedComponents>().mapped::<T>(&name);
+}
+
+fn from_json<T: DeserializeOwned>(value: &JsonValue) -> Result<T, String> {
+    T::deserialize(value).map_err(|e| e.to_string())
+}
+
+fn to_json<T: Serialize>(data: &T) -> Result<JsonValue, String> {
+    serde_json::to_value(data).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::Stat;
+    use crate::test_util::make_empty_ecs;
+    use gw_ecs::prelude::Ecs;
+
+    #[test]
+    fn standard_values_round_trip() {
+        let mut ecs = make_empty_ecs();
+
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 7);
+
+        let world = ecs.create_world("MAIN");
+        let entity = world.spawn((Position::new(3, 4), Sprite::default(), stats));
+
+        let registry = world.read_global::<TypeRegistry<Value>>();
+        let values: Vec<(&str, Value)> = registry
+            .dump_entity(world, entity)
+            .into_iter()
+            .map(|(id, v)| (id, v.unwrap()))
+            .collect();
+        assert_eq!(
+            values.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec!["POSITION", "SPRITE", "STATS"]
+        );
+
+        let mut other = Ecs::empty();
+        crate::register_components(&mut other);
+        let other = other.create_world("OTHER");
+        let copy = registry
+            .spawn(other, values.iter().map(|(id, v)| (*id, v)))
+            .unwrap();
+
+        assert_eq!(other.read_component::<Position>().get(copy).unwrap().x, 3);
+        assert_eq!(
+            other
+                .read_component::<Stats>()
+                .get(copy)
+                .unwrap()
+                .get(Stat::HEALTH),
+            Some(7)
+        );
+        assert_eq!(registry.dump_entity(other, copy).len(), 3);
+    }
+
+    #[test]
+    fn followers_are_remapped() {
+        let mut ecs = make_empty_ecs();
+        let world = ecs.create_world("MAIN");
+        let _other = world.spawn(Position::new(1, 1));
+        let leader = world.spawn((Position::new(2, 2), Leader::new()));
+        let follower = world.spawn(Follower::new(leader, true));
+        let saved = crate::save::save_world(world).unwrap();
+        assert!(saved
+            .entities
+            .iter()
+            .any(|e| e.components.contains_key("FOLLOWER")));
+
+        let mut loaded = make_empty_ecs();
+        let world = loaded.create_world("MAIN");
+        // So that the loaded entities do not get the same ids
+        world.spawn(Position::new(0, 0));
+        let entity_map = crate::save::load_world(world, saved).unwrap();
+
+        let leader = entity_map.get(leader).unwrap();
+        let follower = entity_map.get(follower).unwrap();
+        assert_eq!(
+            world
+                .read_component::<Follower>()
+                .get(follower)
+                .unwrap()
+                .leader,
+            leader
+        );
+    }
+}
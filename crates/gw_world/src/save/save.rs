@@ -0,0 +1,792 @@
+use super::SavedComponents;
+use crate::being::{Being, BeingKinds};
+use crate::camera::Camera;
+use crate::combat::{Melee, Ranged};
+use crate::fov::{FovFlags, FOV};
+use crate::hero::Hero;
+use crate::map::{Map, MapFlags};
+use crate::memory::{MapMemory, RememberedTile};
+use crate::sprite::Sprite;
+use crate::task::Executor;
+use crate::tile::{Tile, Tiles, NO_TILE};
+use gw_ecs::entity::{EntityMap, Generation};
+use gw_ecs::prelude::{Atom, Builder, Ecs, Entity, Join, World};
+use gw_ecs::storage::MaskedStorage;
+use gw_util::point::Point;
+use gw_util::rng::RandomNumberGenerator;
+use gw_util::value::{from_value, to_value};
+use gw_util::xy::{Lock, Wrap};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SaveError {
+    OpenError(std::io::Error),
+    WriteError(std::io::Error),
+    ParseError(String),
+    ProcessError(String),
+}
+
+/// An entity as it was in the saved world.
+/// On load these are mapped to newly created entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SavedEntity {
+    pub id: u32,
+    pub gen: i32,
+}
+
+impl SavedEntity {
+    pub fn to_entity(&self) -> Option<Entity> {
+        match self.gen > 0 {
+            false => None,
+            true => Some(Entity::new(self.id, Generation::new(self.gen))),
+        }
+    }
+}
+
+impl From<Entity> for SavedEntity {
+    fn from(entity: Entity) -> Self {
+        SavedEntity {
+            id: entity.id(),
+            gen: entity.gen().id(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub current: u64,
+    pub worlds: Vec<WorldSave>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WorldSave {
+    pub id: u64,
+    pub entities: Vec<EntitySave>,
+    pub hero: Option<SavedEntity>,
+    pub executor: Option<ExecutorSave>,
+    pub rng: Option<RandomNumberGenerator>,
+    pub camera: Option<CameraSave>,
+    pub map: Option<MapSave>,
+    pub fov: Option<FovSave>,
+    pub memory: Option<MemorySave>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntitySave {
+    pub entity: SavedEntity,
+    pub components: BTreeMap<String, JsonValue>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecutorSave {
+    pub time: u64,
+    pub tasks: Vec<(SavedEntity, u64)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CameraSave {
+    pub center: Point,
+    pub size: (u32, u32),
+    pub follows: Option<SavedEntity>,
+}
+
+/// Map cells refer to tiles by id (index into `tiles`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MapSave {
+    pub id: u32,
+    pub width: u32,
+    pub height: u32,
+    pub wrap: Wrap,
+    pub lock: Lock,
+    pub flags: u32,
+    pub welcome: Option<String>,
+    pub tiles: Vec<String>,
+    pub ground: Vec<u32>,
+    pub fixture: Vec<u32>,
+    pub locations: HashMap<String, usize>,
+    pub flavors: HashMap<usize, String>,
+    pub beings: Vec<(usize, SavedEntity, bool)>,
+    pub items: Vec<(usize, SavedEntity, bool)>,
+    #[serde(default)]
+    pub stashes: Vec<(usize, Vec<SavedEntity>)>,
+    /// (cell index, tile index, volume)
+    #[serde(default)]
+    pub liquids: Vec<(usize, u32, u32)>,
+    #[serde(default)]
+    pub gases: Vec<(usize, u32, u32)>,
+    /// (cell index, action, effects) - the data that the cell effects were parsed from
+    #[serde(default)]
+    pub effects: Vec<(usize, String, JsonValue)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FovSave {
+    pub range: u32,
+    pub width: u32,
+    pub height: u32,
+    pub flags: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MemorySave {
+    pub width: u32,
+    pub height: u32,
+    pub sprites: Vec<Sprite>,
+    pub flags: Vec<u32>,
+    #[serde(default)]
+    pub tiles: Vec<Vec<RememberedTile>>,
+}
+
+///////////////////////////////////////////////////////////
+// SAVE
+
+pub fn save_game(ecs: &Ecs) -> Result<SaveGame, SaveError> {
+    let mut worlds = Vec::new();
+    for world in ecs.iter_worlds() {
+        worlds.push(save_world(world)?);
+    }
+
+    Ok(SaveGame {
+        version: SAVE_VERSION,
+        current: ecs.current_world().id().value(),
+        worlds,
+    })
+}
+
+pub fn save_to_string(ecs: &Ecs) -> Result<String, SaveError> {
+    let game = save_game(ecs)?;
+    serde_json::to_string(&game).map_err(|e| SaveError::ParseError(e.to_string()))
+}
+
+pub fn save_to_file(ecs: &Ecs, path: &str) -> Result<(), SaveError> {
+    let text = save_to_string(ecs)?;
+    std::fs::write(path, text).map_err(SaveError::WriteError)
+}
+
+pub fn save_world(world: &World) -> Result<WorldSave, SaveError> {
+    let registry = match world.try_read_global::<SavedComponents>() {
+        None => {
+            return Err(SaveError::ProcessError(
+                "No saved components registry".to_string(),
+            ))
+        }
+        Some(registry) => registry,
+    };
+
+    let mut entities = Vec::new();
+    for entity in world.entities().join() {
+        let mut data = BTreeMap::new();
+        for (name, value) in registry.dump_entity(world, entity) {
+            match value {
+                Ok(value) => {
+                    data.insert(name.to_string(), value);
+                }
+                Err(e) => {
+                    return Err(SaveError::ProcessError(format!(
+                        "Failed to save component {} - {}",
+                        name, e
+                    )))
+                }
+            }
+        }
+        entities.push(EntitySave {
+            entity: entity.into(),
+            components: data,
+        });
+    }
+
+    let hero = match world.try_read_resource::<Hero>() {
+        None => None,
+        Some(hero) if world.entities().is_alive(hero.entity) => Some(hero.entity.into()),
+        Some(_) => None,
+    };
+
+    let executor = world
+        .try_read_resource::<Executor>()
+        .map(|executor| ExecutorSave {
+            time: executor.time(),
+            tasks: executor.iter().map(|(e, t)| (e.into(), t)).collect(),
+        });
+
+    let rng = world
+        .try_read_resource::<RandomNumberGenerator>()
+        .map(|rng| (*rng).clone());
+
+    let camera = world
+        .try_read_resource::<Camera>()
+        .map(|camera| CameraSave {
+            center: *camera.center(),
+            size: *camera.size(),
+            follows: camera.follows().map(|e| e.into()),
+        });
+
+    let map = match world.try_read_resource::<Map>() {
+        None => None,
+        Some(map) => Some(save_map(&map)?),
+    };
+
+    let fov = world.try_read_resource::<FOV>().map(|fov| {
+        let (width, height) = match world.try_read_resource::<Map>() {
+            None => (0, 0),
+            Some(map) => map.full_size(),
+        };
+        FovSave {
+            range: fov.range(),
+            width,
+            height,
+            flags: fov.iter().map(|f| f.bits()).collect(),
+        }
+    });
+
+    let memory = world.try_read_resource::<MapMemory>().map(|memory| {
+        let (width, height) = memory.size();
+        let mut sprites = Vec::with_capacity((width * height) as usize);
+        let mut tiles = Vec::with_capacity((width * height) as usize);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                sprites.push(memory.get_sprite(x, y).unwrap_or_default());
+                tiles.push(memory.get_tiles(x, y).cloned().unwrap_or_default());
+            }
+        }
+        MemorySave {
+            width,
+            height,
+            sprites,
+            flags: memory.flags().iter().map(|f| f.bits()).collect(),
+            tiles,
+        }
+    });
+
+    Ok(WorldSave {
+        id: world.id().value(),
+        entities,
+        hero,
+        executor,
+        rng,
+        camera,
+        map,
+        fov,
+        memory,
+    })
+}
+
+fn save_map(map: &Map) -> Result<MapSave, SaveError> {
+    let mut tiles: Vec<String> = Vec::new();
+    let mut lookup: HashMap<String, u32> = HashMap::new();
+
+    let mut tile_index = |id: &String| -> u32 {
+        match lookup.get(id) {
+            Some(idx) => *idx,
+            None => {
+                let idx = tiles.len() as u32;
+                tiles.push(id.clone());
+                lookup.insert(id.clone(), idx);
+                idx
+            }
+        }
+    };
+
+    let ground = map.ground.iter().map(|t| tile_index(&t.id)).collect();
+    let fixture = map.fixture.iter().map(|t| tile_index(&t.id)).collect();
+
+    let mut liquids = Vec::new();
+    let mut gases = Vec::new();
+    for idx in 0..map.ground.len() {
+        let volume = map.liquid_volume(idx);
+        if volume > 0 {
+            liquids.push((idx, tile_index(&map.liquid[idx].id), volume));
+        }
+        let volume = map.gas_volume(idx);
+        if volume > 0 {
+            gases.push((idx, tile_index(&map.gas[idx].id), volume));
+        }
+    }
+
+    let mut beings = Vec::new();
+    for (idx, list) in map.beings.iter().enumerate() {
+        for (entity, blocks) in list.iter() {
+            beings.push((idx, (*entity).into(), *blocks));
+        }
+    }
+
+    let mut items = Vec::new();
+    for (idx, list) in map.items.iter().enumerate() {
+        for (entity, blocks) in list.iter() {
+            items.push((idx, (*entity).into(), *blocks));
+        }
+    }
+
+    let mut stashes: Vec<(usize, Vec<SavedEntity>)> = map
+        .stashes
+        .iter()
+        .map(|(idx, list)| (*idx, list.iter().map(|e| (*e).into()).collect()))
+        .collect();
+    stashes.sort_by_key(|(idx, _)| *idx);
+
+    let mut effects = Vec::new();
+    for (idx, actions) in map.cell_effects.iter() {
+        for (action, list) in actions.iter() {
+            let data = map
+                .cell_effect_data
+                .get(idx)
+                .and_then(|all| all.get(action))
+                .map(|data| data.as_slice())
+                .unwrap_or(&[]);
+            let count: usize = data
+                .iter()
+                .map(|d| d.as_map().map(|m| m.len()).unwrap_or(0))
+                .sum();
+            if count != list.len() {
+                return Err(SaveError::ProcessError(format!(
+                    "Map cell effects were not added from data and can not be saved - map={}, index={}, action={}",
+                    map.id, idx, action
+                )));
+            }
+            for value in data.iter() {
+                let json = from_value::<JsonValue>(value).map_err(SaveError::ProcessError)?;
+                effects.push((*idx, action.clone(), json));
+            }
+        }
+    }
+    effects.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+
+    Ok(MapSave {
+        id: map.id,
+        width: map.width,
+        height: map.height,
+        wrap: map.wrap,
+        lock: map.lock,
+        flags: map.flags().bits(),
+        welcome: map.welcome.clone(),
+        tiles,
+        ground,
+        fixture,
+        locations: map.locations.clone(),
+        flavors: map.flavors.clone(),
+        beings,
+        items,
+        stashes,
+        liquids,
+        gases,
+        effects,
+    })
+}
+
+///////////////////////////////////////////////////////////
+// LOAD
+
+/// Restores all the worlds in the save game.
+/// Worlds with the same id are replaced, other worlds in the ecs are left alone.
+/// The globals (Tiles, BeingKinds, ...) must already be loaded.
+pub fn load_game(ecs: &mut Ecs, game: SaveGame) -> Result<(), SaveError> {
+    if game.version != SAVE_VERSION {
+        return Err(SaveError::ParseError(format!(
+            "Unsupported save version - {}",
+            game.version
+        )));
+    }
+
+    for data in game.worlds {
+        let world = ecs.create_world(Atom::from(data.id));
+        crate::setup_world(world);
+        load_world(world, data)?;
+    }
+
+    if ecs.set_current_world(Atom::from(game.current)).is_err() {
+        return Err(SaveError::ProcessError(
+            "Current world not found in save".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn load_from_string(ecs: &mut Ecs, text: &str) -> Result<(), SaveError> {
+    let game: SaveGame =
+        serde_json::from_str(text).map_err(|e| SaveError::ParseError(e.to_string()))?;
+    load_game(ecs, game)
+}
+
+pub fn load_from_file(ecs: &mut Ecs, path: &str) -> Result<(), SaveError> {
+    let text = std::fs::read_to_string(path).map_err(SaveError::OpenError)?;
+    load_from_string(ecs, &text)
+}
+
+/// Loads the saved data into the (empty) world
+pub fn load_world(world: &mut World, data: WorldSave) -> Result<EntityMap, SaveError> {
+    // A (cheap) copy so that the world can be changed while using it
+    let registry = match world.try_read_global::<SavedComponents>() {
+        None => {
+            return Err(SaveError::ProcessError(
+                "No saved components registry".to_string(),
+            ))
+        }
+        Some(registry) => SavedComponents::clone(&registry),
+    };
+    let mut entity_map = EntityMap::default();
+
+    for info in data.entities.iter() {
+        let new_entity = world.create_entity().id();
+        if let Some(old) = info.entity.to_entity() {
+            entity_map.insert(old, new_entity);
+        }
+    }
+
+    for info in data.entities {
+        let entity = remap(&entity_map, info.entity)?;
+        for (name, value) in info.components {
+            let saved = match registry.get(&name) {
+                None => {
+                    return Err(SaveError::ProcessError(format!(
+                        "Unknown saved component - {}",
+                        name
+                    )))
+                }
+                Some(saved) => saved,
+            };
+            if let Err(e) = saved
+                .insert_component(world, entity, &value)
+                .and_then(|_| saved.map_entities(world, entity, &entity_map))
+            {
+                return Err(SaveError::ProcessError(format!(
+                    "Failed to load component {} - {}",
+                    name, e
+                )));
+            }
+        }
+    }
+
+    restore_kind_components(world);
+
+    if let Some(hero) = data.hero {
+        let entity = remap(&entity_map, hero)?;
+        world.insert_resource(Hero::new(entity));
+    }
+
+    if let Some(saved) = data.executor {
+        let mut tasks = Vec::with_capacity(saved.tasks.len());
+        for (entity, time) in saved.tasks {
+            tasks.push((remap(&entity_map, entity)?, time));
+        }
+        let mut executor = Executor::new();
+        executor.restore(saved.time, tasks);
+        world.insert_resource(executor);
+    }
+
+    if let Some(rng) = data.rng {
+        world.insert_resource(rng);
+    }
+
+    if let Some(saved) = data.camera {
+        let mut camera =
+            Camera::new(saved.size.0, saved.size.1).with_center(saved.center.x, saved.center.y);
+        if let Some(entity) = saved.follows {
+            camera.set_follows(remap(&entity_map, entity)?);
+        }
+        world.insert_resource(camera);
+    }
+
+    if let Some(saved) = data.map {
+        let map = load_map(world, saved, &entity_map)?;
+        world.insert_resource(map);
+    }
+
+    if let Some(saved) = data.fov {
+        let mut fov = FOV::new(saved.range);
+        fov.resize(saved.width, saved.height);
+        for (flag, bits) in fov.iter_mut().zip(saved.flags.iter()) {
+            *flag = FovFlags::from_bits_truncate(*bits);
+        }
+        fov.set_dirty();
+        world.insert_resource(fov);
+    }
+
+    if let Some(saved) = data.memory {
+        let mut memory = MapMemory::new(saved.width, saved.height);
+        for (idx, sprite) in saved.sprites.iter().enumerate() {
+            let x = (idx as u32 % saved.width) as i32;
+            let y = (idx as u32 / saved.width) as i32;
+            memory.set_sprite(x, y, sprite.fg, sprite.bg, sprite.glyph);
+        }
+        for (idx, tiles) in saved.tiles.into_iter().enumerate() {
+            let x = (idx as u32 % saved.width) as i32;
+            let y = (idx as u32 / saved.width) as i32;
+            memory.set_tiles(x, y, tiles);
+        }
+        for (flag, bits) in memory.flags_mut().iter_mut().zip(saved.flags.iter()) {
+            *flag = FovFlags::from_bits_truncate(*bits);
+        }
+        world.insert_resource_non_send(memory);
+    }
+
+    Ok(entity_map)
+}
+
+fn remap(entity_map: &EntityMap, saved: SavedEntity) -> Result<Entity, SaveError> {
+    let entity = match saved.to_entity() {
+        None => {
+            return Err(SaveError::ProcessError(format!(
+                "Saved entity is not alive - {:?}",
+                saved
+            )))
+        }
+        Some(entity) => entity,
+    };
+    entity_map
+        .get(entity)
+        .map_err(|e| SaveError::ProcessError(format!("{} - {:?}", e, saved)))
+}
+
+fn load_map(world: &World, saved: MapSave, entity_map: &EntityMap) -> Result<Map, SaveError> {
+    let tiles = world.read_global::<Tiles>();
+
+    let mut lookup = Vec::with_capacity(saved.tiles.len());
+    for id in saved.tiles.iter() {
+        match tiles.get(id) {
+            None if id == &NO_TILE.id => lookup.push(NO_TILE.clone()),
+            None => {
+                return Err(SaveError::ProcessError(format!(
+                    "Unknown tile in saved map - {}",
+                    id
+                )))
+            }
+            Some(tile) => lookup.push(tile),
+        }
+    }
+
+    let count = (saved.width * saved.height) as usize;
+    if saved.ground.len() != count || saved.fixture.len() != count {
+        return Err(SaveError::ProcessError(
+            "Saved map has wrong number of cells".to_string(),
+        ));
+    }
+
+    let mut map = Map::new(saved.width, saved.height);
+    map.set_id(saved.id);
+    map.wrap = saved.wrap;
+    map.lock = saved.lock;
+    map.welcome = saved.welcome;
+    map.locations = saved.locations;
+    map.flavors = saved.flavors;
+
+    let flags = MapFlags::from_bits_truncate(saved.flags);
+    if flags.contains(MapFlags::ALL_REVEALED) {
+        map.reveal_all();
+    }
+    if flags.contains(MapFlags::ALL_VISIBLE) {
+        map.make_fully_visible();
+    }
+
+    for idx in 0..count {
+        let ground = tile_at(&lookup, saved.ground[idx])?;
+        map.reset_tiles(idx, ground);
+        let fixture = tile_at(&lookup, saved.fixture[idx])?;
+        map.force_fixture(idx, fixture);
+    }
+
+    for (idx, tile, volume) in saved.liquids {
+        let liquid = tile_at(&lookup, tile)?;
+        map.set_liquid(idx, liquid, volume);
+    }
+    for (idx, tile, volume) in saved.gases {
+        let gas = tile_at(&lookup, tile)?;
+        map.set_gas(idx, gas, volume);
+    }
+
+    for (idx, entity, blocks) in saved.beings {
+        map.add_being(idx, remap(entity_map, entity)?, blocks);
+    }
+    for (idx, entity, blocks) in saved.items {
+        map.add_item(idx, remap(entity_map, entity)?, blocks);
+    }
+    for (idx, list) in saved.stashes {
+        let mut items = Vec::new();
+        for entity in list {
+            items.push(remap(entity_map, entity)?);
+        }
+        map.stash_items(idx, items);
+    }
+    for (idx, action, json) in saved.effects {
+        let data = to_value(&json).map_err(SaveError::ProcessError)?;
+        map.add_effects(idx, &action, data)
+            .map_err(SaveError::ProcessError)?;
+    }
+
+    Ok(map)
+}
+
+fn tile_at(lookup: &[Arc<Tile>], idx: u32) -> Result<Arc<Tile>, SaveError> {
+    match lookup.get(idx as usize) {
+        None => Err(SaveError::ProcessError(format!(
+            "Bad tile index in saved map - {}",
+            idx
+        ))),
+        Some(tile) => Ok(tile.clone()),
+    }
+}
+
+/// Components that are not saved, but come from the being kind (e.g. Melee)
+fn restore_kind_components(world: &mut World) {
+    if !world.has_resource::<MaskedStorage<Melee>>() {
+        return;
+    }
+    let kinds = match world.try_read_global::<BeingKinds>() {
+        None => return,
+        Some(kinds) => kinds,
+    };
+    let beings = world.read_component::<Being>();
+    let mut melees = world.write_component::<Melee>();
+    let mut ranges = match world.has_resource::<MaskedStorage<Ranged>>() {
+        true => Some(world.write_component::<Ranged>()),
+        false => None,
+    };
+
+    for (entity, being) in (&world.entities(), &beings).join() {
+        let kind = match kinds.get(&being.id) {
+            None => continue,
+            Some(kind) => kind,
+        };
+        if let Some(ref melee) = kind.melee {
+            if !melees.contains(entity) {
+                let _ = melees.insert(entity, melee.clone());
+            }
+        }
+        if let (Some(ranged), Some(ranges)) = (kind.ranged.as_ref(), ranges.as_mut()) {
+            if !ranges.contains(entity) {
+                let _ = ranges.insert(entity, ranged.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::Stat;
+    use crate::being::Stats;
+    use crate::position::Position;
+    use crate::test_util::make_empty_ecs;
+
+    fn add_level(ecs: &mut Ecs, id: &str) -> Entity {
+        let world = ecs.create_world(id);
+        let floor = world.read_global::<Tiles>().get("FLOOR").unwrap();
+        let wall = world.read_global::<Tiles>().get("WALL").unwrap();
+
+        let mut map = Map::new(10, 10);
+        map.fill(floor);
+        map.reset_tiles(0, wall);
+        map.set_location("START", 22);
+
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 7);
+
+        let _other = world.spawn(Position::new(5, 5));
+        let hero = world.spawn((Position::new(2, 2), stats));
+        map.add_being(22, hero, true);
+
+        let mut executor = Executor::new();
+        executor.insert(hero, 50);
+
+        world.insert_resource(map);
+        world.insert_resource(executor);
+        world.insert_resource(Hero::new(hero));
+        world.insert_resource(RandomNumberGenerator::seeded(12345));
+        crate::setup_world(world);
+        hero
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut ecs = make_empty_ecs();
+        add_level(&mut ecs, "ONE");
+        add_level(&mut ecs, "TWO");
+        ecs.set_current_world("TWO").unwrap();
+
+        let expected_roll = {
+            let world = ecs.current_world();
+            let mut rng = (*world.read_resource::<RandomNumberGenerator>()).clone();
+            rng.rand(1000)
+        };
+
+        let text = save_to_string(&ecs).unwrap();
+
+        let mut loaded = make_empty_ecs();
+        load_from_string(&mut loaded, &text).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded.current_world().id() == Atom::from("TWO"));
+
+        let world = loaded.current_world();
+        let hero = world.read_resource::<Hero>().entity;
+        assert!(world.entities().is_alive(hero));
+
+        let pos = *world.read_component::<Position>().get(hero).unwrap();
+        assert_eq!(pos.point(), Point::new(2, 2));
+        assert_eq!(
+            world
+                .read_component::<Stats>()
+                .get(hero)
+                .unwrap()
+                .get(Stat::HEALTH),
+            Some(7)
+        );
+
+        let map = world.read_resource::<Map>();
+        assert_eq!(map.ground[0].id, "WALL");
+        assert_eq!(map.ground[1].id, "FLOOR");
+        assert_eq!(map.get_location("START"), Some(22));
+        assert_eq!(map.iter_beings(22).collect::<Vec<_>>(), vec![hero]);
+
+        let executor = world.read_resource::<Executor>();
+        assert_eq!(executor.iter().collect::<Vec<_>>(), vec![(hero, 50)]);
+
+        let mut rng = world.write_resource::<RandomNumberGenerator>();
+        assert_eq!(rng.rand(1000), expected_roll);
+    }
+
+    #[test]
+    fn unknown_tile() {
+        let mut ecs = make_empty_ecs();
+        add_level(&mut ecs, "ONE");
+        let mut game = save_game(&ecs).unwrap();
+        game.worlds[0].map.as_mut().unwrap().tiles[0] = "UNKNOWN".to_string();
+
+        let mut loaded = make_empty_ecs();
+        assert!(load_game(&mut loaded, game).is_err());
+    }
+
+    #[test]
+    fn cell_effects() {
+        let mut ecs = make_empty_ecs();
+        add_level(&mut ecs, "ONE");
+        {
+            let world = ecs.current_world();
+            let data = gw_util::json::parse_string(r#"{ "tile": "WALL" }"#).unwrap();
+            world
+                .write_resource::<Map>()
+                .add_effects(5, "DROP", data)
+                .unwrap();
+        }
+
+        let text = save_to_string(&ecs).unwrap();
+        let mut loaded = make_empty_ecs();
+        load_from_string(&mut loaded, &text).unwrap();
+
+        let world = loaded.current_world_mut();
+        assert!(world.read_resource::<Map>().has_cell_effects(5, "DROP"));
+        crate::effect::fire_cell_action(world, Point::new(5, 0), "drop", None);
+        assert_eq!(world.read_resource::<Map>().ground[5].id, "WALL");
+
+        // effects that were not added from data can not be saved
+        let effect = world.read_resource::<Map>().get_cell_effects(5, "DROP");
+        world
+            .write_resource::<Map>()
+            .add_effect(6, "DROP", effect.unwrap()[0].clone());
+        assert!(save_game(&loaded).is_err());
+    }
+}
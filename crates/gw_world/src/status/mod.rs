@@ -0,0 +1,5 @@
+mod status;
+pub use status::*;
+
+mod update;
+pub use update::*;
@@ -0,0 +1,194 @@
+use gw_ecs::prelude::Component;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The amount of executor time in one status effect turn
+pub const STATUS_TURN_TIME: u64 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum StatusKind {
+    POISON,
+    REGENERATION,
+    HASTE,
+    SLOW,
+    CONFUSION,
+    BLIND,
+}
+
+impl StatusKind {
+    /// Stacking statuses add both turns and power, the others keep the strongest
+    pub fn stacks(&self) -> bool {
+        matches!(self, StatusKind::POISON | StatusKind::REGENERATION)
+    }
+
+    /// The adjective used in log messages - e.g. "{name} is poisoned"
+    pub fn adjective(&self) -> &'static str {
+        match self {
+            StatusKind::POISON => "poisoned",
+            StatusKind::REGENERATION => "regenerating",
+            StatusKind::HASTE => "hasted",
+            StatusKind::SLOW => "slowed",
+            StatusKind::CONFUSION => "confused",
+            StatusKind::BLIND => "blind",
+        }
+    }
+}
+
+impl FromStr for StatusKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "POISON" | "POISONED" => Ok(StatusKind::POISON),
+            "REGENERATION" | "REGEN" => Ok(StatusKind::REGENERATION),
+            "HASTE" | "HASTED" => Ok(StatusKind::HASTE),
+            "SLOW" | "SLOWED" => Ok(StatusKind::SLOW),
+            "CONFUSION" | "CONFUSED" => Ok(StatusKind::CONFUSION),
+            "BLIND" | "BLINDNESS" => Ok(StatusKind::BLIND),
+            _ => Err(format!("Unknown status kind - {}", s)),
+        }
+    }
+}
+
+impl Display for StatusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub turns: u32,
+    pub power: i32,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusKind, turns: u32, power: i32) -> Self {
+        StatusEffect { kind, turns, power }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Component)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+    pub(super) last_tick: u64,
+    pub(super) base_act_time: Option<u32>,
+}
+
+impl StatusEffects {
+    pub fn new(now: u64) -> Self {
+        StatusEffects {
+            effects: Vec::new(),
+            last_tick: now,
+            base_act_time: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|e| e.kind == kind)
+    }
+
+    pub fn get(&self, kind: StatusKind) -> Option<&StatusEffect> {
+        self.effects.iter().find(|e| e.kind == kind)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.iter()
+    }
+
+    /// Adds the status - returns true if the being did not already have it
+    pub fn add(&mut self, effect: StatusEffect) -> bool {
+        match self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            None => {
+                self.effects.push(effect);
+                true
+            }
+            Some(current) => {
+                if effect.kind.stacks() {
+                    current.turns = current.turns.saturating_add(effect.turns);
+                    current.power = current.power.saturating_add(effect.power);
+                } else {
+                    current.turns = current.turns.max(effect.turns);
+                    current.power = current.power.max(effect.power);
+                }
+                false
+            }
+        }
+    }
+
+    /// Removes the status - returns true if the being had it
+    pub fn remove(&mut self, kind: StatusKind) -> bool {
+        let len = self.effects.len();
+        self.effects.retain(|e| e.kind != kind);
+        len != self.effects.len()
+    }
+
+    /// Counts down one turn - returns the statuses that expired
+    pub(super) fn tick(&mut self) -> Vec<StatusKind> {
+        let mut expired = Vec::new();
+        for effect in self.effects.iter_mut() {
+            effect.turns = effect.turns.saturating_sub(1);
+            if effect.turns == 0 {
+                expired.push(effect.kind);
+            }
+        }
+        self.effects.retain(|e| e.turns > 0);
+        expired
+    }
+
+    /// The act time to use given the base act time of the being
+    pub fn act_time(&self, base: u32) -> u32 {
+        match (self.has(StatusKind::HASTE), self.has(StatusKind::SLOW)) {
+            (true, false) => (base / 2).max(1),
+            (false, true) => base.saturating_mul(2),
+            _ => base,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stacking() {
+        let mut status = StatusEffects::new(0);
+        assert!(status.add(StatusEffect::new(StatusKind::POISON, 5, 1)));
+        assert!(!status.add(StatusEffect::new(StatusKind::POISON, 3, 2)));
+        assert_eq!(
+            status.get(StatusKind::POISON),
+            Some(&StatusEffect::new(StatusKind::POISON, 8, 3))
+        );
+
+        assert!(status.add(StatusEffect::new(StatusKind::HASTE, 5, 1)));
+        assert!(!status.add(StatusEffect::new(StatusKind::HASTE, 3, 1)));
+        assert_eq!(status.get(StatusKind::HASTE).unwrap().turns, 5);
+    }
+
+    #[test]
+    fn tick_expires() {
+        let mut status = StatusEffects::new(0);
+        status.add(StatusEffect::new(StatusKind::BLIND, 1, 1));
+        status.add(StatusEffect::new(StatusKind::SLOW, 2, 1));
+        assert_eq!(status.act_time(100), 200);
+
+        assert_eq!(status.tick(), vec![StatusKind::BLIND]);
+        assert!(!status.has(StatusKind::BLIND));
+        assert_eq!(status.tick(), vec![StatusKind::SLOW]);
+        assert!(status.is_empty());
+        assert_eq!(status.act_time(100), 100);
+    }
+
+    #[test]
+    fn parse_kind() {
+        assert_eq!("poison".parse(), Ok(StatusKind::POISON));
+        assert_eq!("Confused".parse(), Ok(StatusKind::CONFUSION));
+        assert!("taco".parse::<StatusKind>().is_err());
+    }
+}
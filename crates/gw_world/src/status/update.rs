@@ -0,0 +1,237 @@
+use super::{StatusEffect, StatusEffects, StatusKind, STATUS_TURN_TIME};
+use crate::action::dead::kill_being;
+use crate::being::{Being, Stat, Stats};
+use crate::combat::being_name;
+use crate::log::Logger;
+use crate::task::Executor;
+use gw_ecs::prelude::{Entity, World};
+
+/// Gives the status to the entity (adding a StatusEffects component if necessary)
+pub fn apply_status(world: &mut World, entity: Entity, effect: StatusEffect) -> bool {
+    if !world.entities().is_alive(entity) || !world.read_component::<Being>().contains(entity) {
+        return false;
+    }
+
+    let is_new = {
+        let now = world.read_resource::<Executor>().time();
+        let mut all_status = world.write_component::<StatusEffects>();
+        if !all_status.contains(entity) {
+            let _ = all_status.insert(entity, StatusEffects::new(now));
+        }
+        let mut status = all_status.get_mut(entity).unwrap();
+        status.add(effect)
+    };
+
+    if is_new {
+        let name = being_name(world, entity);
+        world
+            .write_global::<Logger>()
+            .log_combat(format!("{} is {}", name, effect.kind.adjective()), true);
+    }
+
+    sync_act_time(world, entity);
+    true
+}
+
+/// Takes the status away from the entity
+pub fn remove_status(world: &mut World, entity: Entity, kind: StatusKind) -> bool {
+    let removed = match world.write_component::<StatusEffects>().get_mut(entity) {
+        None => false,
+        Some(mut status) => status.remove(kind),
+    };
+
+    if removed {
+        log_expired(world, entity, kind);
+        sync_act_time(world, entity);
+    }
+    removed
+}
+
+/// Runs all of the turns that have passed since the last update.
+/// Returns false if the entity died.
+pub fn update_status_effects(world: &mut World, entity: Entity) -> bool {
+    if !world.read_component::<StatusEffects>().contains(entity) {
+        return true;
+    }
+
+    let now = world.read_resource::<Executor>().time();
+    let turns = {
+        let mut all_status = world.write_component::<StatusEffects>();
+        let mut status = all_status.get_mut(entity).unwrap();
+        let turns = now.saturating_sub(status.last_tick) / STATUS_TURN_TIME;
+        status.last_tick += turns * STATUS_TURN_TIME;
+        turns
+    };
+
+    let mut expired = Vec::new();
+    for _ in 0..turns {
+        let health_delta = {
+            let mut all_status = world.write_component::<StatusEffects>();
+            let mut status = all_status.get_mut(entity).unwrap();
+            if status.is_empty() {
+                break;
+            }
+            let delta = status.iter().fold(0, |acc, effect| match effect.kind {
+                StatusKind::POISON => acc - effect.power,
+                StatusKind::REGENERATION => acc + effect.power,
+                _ => acc,
+            });
+            expired.extend(status.tick());
+            delta
+        };
+
+        if health_delta != 0 {
+            if let Some(mut stats) = world.write_component::<Stats>().get_mut(entity) {
+                stats.update(Stat::HEALTH, health_delta);
+            }
+        }
+
+        if is_dead(world, entity) {
+            let name = being_name(world, entity);
+            world.write_global::<Logger>().log(format!("{} dies", name));
+            kill_being(world, entity);
+            return false;
+        }
+    }
+
+    for kind in expired {
+        log_expired(world, entity, kind);
+    }
+    sync_act_time(world, entity);
+    true
+}
+
+fn is_dead(world: &World, entity: Entity) -> bool {
+    match world.read_component::<Stats>().get(entity) {
+        None => false,
+        Some(stats) => stats.get(Stat::HEALTH).map(|h| h <= 0).unwrap_or(false),
+    }
+}
+
+fn log_expired(world: &World, entity: Entity, kind: StatusKind) {
+    let name = being_name(world, entity);
+    world
+        .write_global::<Logger>()
+        .log(format!("{} is no longer {}", name, kind.adjective()));
+}
+
+/// Haste + Slow change the act time of the being, the original is restored when they expire.
+fn sync_act_time(world: &mut World, entity: Entity) {
+    let mut all_status = world.write_component::<StatusEffects>();
+    let mut status = match all_status.get_mut(entity) {
+        None => return,
+        Some(status) => status,
+    };
+    let mut beings = world.write_component::<Being>();
+    let mut being = match beings.get_mut(entity) {
+        None => return,
+        Some(being) => being,
+    };
+
+    if status.has(StatusKind::HASTE) || status.has(StatusKind::SLOW) {
+        let base = *status.base_act_time.get_or_insert(being.act_time);
+        being.act_time = status.act_time(base);
+    } else if let Some(base) = status.base_act_time.take() {
+        being.act_time = base;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::task::do_next_task;
+    use crate::test_util;
+    use gw_ecs::prelude::Ecs;
+
+    fn make_ecs() -> (Ecs, Entity) {
+        let mut ecs = test_util::make_ecs(5, 3);
+        let world = ecs.current_world_mut();
+        let mut being = Being::new("RAT".to_string());
+        being.name = Some("rat".to_string());
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 5);
+        let entity = world.spawn((being, stats));
+        (ecs, entity)
+    }
+
+    fn health(world: &World, entity: Entity) -> i32 {
+        world
+            .read_component::<Stats>()
+            .get(entity)
+            .unwrap()
+            .get(Stat::HEALTH)
+            .unwrap()
+    }
+
+    fn run_turn(world: &mut World, entity: Entity, time: u64) {
+        world.write_resource::<Executor>().insert(entity, time);
+        let _ = do_next_task(world);
+    }
+
+    #[test]
+    fn poison_ticks_and_expires() {
+        let (mut ecs, rat) = make_ecs();
+        let world = ecs.current_world_mut();
+
+        assert!(apply_status(
+            world,
+            rat,
+            StatusEffect::new(StatusKind::POISON, 2, 1)
+        ));
+        run_turn(world, rat, 150);
+        assert_eq!(health(world, rat), 4);
+
+        run_turn(world, rat, 150);
+        assert_eq!(health(world, rat), 3);
+        assert!(!world
+            .read_component::<StatusEffects>()
+            .get(rat)
+            .unwrap()
+            .has(StatusKind::POISON));
+
+        let mut logger = world.write_global::<Logger>();
+        let msgs: Vec<String> = logger.iter().map(|m| m.msg.clone()).collect();
+        assert_eq!(msgs[0], "rat is no longer poisoned");
+    }
+
+    #[test]
+    fn poison_kills() {
+        let (mut ecs, rat) = make_ecs();
+        let world = ecs.current_world_mut();
+
+        apply_status(world, rat, StatusEffect::new(StatusKind::POISON, 10, 3));
+        run_turn(world, rat, 200);
+        assert!(!world.entities().is_alive(rat));
+        assert_eq!(world.read_resource::<Executor>().len(), 0);
+    }
+
+    #[test]
+    fn haste_restores_act_time() {
+        let (mut ecs, rat) = make_ecs();
+        let world = ecs.current_world_mut();
+
+        apply_status(world, rat, StatusEffect::new(StatusKind::HASTE, 1, 1));
+        assert_eq!(
+            world.read_component::<Being>().get(rat).unwrap().act_time,
+            50
+        );
+
+        apply_status(world, rat, StatusEffect::new(StatusKind::SLOW, 3, 1));
+        assert_eq!(
+            world.read_component::<Being>().get(rat).unwrap().act_time,
+            100
+        );
+
+        run_turn(world, rat, 100);
+        assert_eq!(
+            world.read_component::<Being>().get(rat).unwrap().act_time,
+            200
+        );
+
+        remove_status(world, rat, StatusKind::SLOW);
+        assert_eq!(
+            world.read_component::<Being>().get(rat).unwrap().act_time,
+            100
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use crate::action::move_step::MoveStepAction;
+use crate::action::BoxedAction;
+use crate::being::{Being, BeingFlags};
+use crate::fov::{can_see, FOV};
+use crate::hero::Hero;
+use crate::horde::are_enemies;
+use crate::light::is_dark;
+use crate::log::Logger;
+use crate::map::{Cell, Map};
+use crate::position::Position;
+use gw_ecs::prelude::{Entity, Join, World};
+use gw_util::path::{calculate_costs, PathfindingSource, SearchGrid, BLOCKED};
+use gw_util::point::Point;
+use gw_util::xy::Wrap;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoMoveGoal {
+    Explore,
+    Travel(Point),
+}
+
+/// The hero walking on their own (auto-explore or travel) until they get there or something interesting happens
+#[derive(Default)]
+pub struct AutoMove {
+    goal: Option<AutoMoveGoal>,
+    seen: HashSet<Entity>,
+    messages: usize,
+}
+
+impl AutoMove {
+    pub fn goal(&self) -> Option<AutoMoveGoal> {
+        self.goal
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.goal.is_some()
+    }
+
+    pub fn stop(&mut self) {
+        self.goal = None;
+        self.seen.clear();
+    }
+}
+
+/// Starts exploring the level - the hero walks to the closest cell that has not been revealed yet
+pub fn start_explore(world: &mut World) {
+    start_auto_move(world, AutoMoveGoal::Explore);
+}
+
+/// Starts walking to the point (through the cells the hero knows about)
+pub fn start_travel(world: &mut World, point: Point) {
+    start_auto_move(world, AutoMoveGoal::Travel(point));
+}
+
+/// Starts walking to the named map location - returns false if there is no such location
+pub fn start_travel_to_location(world: &mut World, id: &str) -> bool {
+    let point = {
+        let map = world.read_resource::<Map>();
+        match map.get_location(id) {
+            None => return false,
+            Some(idx) => map.to_point(idx),
+        }
+    };
+    start_travel(world, point);
+    true
+}
+
+pub fn stop_auto_move(world: &mut World) {
+    if let Some(mut auto_move) = world.try_write_resource::<AutoMove>() {
+        auto_move.stop();
+    }
+}
+
+fn start_auto_move(world: &mut World, goal: AutoMoveGoal) {
+    let hero = world.read_resource::<Hero>().entity;
+    // The beings that are already in view do not stop us
+    let seen = visible_beings(world, hero);
+    let messages = world.read_global::<Logger>().total();
+
+    let mut auto_move = world.write_resource::<AutoMove>();
+    auto_move.goal = Some(goal);
+    auto_move.seen = seen;
+    auto_move.messages = messages;
+}
+
+/// The beings that the hero can see that should stop an auto move - enemies and anything with INTERRUPT_WHEN_SEEN
+fn visible_beings(world: &World, hero: Entity) -> HashSet<Entity> {
+    let fov = world.try_read_resource::<FOV>();
+    let entities = world.entities();
+    let beings = world.read_component::<Being>();
+    let positions = world.read_component::<Position>();
+
+    (&entities, &beings, &positions)
+        .join()
+        .filter(|(entity, being, pos)| {
+            if *entity == hero {
+                return false;
+            }
+            if !being.has_flag(BeingFlags::INTERRUPT_WHEN_SEEN)
+                && !are_enemies(world, hero, *entity)
+            {
+                return false;
+            }
+            let point = pos.point();
+            let visible = match fov.as_ref() {
+                None => can_see(world, hero, *entity),
+                Some(fov) => fov.is_visible(point.x, point.y),
+            };
+            visible && !is_dark(world, point)
+        })
+        .map(|(entity, _, _)| entity)
+        .collect()
+}
+
+/// Did anything happen since the last step (a new monster in view or a new message)?
+fn is_interrupted(world: &World, hero: Entity) -> bool {
+    let seen = visible_beings(world, hero);
+    let messages = world.read_global::<Logger>().total();
+
+    let mut auto_move = world.write_resource::<AutoMove>();
+    let interrupted =
+        messages != auto_move.messages || seen.iter().any(|e| !auto_move.seen.contains(e));
+    auto_move.seen = seen;
+    auto_move.messages = messages;
+    interrupted
+}
+
+/// Pathing through the cells that the hero knows about (revealed or magic mapped)
+struct KnownPath<'a> {
+    map: &'a Map,
+    fov: Option<&'a FOV>,
+}
+
+impl<'a> KnownPath<'a> {
+    fn is_known(&self, x: i32, y: i32) -> bool {
+        match self.fov {
+            None => true,
+            Some(fov) => fov.is_revealed(x, y) || fov.is_mapped(x, y),
+        }
+    }
+}
+
+impl<'a> PathfindingSource for KnownPath<'a> {
+    fn move_cost(&self, x: i32, y: i32) -> Option<f32> {
+        let idx = self.map.get_wrapped_index(x, y)?;
+        if !self.is_known(x, y) || self.map.get_cell(idx).unwrap().blocks() {
+            return None;
+        }
+        if self.map.blocked[idx] {
+            return Some(5.0);
+        }
+        Some(1.0)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.map.size()
+    }
+
+    fn wrap(&self) -> Wrap {
+        self.map.wrap
+    }
+}
+
+/// The direction of the next step toward the goal - None if there is nowhere (left) to go
+fn next_step(world: &World, hero_point: Point, goal: AutoMoveGoal) -> Option<Point> {
+    let map = world.read_resource::<Map>();
+    let fov = world.try_read_resource::<FOV>();
+    let source = KnownPath {
+        map: &map,
+        fov: fov.as_deref(),
+    };
+
+    let (width, height) = source.size();
+    let mut grid = SearchGrid::new(width, height);
+    match goal {
+        AutoMoveGoal::Travel(point) => grid.set_goal_xy(point.x, point.y),
+        AutoMoveGoal::Explore => {
+            let fov = fov.as_ref()?;
+            for y in 0..height as i32 {
+                for x in 0..width as i32 {
+                    if !fov.is_revealed(x, y) {
+                        grid.set_goal_xy(x, y);
+                    }
+                }
+            }
+        }
+    }
+    calculate_costs(&mut grid, &source, true);
+
+    let distance = grid.distance_xy(hero_point.x, hero_point.y);
+    if distance <= 0.0 || distance >= BLOCKED {
+        return None;
+    }
+    grid.next_dir(hero_point.x, hero_point.y, &*map, true)
+        .copied()
+}
+
+/// The path (without the start) that a travel from the start to the goal would take - None if there is no known way
+pub fn travel_path(world: &World, start: Point, goal: Point) -> Option<Vec<Point>> {
+    let map = world.read_resource::<Map>();
+    let fov = world.try_read_resource::<FOV>();
+    let source = KnownPath {
+        map: &map,
+        fov: fov.as_deref(),
+    };
+
+    let (width, height) = source.size();
+    let mut grid = SearchGrid::new(width, height);
+    grid.set_goal_xy(goal.x, goal.y);
+    calculate_costs(&mut grid, &source, true);
+
+    if grid.distance_xy(start.x, start.y) >= BLOCKED {
+        return None;
+    }
+    let path: Vec<Point> = grid.path_from(start, &*map, true).collect();
+    match path.last() == Some(&goal) || start == goal {
+        true => Some(path),
+        false => None,
+    }
+}
+
+/// The next step of the hero's auto move (if any).  Stops the auto move when the hero gets there or is interrupted.
+pub fn next_auto_move_action(world: &mut World, entity: Entity) -> Option<BoxedAction> {
+    let goal = world.try_read_resource::<AutoMove>()?.goal()?;
+
+    if is_interrupted(world, entity) {
+        stop_auto_move(world);
+        return None;
+    }
+
+    let hero_point = world.read_component::<Position>().get(entity)?.point();
+    match next_step(world, hero_point, goal) {
+        None => {
+            stop_auto_move(world);
+            if goal == AutoMoveGoal::Explore {
+                world
+                    .write_global::<Logger>()
+                    .log("Nothing left to explore");
+            }
+            None
+        }
+        Some(dir) => Some(Box::new(MoveStepAction::new(entity, dir.x, dir.y))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{add_test_world, make_empty_ecs};
+    use crate::tile::Tiles;
+    use gw_ecs::prelude::Ecs;
+
+    // ##########
+    // #........#
+    // ##########
+    fn make_ecs() -> (Ecs, Entity) {
+        let mut ecs = make_empty_ecs();
+        let world = add_test_world(&mut ecs, 10, 3);
+        let (floor, wall) = {
+            let tiles = world.read_global::<Tiles>();
+            (tiles.get("FLOOR").unwrap(), tiles.get("WALL").unwrap())
+        };
+        {
+            let mut map = world.write_resource::<Map>();
+            map.fill(wall);
+            for x in 1..9 {
+                let idx = map.get_index(x, 1).unwrap();
+                map.force_tile(idx, floor.clone());
+            }
+            let end = map.get_index(8, 1).unwrap();
+            map.set_location("END", end);
+        }
+
+        let hero = world.spawn((Being::new("hero".to_string()), Position::new(1, 1)));
+        world.insert_resource(Hero::new(hero));
+
+        // The hero knows about the left half of the hall
+        let mut fov = FOV::new(10);
+        fov.resize(10, 3);
+        for y in 0..3 {
+            for x in 0..5 {
+                fov.set_visible(x, y);
+            }
+        }
+        world.insert_resource(fov);
+        (ecs, hero)
+    }
+
+    fn step(world: &mut World, hero: Entity) -> Option<Point> {
+        let mut action = next_auto_move_action(world, hero)?;
+        action.execute(world);
+        Some(
+            world
+                .read_component::<Position>()
+                .get(hero)
+                .unwrap()
+                .point(),
+        )
+    }
+
+    #[test]
+    fn explore_walks_to_unrevealed() {
+        let (mut ecs, hero) = make_ecs();
+        let world = ecs.current_world_mut();
+
+        start_explore(world);
+        assert_eq!(step(world, hero), Some(Point::new(2, 1)));
+        assert_eq!(step(world, hero), Some(Point::new(3, 1)));
+        assert_eq!(step(world, hero), Some(Point::new(4, 1)));
+        assert_eq!(step(world, hero), Some(Point::new(5, 1)));
+
+        world.write_resource::<FOV>().reveal_all();
+        assert_eq!(step(world, hero), None);
+        assert!(!world.read_resource::<AutoMove>().is_active());
+
+        let mut logger = world.write_global::<Logger>();
+        assert_eq!(logger.iter().next().unwrap().msg, "Nothing left to explore");
+    }
+
+    #[test]
+    fn travel_stops_for_monsters() {
+        let (mut ecs, hero) = make_ecs();
+        let world = ecs.current_world_mut();
+        world.write_resource::<FOV>().reveal_all();
+
+        assert!(start_travel_to_location(world, "END"));
+        assert_eq!(step(world, hero), Some(Point::new(2, 1)));
+
+        world.spawn((Being::new("rat".to_string()), Position::new(7, 1)));
+        world.write_resource::<FOV>().set_visible(7, 1);
+
+        assert_eq!(step(world, hero), None);
+        assert!(!world.read_resource::<AutoMove>().is_active());
+    }
+
+    #[test]
+    fn path_through_known_cells() {
+        let (ecs, _) = make_ecs();
+        let world = ecs.current_world();
+
+        let path = travel_path(world, Point::new(1, 1), Point::new(4, 1)).unwrap();
+        assert_eq!(
+            path,
+            vec![Point::new(2, 1), Point::new(3, 1), Point::new(4, 1)]
+        );
+        assert!(travel_path(world, Point::new(1, 1), Point::new(7, 1)).is_none());
+    }
+}
@@ -1,64 +1,661 @@
-use super::{execute_actor_action, get_hero_entity, TaskResult};
+use super::{
+    execute_actor_action, get_hero_entity, ScentMap, TaskResult, SCENT_TIME, SUPER_SCENT_TIME,
+};
 use crate::{
-    action::{idle::IdleAction, move_step::MoveStepAction, BoxedAction},
-    being::Being,
-    fov::FOV,
-    map::Map,
+    action::{
+        idle::IdleAction, melee::MeleeAction, move_step::MoveStepAction, open_door::OpenDoorAction,
+        pickup::PickupAction, ranged::RangedAction, BoxedAction,
+    },
+    being::{AIFlags, Being, BeingFlags, BeingKindFlags, Stat, Stats},
+    combat::{has_ammo, trace_projectile, Melee, Ranged},
+    fov::{can_see, has_line_of_sight, Vision, FOV},
+    horde::{leader_of, leader_target, set_leader_target, FOLLOW_DISTANCE},
+    item::Inventory,
+    map::{Cell, Map},
     position::Position,
+    status::{StatusEffects, StatusKind},
+    task::Executor,
+};
+use gw_ecs::prelude::{Entity, World};
+use gw_util::{
+    path::{a_star_search, BlockedSource, PathfindingSource},
+    point::{Point, DIRS},
+    rng::RandomNumberGenerator,
+    xy::Wrap,
 };
-use gw_ecs::prelude::{Entity, Fetch, ReadComp, World};
-use gw_util::path::a_star_search;
 
+/// Archers try to stay this far (or closer) from the hero - but not adjacent
+pub const ARCHER_RANGE: i32 = 5;
+
+/// What the monster knows this turn
+struct MonsterInfo {
+    entity: Entity,
+    act_time: u32,
+    flags: AIFlags,
+    point: Point,
+    hero: Entity,
+    hero_point: Option<Point>,
+    can_see_hero: bool,
+    can_attack: bool,
+    captive: bool,
+    ally: bool,
+    leader_point: Option<Point>,
+}
+
+impl MonsterInfo {
+    fn gather(world: &World, entity: Entity) -> Option<MonsterInfo> {
+        let hero = get_hero_entity(world);
+        let beings = world.read_component::<Being>();
+        let positions = world.read_component::<Position>();
+
+        let being = beings.get(entity)?;
+        let point = positions.get(entity)?.point();
+        let hero_point = match beings.get(hero) {
+            None => None,
+            Some(hero_being) => match hero_being.has_flag(crate::being::BeingFlags::DESTROYED) {
+                true => None,
+                false => positions.get(hero).map(|p| p.point()),
+            },
+        };
+
+        // Can player see me?  If so, I can see player...
+        let can_see_hero = match hero_point {
+            None => false,
+            Some(hero_point) => {
+                !is_blind(world, entity) && sees_hero(world, entity, point, hero, hero_point)
+            }
+        };
+
+        let can_attack = !being.ai_flags.contains(AIFlags::NEVER_BLOW)
+            && !being.kind_flags.contains(BeingKindFlags::WILL_NOT_ATTACK)
+            && world.read_component::<Melee>().contains(entity);
+
+        let leader_point = leader_of(world, entity)
+            .and_then(|leader| positions.get(leader))
+            .map(|p| p.point());
+
+        Some(MonsterInfo {
+            entity,
+            act_time: being.act_time,
+            flags: being.ai_flags,
+            point,
+            hero,
+            hero_point,
+            can_see_hero,
+            can_attack,
+            captive: being.has_flag(BeingFlags::CAPTIVE),
+            ally: being.has_flag(BeingFlags::ALLY),
+            leader_point,
+        })
+    }
+
+    fn idle(&self) -> BoxedAction {
+        Box::new(IdleAction::new(self.entity, self.act_time))
+    }
+
+    fn hero_distance(&self) -> Option<i32> {
+        self.hero_point.map(|hero| self.distance_to(hero))
+    }
+
+    fn distance_to(&self, point: Point) -> i32 {
+        (point.x - self.point.x)
+            .abs()
+            .max((point.y - self.point.y).abs())
+    }
+}
+
+/// A monster AI that is driven by the being's AIFlags
 pub fn basic_monster_ai(world: &mut World, entity: Entity) -> TaskResult {
-    let hero_entity = get_hero_entity(world);
+    let info = match MonsterInfo::gather(world, entity) {
+        None => return TaskResult::Finished,
+        Some(info) => info,
+    };
 
-    let (beings, positions) = <(ReadComp<Being>, ReadComp<Position>)>::fetch(world);
+    let action = pick_action(world, &info);
+    execute_actor_action(action, world, entity)
+}
 
-    let being = beings.get(entity).unwrap();
-    let pos = positions.get(entity).unwrap();
+fn pick_action(world: &World, info: &MonsterInfo) -> BoxedAction {
+    // In chains - waiting to be freed
+    if info.captive {
+        return info.idle();
+    }
 
-    let (act_time, _ai_flags) = (being.act_time, being.ai_flags.clone());
-    let mons_pt = pos.point();
+    if info.ally {
+        return ally_action(world, info);
+    }
 
-    // Can player see me?  If so, I can see player...
-    let can_see_player = match world.try_read_resource::<FOV>() {
-        None => {
-            // TODO - do a line test?
-            false
+    if info.flags.contains(AIFlags::TAKE_ITEM) && items_here(world, info) {
+        return Box::new(PickupAction::new(info.entity));
+    }
+
+    let adjacent = info.hero_distance() == Some(1);
+
+    if should_flee(world, info) {
+        if let Some(step) = flee_step(world, info) {
+            return Box::new(MoveStepAction::new(info.entity, step.x, step.y));
+        }
+        // Cornered...
+        if adjacent && info.can_attack {
+            return Box::new(MeleeAction::new(info.entity, info.hero));
+        }
+        return info.idle();
+    }
+
+    if info.can_see_hero {
+        let hero_point = info.hero_point.unwrap();
+        set_leader_target(world, info.entity, info.hero);
+
+        if info.flags.contains(AIFlags::ARCHER) && adjacent {
+            if let Some(step) = flee_step(world, info) {
+                return Box::new(MoveStepAction::new(info.entity, step.x, step.y));
+            }
+        }
+
+        if adjacent && info.can_attack {
+            return Box::new(MeleeAction::new(info.entity, info.hero));
         }
-        Some(fov) => fov.is_visible(mons_pt.x, mons_pt.y),
+
+        if can_shoot(world, info, hero_point) {
+            return Box::new(RangedAction::new(info.entity, hero_point));
+        }
+
+        if info.flags.contains(AIFlags::ARCHER)
+            && !adjacent
+            && info.hero_distance().unwrap() <= ARCHER_RANGE
+        {
+            // Hold position
+            return info.idle();
+        }
+
+        return approach(world, info, hero_point);
+    }
+
+    if let Some(action) = follow_leader(world, info) {
+        return action;
+    }
+
+    if info.flags.intersects(AIFlags::SCENT | AIFlags::SUPER_SCENT) {
+        let max_age = match info.flags.contains(AIFlags::SUPER_SCENT) {
+            true => SUPER_SCENT_TIME,
+            false => SCENT_TIME,
+        };
+        let now = world.read_resource::<Executor>().time();
+        if let Some(scent) = world.try_read_resource::<ScentMap>() {
+            if let Some(dir) = scent.follow(info.point, now, max_age) {
+                let target = info.point + dir;
+                drop(scent);
+                return step_toward(world, info, target);
+            }
+        }
+    }
+
+    if info.flags.contains(AIFlags::TAKE_ITEM) {
+        if let Some(dir) = adjacent_item(world, info) {
+            return Box::new(MoveStepAction::new(info.entity, dir.x, dir.y));
+        }
+    }
+
+    if info
+        .flags
+        .intersects(AIFlags::AIMLESS_MOVE | AIFlags::WANDER)
+    {
+        let mut rng = world.write_resource::<RandomNumberGenerator>();
+        if rng.chance(25) {
+            let dir = *rng.random_slice_entry(&DIRS).unwrap();
+            return Box::new(MoveStepAction::new(info.entity, dir.x, dir.y));
+        }
+    }
+
+    info.idle()
+}
+
+/// Followers join the leader's fight or head back toward the leader if they stray too far
+fn follow_leader(world: &World, info: &MonsterInfo) -> Option<BoxedAction> {
+    let leader_point = info.leader_point?;
+
+    if let Some(target) = leader_target(world, info.entity) {
+        let target_point = world.read_component::<Position>().get(target)?.point();
+        if info.distance_to(target_point) == 1 && info.can_attack {
+            return Some(Box::new(MeleeAction::new(info.entity, target)));
+        }
+        return Some(approach(world, info, target_point));
+    }
+
+    match info.distance_to(leader_point) > FOLLOW_DISTANCE {
+        false => None,
+        true => Some(approach(world, info, leader_point)),
+    }
+}
+
+/// Allies fight the hero's enemies and otherwise stay close
+fn ally_action(world: &World, info: &MonsterInfo) -> BoxedAction {
+    match follow_leader(world, info) {
+        Some(action) => action,
+        None => info.idle(),
+    }
+}
+
+/// Monsters with Vision use it, otherwise - Can player see me?  If so, I can see player...
+fn sees_hero(world: &World, entity: Entity, point: Point, hero: Entity, hero_point: Point) -> bool {
+    if world.read_component::<Vision>().contains(entity) {
+        return can_see(world, entity, hero);
+    }
+    match world.try_read_resource::<FOV>() {
+        None => has_line_of_sight(world, point, hero_point),
+        Some(fov) => fov.is_visible(point.x, point.y),
+    }
+}
+
+/// Is the hero in range of a clear shot?
+fn can_shoot(world: &World, info: &MonsterInfo, hero_point: Point) -> bool {
+    let ranges = world.read_component::<Ranged>();
+    let ranged = match ranges.get(info.entity) {
+        None => return false,
+        Some(ranged) => ranged,
     };
+    if info.hero_distance().unwrap() > ranged.range as i32 || !has_ammo(world, info.entity, &ranged)
+    {
+        return false;
+    }
+    let will_not_attack = world
+        .read_component::<Being>()
+        .get(info.entity)
+        .map(|b| b.kind_flags.contains(BeingKindFlags::WILL_NOT_ATTACK))
+        .unwrap_or(true);
+    if will_not_attack {
+        return false;
+    }
 
-    let action: BoxedAction = if !can_see_player {
-        Box::new(IdleAction::new(entity, act_time as u32))
-    } else {
-        // world.set_update_sidebar(); // something is probably going to change
-        let hero_pos = positions.get(hero_entity).unwrap();
-        // let (_hero_being, hero_pos) = query.get(&level.world, hero_entity).unwrap();
+    let map = world.read_resource::<Map>();
+    trace_projectile(&map, info.point, hero_point, ranged.range).target == Some(info.hero)
+}
 
-        let map = world.read_resource::<Map>();
-        match a_star_search(mons_pt, hero_pos.point(), &*map, false) {
-            Some(path) => {
-                if path.len() > 1 {
-                    let step = path[1] - path[0];
-                    Box::new(MoveStepAction::new(entity, step.x, step.y))
-                // } else if path.len() == 1 {
-                //     // next to player
-                //     // return TalkAction::new(entity, "Tag, you are it");
-                //     Box::new(MeleeAction::new(entity, hero_entity))
-                } else {
-                    Box::new(IdleAction::new(entity, act_time as u32))
-                }
+fn is_blind(world: &World, entity: Entity) -> bool {
+    match world.read_component::<StatusEffects>().get(entity) {
+        None => false,
+        Some(status) => status.has(StatusKind::BLIND),
+    }
+}
+
+fn should_flee(world: &World, info: &MonsterInfo) -> bool {
+    if info.hero_point.is_none() {
+        return false;
+    }
+    let stats = world.read_component::<Stats>();
+    let stats = match stats.get(info.entity) {
+        None => return false,
+        Some(stats) => stats,
+    };
+
+    if info.flags.contains(AIFlags::TAKE_HIT_RUN) {
+        if let (Some(health), Some(max)) = (stats.get(Stat::HEALTH), stats.get_max(Stat::HEALTH)) {
+            if health < max {
+                return true;
             }
-            None => {
-                // Box::new(TalkAction::new(entity, "I see you")),
-                Box::new(IdleAction::new(entity, act_time as u32))
+        }
+    }
+
+    if info.flags.contains(AIFlags::LOW_MANA_RUN) {
+        if let (Some(mana), Some(max)) = (stats.get(Stat::MAGIC), stats.get_max(Stat::MAGIC)) {
+            if max > 0 && mana * 4 < max {
+                return true;
             }
         }
+    }
+
+    false
+}
+
+/// The step that takes the monster furthest from the hero (if any step increases the distance)
+fn flee_step(world: &World, info: &MonsterInfo) -> Option<Point> {
+    let hero_point = info.hero_point?;
+    let map = world.read_resource::<Map>();
+    let path = MonsterPath::new(&map, info.flags);
+
+    let mut best_dist = distance_squared(info.point, hero_point);
+    let mut best = None;
+    for dir in DIRS.iter() {
+        let next = info.point + *dir;
+        if path.is_blocked(next.x, next.y) {
+            continue;
+        }
+        let dist = distance_squared(next, hero_point);
+        if dist > best_dist {
+            best_dist = dist;
+            best = Some(*dir);
+        }
+    }
+    best
+}
+
+fn distance_squared(a: Point, b: Point) -> i32 {
+    (a.x - b.x).pow(2) + (a.y - b.y).pow(2)
+}
+
+fn approach(world: &World, info: &MonsterInfo, target: Point) -> BoxedAction {
+    if info.flags.contains(AIFlags::STUPID) {
+        let dir = Point::new(
+            (target.x - info.point.x).signum(),
+            (target.y - info.point.y).signum(),
+        );
+        return step_toward(world, info, info.point + dir);
+    }
+
+    let path = {
+        let map = world.read_resource::<Map>();
+        a_star_search(
+            info.point,
+            target,
+            &MonsterPath::new(&map, info.flags),
+            false,
+        )
     };
 
-    drop(beings);
-    drop(positions);
+    // The path does not include the starting point
+    match path.and_then(|path| path.first().copied()) {
+        Some(next) => step_toward(world, info, next),
+        _ => {
+            // Box::new(TalkAction::new(entity, "I see you")),
+            info.idle()
+        }
+    }
+}
+
+/// Moves into the adjacent point - opening or bashing a door that is in the way
+fn step_toward(world: &World, info: &MonsterInfo, next: Point) -> BoxedAction {
+    let map = world.read_resource::<Map>();
+    if let Some(idx) = map.get_wrapped_index(next.x, next.y) {
+        let blocks = map.get_cell(idx).unwrap().blocks();
+        if blocks && !info.flags.contains(AIFlags::PASS_WALL) {
+            let can_open = map.has_cell_effects(idx, "open");
+            if info.flags.contains(AIFlags::OPEN_DOOR) && can_open {
+                return Box::new(OpenDoorAction::new(info.entity, next));
+            }
+            if info.flags.contains(AIFlags::BASH_DOOR)
+                && (can_open || map.has_cell_effects(idx, "unlock"))
+            {
+                return Box::new(OpenDoorAction::bash(info.entity, next));
+            }
+        }
+    }
+
+    let step = next - info.point;
+    Box::new(MoveStepAction::new(info.entity, step.x, step.y))
+}
+
+fn items_here(world: &World, info: &MonsterInfo) -> bool {
+    if !world.read_component::<Inventory>().contains(info.entity) {
+        return false;
+    }
+    let map = world.read_resource::<Map>();
+    match map.get_wrapped_index(info.point.x, info.point.y) {
+        None => false,
+        Some(idx) => map.iter_items(idx).next().is_some(),
+    }
+}
+
+fn adjacent_item(world: &World, info: &MonsterInfo) -> Option<Point> {
+    if !world.read_component::<Inventory>().contains(info.entity) {
+        return None;
+    }
+    let map = world.read_resource::<Map>();
+    DIRS.iter().copied().find(|dir| {
+        match map.get_wrapped_index(info.point.x + dir.x, info.point.y + dir.y) {
+            None => false,
+            Some(idx) => map.iter_items(idx).next().is_some() && !map.is_blocked(idx),
+        }
+    })
+}
+
+/// Pathing over the map using the monster's abilities (doors, walls)
+struct MonsterPath<'a> {
+    map: &'a Map,
+    flags: AIFlags,
+}
+
+impl<'a> MonsterPath<'a> {
+    fn new(map: &'a Map, flags: AIFlags) -> Self {
+        MonsterPath { map, flags }
+    }
+
+    /// Can the monster get through this blocking cell?
+    fn can_pass(&self, idx: usize) -> bool {
+        if self.flags.contains(AIFlags::PASS_WALL) {
+            return true;
+        }
+        let can_open = self.map.has_cell_effects(idx, "open");
+        if self.flags.contains(AIFlags::OPEN_DOOR) && can_open {
+            return true;
+        }
+        self.flags.contains(AIFlags::BASH_DOOR)
+            && (can_open || self.map.has_cell_effects(idx, "unlock"))
+    }
+}
+
+impl<'a> PathfindingSource for MonsterPath<'a> {
+    fn move_cost(&self, x: i32, y: i32) -> Option<f32> {
+        let idx = self.map.get_wrapped_index(x, y)?;
+        if self.map.get_cell(idx).unwrap().blocks() {
+            return match self.can_pass(idx) {
+                true => Some(2.0),
+                false => None,
+            };
+        }
+        if self.map.blocked[idx] {
+            return Some(5.0);
+        }
+        Some(1.0)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.map.size()
+    }
+
+    fn wrap(&self) -> Wrap {
+        self.map.wrap
+    }
+}
+
+impl<'a> BlockedSource for MonsterPath<'a> {
+    fn is_blocked(&self, x: i32, y: i32) -> bool {
+        let idx = match self.map.get_wrapped_index(x, y) {
+            None => return true,
+            Some(idx) => idx,
+        };
+        if self.map.get_cell(idx).unwrap().blocks() && !self.can_pass(idx) {
+            return true;
+        }
+        self.map.blocked[idx]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::effect::ForceTile;
+    use crate::hero::Hero;
+    use crate::horde::join_leader;
+    use crate::test_util::make_ecs;
+    use crate::tile::TileBuilder;
+
+    fn make_being(world: &mut World, name: &str, point: Point, flags: &str) -> Entity {
+        let mut being = Being::new(name.to_string());
+        being.ai_flags.apply(flags);
+
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 8);
+
+        world.spawn((being, Position::new(point.x, point.y), stats))
+    }
+
+    fn see_all(world: &mut World) {
+        let (width, height) = world.read_resource::<Map>().size();
+        let mut fov = FOV::new(10);
+        fov.resize(width, height);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                fov.set_visible(x, y);
+            }
+        }
+        world.insert_resource(fov);
+    }
+
+    fn point_of(world: &World, entity: Entity) -> Point {
+        world
+            .read_component::<Position>()
+            .get(entity)
+            .unwrap()
+            .point()
+    }
+
+    #[test]
+    fn hurt_monster_flees() {
+        let mut ecs = make_ecs(7, 1);
+        let world = ecs.current_world_mut();
+        see_all(world);
+
+        let hero = make_being(world, "hero", Point::new(2, 0), "");
+        world.insert_resource(Hero::new(hero));
+        let rat = make_being(world, "rat", Point::new(3, 0), "TAKE_HIT_RUN");
+
+        // Not hurt - holds its ground
+        basic_monster_ai(world, rat);
+        assert_eq!(point_of(world, rat), Point::new(3, 0));
+
+        world
+            .write_component::<Stats>()
+            .get_mut(rat)
+            .unwrap()
+            .update(Stat::HEALTH, -3);
+
+        basic_monster_ai(world, rat);
+        assert_eq!(point_of(world, rat), Point::new(4, 0));
+    }
+
+    #[test]
+    fn allies_follow_and_fight() {
+        let mut ecs = make_ecs(9, 1);
+        let world = ecs.current_world_mut();
+
+        let hero = make_being(world, "hero", Point::new(0, 0), "");
+        world.insert_resource(Hero::new(hero));
+        let ally = make_being(world, "ally", Point::new(4, 0), "");
+        world
+            .write_component::<Being>()
+            .get_mut(ally)
+            .unwrap()
+            .set_flag(BeingFlags::ALLY);
+        join_leader(world, ally, hero, false);
+
+        // Too far from the hero
+        basic_monster_ai(world, ally);
+        assert_eq!(point_of(world, ally), Point::new(3, 0));
+        basic_monster_ai(world, ally);
+        assert_eq!(point_of(world, ally), Point::new(2, 0));
+        basic_monster_ai(world, ally);
+        assert_eq!(point_of(world, ally), Point::new(2, 0));
+
+        // Joins the hero's fight
+        let rat = make_being(world, "rat", Point::new(6, 0), "");
+        set_leader_target(world, hero, rat);
+        basic_monster_ai(world, ally);
+        assert_eq!(point_of(world, ally), Point::new(3, 0));
+    }
+
+    #[test]
+    fn follows_scent() {
+        let mut ecs = make_ecs(7, 3);
+        let world = ecs.current_world_mut();
+
+        let hero = make_being(world, "hero", Point::new(1, 1), "");
+        world.insert_resource(Hero::new(hero));
+        let dog = make_being(world, "dog", Point::new(5, 1), "SCENT");
+
+        // The hero sees nothing, so nobody sees the hero
+        let mut fov = FOV::new(10);
+        fov.resize(7, 3);
+        world.insert_resource(fov);
+
+        crate::task::ensure_scent_map(world);
+        {
+            let mut scent = world.write_resource::<ScentMap>();
+            scent.lay(3, 1, 50);
+            scent.lay(2, 1, 100);
+        }
+
+        let rat = make_being(world, "rat", Point::new(5, 2), "");
+
+        basic_monster_ai(world, dog);
+        assert_eq!(point_of(world, dog), Point::new(4, 1));
+        basic_monster_ai(world, dog);
+        assert_eq!(point_of(world, dog), Point::new(3, 1));
+
+        // No nose
+        basic_monster_ai(world, rat);
+        assert_eq!(point_of(world, rat), Point::new(5, 2));
+    }
+
+    #[test]
+    fn opens_doors() {
+        let mut ecs = make_ecs(5, 1);
+        let world = ecs.current_world_mut();
+        see_all(world);
+
+        let door = TileBuilder::new("TEST_DOOR")
+            .blocks()
+            .action(
+                "open".to_string(),
+                Box::new(ForceTile::new("FLOOR".to_string())),
+            )
+            .build();
+        {
+            let mut map = world.write_resource::<Map>();
+            map.reset_tiles(1, door.clone());
+            map.reset_tiles(3, door);
+        }
+
+        let hero = make_being(world, "hero", Point::new(2, 0), "");
+        world.insert_resource(Hero::new(hero));
+        let orc = make_being(world, "orc", Point::new(0, 0), "OPEN_DOOR");
+        let rat = make_being(world, "rat", Point::new(4, 0), "");
+
+        basic_monster_ai(world, orc);
+        basic_monster_ai(world, rat);
+
+        let map = world.read_resource::<Map>();
+        assert!(!map.get_cell(1).unwrap().blocks());
+        assert!(map.get_cell(3).unwrap().blocks());
+        drop(map);
+
+        assert_eq!(point_of(world, orc), Point::new(0, 0));
+        assert_eq!(point_of(world, rat), Point::new(4, 0));
+    }
+
+    #[test]
+    fn archer_shoots() {
+        let mut ecs = make_ecs(7, 1);
+        let world = ecs.current_world_mut();
+        see_all(world);
+
+        let hero = make_being(world, "hero", Point::new(0, 0), "");
+        world.insert_resource(Hero::new(hero));
+        let archer = make_being(world, "archer", Point::new(4, 0), "ARCHER");
+
+        let mut data = std::collections::HashMap::new();
+        data.insert("damage".into(), gw_util::value::Value::from(2));
+        let ranged = crate::combat::parse_ranged(&data.into()).ok().unwrap();
+        let _ = world.write_component::<Ranged>().insert(archer, ranged);
 
-    return execute_actor_action(action, world, entity);
+        basic_monster_ai(world, archer);
+        assert_eq!(point_of(world, archer), Point::new(4, 0));
+        assert_eq!(
+            world
+                .read_component::<Stats>()
+                .get(hero)
+                .unwrap()
+                .get(Stat::HEALTH),
+            Some(6)
+        );
+    }
 }
@@ -1,13 +1,17 @@
+mod auto_move;
 mod basic_monster;
 mod idle;
 mod mirror_entity;
 mod move_randomly;
+mod scent;
 mod task;
 mod user_control;
 
+pub use auto_move::*;
 pub use basic_monster::*;
 pub use idle::*;
 pub use mirror_entity::*;
 pub use move_randomly::*;
+pub use scent::*;
 pub use task::*;
 pub use user_control::*;
@@ -0,0 +1,111 @@
+use crate::map::Map;
+use gw_ecs::prelude::World;
+use gw_util::point::{Point, DIRS};
+
+/// How long (in executor time) scent lasts for SCENT monsters
+pub const SCENT_TIME: u64 = 2000;
+/// How long (in executor time) scent lasts for SUPER_SCENT monsters
+pub const SUPER_SCENT_TIME: u64 = 5000;
+
+/// The trail left by the hero - each cell holds the last time the hero was there.
+#[derive(Debug, Default)]
+pub struct ScentMap {
+    width: u32,
+    height: u32,
+    data: Vec<u64>,
+}
+
+impl ScentMap {
+    pub fn new(width: u32, height: u32) -> Self {
+        ScentMap {
+            width,
+            height,
+            data: vec![0; (width * height) as usize],
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        Some((x + y * self.width as i32) as usize)
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(0);
+    }
+
+    /// The time the scent was laid (0 = no scent)
+    pub fn get(&self, x: i32, y: i32) -> u64 {
+        match self.index(x, y) {
+            None => 0,
+            Some(idx) => self.data[idx],
+        }
+    }
+
+    /// Marks the point with the time.  Adjacent cells get a fainter scent.
+    pub fn lay(&mut self, x: i32, y: i32, time: u64) {
+        for dir in DIRS.iter() {
+            if let Some(idx) = self.index(x + dir.x, y + dir.y) {
+                self.data[idx] = self.data[idx].max(time.saturating_sub(1));
+            }
+        }
+        if let Some(idx) = self.index(x, y) {
+            self.data[idx] = time;
+        }
+    }
+
+    /// The direction of the freshest scent around the point that is fresher than the point itself
+    pub fn follow(&self, from: Point, now: u64, max_age: u64) -> Option<Point> {
+        let oldest = now.saturating_sub(max_age).max(1);
+        let mut best_time = self.get(from.x, from.y).max(oldest);
+        let mut best = None;
+
+        for dir in DIRS.iter() {
+            let time = self.get(from.x + dir.x, from.y + dir.y);
+            if time > best_time {
+                best_time = time;
+                best = Some(*dir);
+            }
+        }
+        best
+    }
+}
+
+/// Makes sure there is a scent map that matches the size of the map
+pub fn ensure_scent_map(world: &mut World) {
+    let (width, height) = {
+        let map = world.read_resource::<Map>();
+        (map.width, map.height)
+    };
+    let needs_new = match world.try_read_resource::<ScentMap>() {
+        None => true,
+        Some(scent) => scent.width != width || scent.height != height,
+    };
+    if needs_new {
+        world.insert_resource(ScentMap::new(width, height));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn follow_trail() {
+        let mut scent = ScentMap::new(10, 10);
+        scent.lay(2, 2, 100);
+        scent.lay(3, 2, 200);
+        scent.lay(4, 3, 300);
+
+        assert_eq!(scent.follow(Point::new(1, 2), 400, 1000).unwrap().x, 1);
+        assert_eq!(
+            scent.follow(Point::new(3, 2), 400, 1000),
+            Some(Point::new(1, 1))
+        );
+        assert_eq!(scent.follow(Point::new(4, 3), 400, 1000), None);
+
+        // too old
+        assert_eq!(scent.follow(Point::new(1, 2), 5000, 1000), None);
+    }
+}
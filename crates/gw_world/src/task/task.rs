@@ -1,5 +1,15 @@
 use super::{basic_monster_ai, idle_ai, mirror_entity_ai, move_randomly_ai, user_control_ai};
-use crate::{hero::Hero, position::Position};
+use crate::{
+    action::search::passive_search,
+    animation::is_animating,
+    being::{speed_adjusted_time, take_level_up_screen},
+    fov::refresh_vision,
+    hero::Hero,
+    log::Logger,
+    map::{fire_stand_effects, update_environment},
+    position::Position,
+    status::update_status_effects,
+};
 use gw_app::log;
 use gw_app::screen::BoxedScreen;
 use gw_ecs::prelude::{Component, Entity, World};
@@ -201,6 +211,21 @@ impl Executor {
         self.tasks.remove(entity);
     }
 
+    /// Iterates the scheduled entities along with the (absolute) time they will act
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, u64)> + '_ {
+        self.tasks.tasks.iter().map(|t| (t.entity, t.time))
+    }
+
+    /// Replaces the clock and queue - used when restoring a saved game
+    pub fn restore<I: IntoIterator<Item = (Entity, u64)>>(&mut self, time: u64, entries: I) {
+        self.tasks.clear();
+        self.tasks.time = 0;
+        for (entity, at) in entries {
+            self.tasks.insert(TaskEntry::new(entity, at));
+        }
+        self.tasks.time = time;
+    }
+
     fn pop(&mut self) -> Option<TaskEntry> {
         self.tasks.pop()
     }
@@ -310,11 +335,23 @@ impl Executor {
 
 #[must_use]
 pub fn do_next_task(world: &mut World) -> DoNextTaskResult {
+    // Let the animations (e.g. arrows) finish first
+    if is_animating(world) {
+        return DoNextTaskResult::Done;
+    }
+
+    if let Some(screen) = take_level_up_screen(world) {
+        return DoNextTaskResult::PushMode(screen);
+    }
+
     let task = world.write_resource::<Executor>().pop();
 
     match task {
         None => DoNextTaskResult::Done,
         Some(task) => {
+            let time = world.read_resource::<Executor>().time();
+            world.write_global::<Logger>().set_time(time);
+
             let hero_entity = get_hero_entity(world);
             let res = if hero_entity == task.entity {
                 DoNextTaskResult::Hero
@@ -322,6 +359,21 @@ pub fn do_next_task(world: &mut World) -> DoNextTaskResult {
                 DoNextTaskResult::Other
             };
 
+            refresh_vision(world);
+            update_environment(world);
+
+            if !update_status_effects(world, task.entity) {
+                return res; // Died from a status effect
+            }
+
+            if !fire_stand_effects(world, task.entity) {
+                return res; // Died from the liquid/gas (or tile) it is standing in
+            }
+
+            if hero_entity == task.entity {
+                passive_search(world, hero_entity);
+            }
+
             let task_comp = world
                 .read_component::<Task>()
                 .get(task.entity)
@@ -348,6 +400,7 @@ pub fn do_next_task(world: &mut World) -> DoNextTaskResult {
                         }
                         TaskResult::Success(t) => {
                             // insert
+                            let t = speed_adjusted_time(world, task.entity, t);
                             world.write_resource::<Executor>().insert(task.entity, t);
                             res
                         }
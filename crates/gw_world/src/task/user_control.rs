@@ -1,4 +1,4 @@
-use super::TaskResult;
+use super::{next_auto_move_action, stop_auto_move, TaskResult};
 use crate::action::{ActionResult, BoxedAction};
 use gw_ecs::prelude::{Entity, World};
 
@@ -28,8 +28,15 @@ pub fn user_control_ai(world: &mut World, entity: Entity) -> TaskResult {
 
     let action = world.write_resource::<UserAction>().take();
     match action {
-        None => TaskResult::Retry,
-        Some(user_action) => execute_actor_action(user_action, world, entity),
+        None => match next_auto_move_action(world, entity) {
+            None => TaskResult::Retry,
+            Some(step) => execute_actor_action(step, world, entity),
+        },
+        Some(user_action) => {
+            // The player took over
+            stop_auto_move(world);
+            execute_actor_action(user_action, world, entity)
+        }
     }
 }
 
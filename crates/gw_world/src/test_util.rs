@@ -0,0 +1,35 @@
+//! The fixtures shared by the unit tests.
+
+use crate::hero::Hero;
+use crate::map::Map;
+use crate::tile::Tiles;
+use gw_ecs::prelude::{Ecs, World};
+use gw_util::rng::RandomNumberGenerator;
+
+/// An ecs with the standard components and globals, but no worlds
+pub fn make_empty_ecs() -> Ecs {
+    let mut ecs = Ecs::empty();
+    crate::register_components(&mut ecs);
+    crate::setup_ecs(&mut ecs);
+    ecs
+}
+
+/// Adds the "TEST" world with a FLOOR map, a seeded rng, no hero and the standard resources
+pub fn add_test_world(ecs: &mut Ecs, width: u32, height: u32) -> &mut World {
+    let world = ecs.create_world("TEST");
+    let floor = world.read_global::<Tiles>().get("FLOOR").unwrap();
+    let mut map = Map::new(width, height);
+    map.fill(floor);
+    world.insert_resource(map);
+    world.insert_resource(RandomNumberGenerator::seeded(12345));
+    world.insert_resource(Hero::default());
+    crate::setup_world(world);
+    world
+}
+
+/// An ecs with the "TEST" world (see `add_test_world`)
+pub fn make_ecs(width: u32, height: u32) -> Ecs {
+    let mut ecs = make_empty_ecs();
+    add_test_world(&mut ecs, width, height);
+    ecs
+}
@@ -1,5 +1,6 @@
 use super::{Tile, TileFlags, TileKind, TileLayer, TileMove};
 use crate::effect::{parse_effect, BoxedEffect};
+use crate::light::{parse_light, Light};
 use crate::sprite::parse_glyph;
 use crate::sprite::Sprite;
 use gw_app::color::get_color;
@@ -30,6 +31,28 @@ impl TileBuilder {
         self
     }
 
+    pub fn spread(mut self, pct: u32) -> Self {
+        self.tile.spread = pct.min(100);
+        self
+    }
+
+    pub fn dissipate(mut self, pct: u32) -> Self {
+        self.tile.dissipate = pct.min(100);
+        self
+    }
+
+    pub fn burn(mut self, chance: u32, on_fire: &str) -> Self {
+        self.tile.burn = chance.min(100);
+        self.tile.on_fire = Some(on_fire.to_uppercase());
+        self
+    }
+
+    pub fn promotes(mut self, chance: u32, promotes_to: Option<&str>) -> Self {
+        self.tile.promote_chance = chance.min(100);
+        self.tile.promotes_to = promotes_to.map(|id| id.to_uppercase());
+        self
+    }
+
     pub fn sprite(mut self, glyph: Glyph, fg: RGBA, bg: RGBA) -> Self {
         self.tile.glyph = glyph;
         self.tile.fg = fg;
@@ -67,6 +90,25 @@ impl TileBuilder {
         self
     }
 
+    pub fn mimic(mut self, tile: Arc<Tile>) -> Self {
+        self.set_mimic(tile);
+        self
+    }
+
+    pub(super) fn set_mimic(&mut self, tile: Arc<Tile>) {
+        self.tile.mimic = Some(tile);
+    }
+
+    pub fn discover(mut self, id: &str) -> Self {
+        self.tile.discover = Some(id.to_uppercase());
+        self
+    }
+
+    pub fn light(mut self, light: Light) -> Self {
+        self.tile.light = Some(light);
+        self
+    }
+
     pub fn blocks_vision(mut self) -> Self {
         self.tile.move_flags |= TileMove::BLOCKS_VISION;
         self
@@ -169,6 +211,12 @@ impl TileBuilder {
             "flavor" => {
                 self.tile.flavor = value.to_string();
             }
+            "discover" => {
+                self.tile.discover = Some(value.to_string().to_uppercase());
+            }
+            "light" => {
+                self.tile.light = Some(parse_light(value)?);
+            }
             "flags" => {
                 self.tile.flags.apply(&value.to_string());
             }
@@ -191,6 +239,28 @@ impl TileBuilder {
                 self.tile.layer = value.to_string().parse().unwrap();
                 self.layer_set = true;
             }
+            "spread" => match value.as_int() {
+                Some(v) if v >= 0 => self.tile.spread = (v as u32).min(100),
+                _ => return Err(format!("Bad spread value for tile - {}", value)),
+            },
+            "burn" => match value.as_int() {
+                Some(v) if v >= 0 => self.tile.burn = (v as u32).min(100),
+                _ => return Err(format!("Bad burn value for tile - {}", value)),
+            },
+            "on_fire" => {
+                self.tile.on_fire = Some(value.to_string().to_uppercase());
+            }
+            "promote_chance" => match value.as_int() {
+                Some(v) if v >= 0 => self.tile.promote_chance = (v as u32).min(100),
+                _ => return Err(format!("Bad promote_chance value for tile - {}", value)),
+            },
+            "promotes_to" => {
+                self.tile.promotes_to = Some(value.to_string().to_uppercase());
+            }
+            "dissipate" => match value.as_int() {
+                Some(v) if v >= 0 => self.tile.dissipate = (v as u32).min(100),
+                _ => return Err(format!("Bad dissipate value for tile - {}", value)),
+            },
 
             // Tile Actions
             "use" => return self.add_effects("use", value),
@@ -215,12 +285,14 @@ impl TileBuilder {
                 }
             }
             "enter" => return self.add_effects("enter", value),
+            "stand" => return self.add_effects("stand", value),
             "exit" => return self.add_effects("exit", value),
             "lock" => return self.add_effects("lock", value),
             "unlock" => return self.add_effects("unlock", value),
             "open" => return self.add_effects("open", value),
             "close" => return self.add_effects("close", value),
             "drop" => return self.add_effects("drop", value),
+            "pickup" => return self.add_effects("pickup", value),
 
             _ => log(format!("Ignoring tile field - {}", field)),
         }
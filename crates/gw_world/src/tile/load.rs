@@ -3,9 +3,19 @@ use super::Tiles;
 use gw_app::loader::{LoadError, LoadHandler};
 use gw_app::log;
 use gw_ecs::prelude::Ecs;
-use gw_util::value::Value;
+use gw_util::value::{Key, Value};
 use std::fs::read_to_string;
 
+fn set_mimic(builder: &mut TileBuilder, tiles: &Tiles, value: &Value) -> Result<(), String> {
+    match tiles.get(&value.to_string()) {
+        None => Err(format!("Unknown mimic tile - {}", value)),
+        Some(tile) => {
+            builder.set_mimic(tile);
+            Ok(())
+        }
+    }
+}
+
 pub fn load_tile_data(dest: &mut Tiles, data: Value) -> Result<u32, String> {
     let map = match data.to_map() {
         None => return Err("Tile data must be a map.".to_string()),
@@ -14,7 +24,11 @@ pub fn load_tile_data(dest: &mut Tiles, data: Value) -> Result<u32, String> {
 
     let mut count: u32 = 0;
 
-    for (name, data) in map.iter() {
+    // The tiles that mimic other tiles go last so that they can find them
+    let mut entries: Vec<(&Key, &Value)> = map.iter().collect();
+    entries.sort_by_key(|(_, data)| data.get_value("mimic").is_some());
+
+    for (name, data) in entries {
         let data_table = match data.as_map() {
             None => return Err(format!("Bad data format - {}", name.to_string())),
             Some(v) => v,
@@ -37,6 +51,10 @@ pub fn load_tile_data(dest: &mut Tiles, data: Value) -> Result<u32, String> {
                         if key == "ch" {
                             continue;
                         }
+                        if key == "mimic" {
+                            set_mimic(&mut builder, dest, value)?;
+                            continue;
+                        }
                         if let Err(e) = builder.set(key, value) {
                             return Err(format!("Error processing tile[{}] - {}", &name, e));
                         }
@@ -52,6 +70,10 @@ pub fn load_tile_data(dest: &mut Tiles, data: Value) -> Result<u32, String> {
         let mut builder = TileBuilder::new(&name.to_string());
 
         for (key, value) in data_table.iter() {
+            if key == "mimic" {
+                set_mimic(&mut builder, dest, value)?;
+                continue;
+            }
             if let Err(e) = builder.set(key, value) {
                 return Err(format!("Error processing tile[{}] - {}", &name, e));
             }
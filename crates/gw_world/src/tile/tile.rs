@@ -1,5 +1,6 @@
 use super::*;
 use crate::effect::BoxedEffect;
+use crate::light::Light;
 use crate::treasure::Treasure;
 use gw_app::Glyph;
 use gw_app::RGBA;
@@ -24,10 +25,21 @@ pub struct Tile {
     pub move_flags: TileMove,
     pub liquid: TileLiquid,
     pub layer: TileLayer,
+    // liquid/gas - pct of the volume that flows to the neighbors (spread) or is lost (dissipate) each turn
+    pub spread: u32,
+    pub dissipate: u32,
+    // fire - chance (each turn) to catch fire when next to a fire and the tile it becomes
+    pub burn: u32,
+    pub on_fire: Option<String>,
+    // promotion - chance each turn and the tile it becomes (None = removed)
+    pub promote_chance: u32,
+    pub promotes_to: Option<String>,
     pub effects: HashMap<String, Vec<BoxedEffect>>,
     pub kind: TileKind,
     pub treasure: Treasure,
-    pub mimic: Option<Arc<Tile>>,
+    pub mimic: Option<Arc<Tile>>, // secret tiles look like this tile
+    pub discover: Option<String>, // secret tiles become this tile when they are discovered
+    pub light: Option<Light>,
     pub object: usize, // TODO - Option<ItemKind>
 
     pub level: usize,
@@ -55,8 +67,16 @@ impl Tile {
             effects: HashMap::new(),
             liquid: TileLiquid::NONE,
             layer: TileLayer::GROUND,
+            spread: 0,
+            dissipate: 0,
+            burn: 0,
+            on_fire: None,
+            promote_chance: 0,
+            promotes_to: None,
 
             mimic: None,
+            discover: None,
+            light: None,
             object: 0,
 
             level: 0,
@@ -80,6 +100,14 @@ impl Tile {
     pub fn is_obstruction(&self) -> bool {
         self.move_flags.contains(TileMove::BLOCKS_DIAGONAL)
     }
+
+    pub fn is_fire(&self) -> bool {
+        self.flags.contains(TileFlags::IS_FIRE)
+    }
+
+    pub fn is_flammable(&self) -> bool {
+        self.burn > 0 && self.on_fire.is_some()
+    }
 }
 
 pub fn tile_is_none(tile: &Arc<Tile>) -> bool {
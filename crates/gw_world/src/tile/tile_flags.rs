@@ -22,6 +22,10 @@ bitflags! {
         const CLIMB_PORTAL = fl!(20);
         const DESCEND_PORTAL = fl!(21);
 
+        const IS_FIRE = fl!(24);   // spreads fire to flammable neighbors
+        const PROMOTE_ON_STEP = fl!(25);
+        const PROMOTE_IF_VACANT = fl!(26);  // only promotes when no beings or items are in the cell
+
         // !!!!!!!!!!!!!!!!!!!!!
         // NOTE - If you add anything, you must add to FromStr impl below!!!!
         // !!!!!!!!!!!!!!!!!!!!!
@@ -71,6 +75,10 @@ impl FromStr for TileFlags {
                 "CLIMB_PORTAL" => result |= TileFlags::CLIMB_PORTAL,
                 "DESCEND_PORTAL" => result |= TileFlags::DESCEND_PORTAL,
 
+                "IS_FIRE" => result |= TileFlags::IS_FIRE,
+                "PROMOTE_ON_STEP" => result |= TileFlags::PROMOTE_ON_STEP,
+                "PROMOTE_IF_VACANT" => result |= TileFlags::PROMOTE_IF_VACANT,
+
                 "" => {}
                 _ => return Err(format!("Unknown TileFlag: {}", s)),
             }
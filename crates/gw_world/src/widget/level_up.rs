@@ -0,0 +1,150 @@
+use crate::being::{Progression, Stats};
+use crate::combat::being_name;
+use gw_app::{AppEvent, Ecs, Screen, ScreenResult};
+use gw_ecs::prelude::{Entity, World};
+use gw_ui::ui::{page, Frame, Margined, Positioned, Text, UI};
+
+/// The lines shown when the being reaches the level - the stats that grew with their new values
+pub fn level_up_lines(world: &World, entity: Entity, level: u32) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{} reaches level {}!",
+        being_name(world, entity),
+        level
+    )];
+
+    let progression = world.read_global::<Progression>();
+    let mut growth: Vec<(String, i32, Option<i32>)> = {
+        let stats = world.read_component::<Stats>();
+        let stats = stats.get(entity);
+        progression
+            .growth()
+            .map(|(stat, delta)| {
+                let max = stats.as_ref().and_then(|s| s.get_max(stat.clone()));
+                (stat.to_string(), *delta, max)
+            })
+            .collect()
+    };
+    growth.sort_by(|a, b| a.0.cmp(&b.0));
+
+    if !growth.is_empty() {
+        lines.push(String::new());
+    }
+    for (name, delta, max) in growth {
+        let value = match max {
+            None => "-".to_string(),
+            Some(v) => v.to_string(),
+        };
+        lines.push(format!(
+            "#[gray]{:<10}#[] {:>4} #[green]({:+})#[]",
+            name, value, delta
+        ));
+    }
+    lines
+}
+
+pub struct LevelUpScreenBuilder {
+    font: String,
+    size: (u32, u32),
+}
+
+impl LevelUpScreenBuilder {
+    fn new() -> Self {
+        LevelUpScreenBuilder {
+            font: "DEFAULT".to_string(),
+            size: (40, 16),
+        }
+    }
+
+    pub fn font(mut self, font: &str) -> Self {
+        self.font = font.to_string();
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    pub fn build(self, world: &World, entity: Entity, level: u32) -> Box<LevelUpScreen> {
+        Box::new(LevelUpScreen::new(
+            self,
+            level_up_lines(world, entity, level),
+        ))
+    }
+}
+
+/// Shows the stats that grew when the hero gained a level - any key closes it.
+/// Register a function that builds it with `register_level_up_screen`.
+pub struct LevelUpScreen {
+    ui: UI,
+}
+
+impl LevelUpScreen {
+    pub fn builder() -> LevelUpScreenBuilder {
+        LevelUpScreenBuilder::new()
+    }
+
+    fn new(builder: LevelUpScreenBuilder, lines: Vec<String>) -> Self {
+        let (width, height) = builder.size;
+        let text = lines.join("\n");
+
+        let ui = page(builder.size, &builder.font, |body| {
+            Frame::new(body, |frame| {
+                frame.title("] Level Up [").margin(1).size(width, height);
+
+                Text::new(frame, |txt| {
+                    txt.text(&text)
+                        .size(width.saturating_sub(4), height.saturating_sub(4));
+                });
+            });
+        });
+
+        LevelUpScreen { ui }
+    }
+}
+
+impl Screen for LevelUpScreen {
+    fn is_full_screen(&self) -> bool {
+        self.ui.is_full_screen()
+    }
+
+    fn input(&mut self, _ecs: &mut Ecs, ev: &AppEvent) -> ScreenResult {
+        match ev {
+            AppEvent::KeyDown(_) => ScreenResult::Pop,
+            _ => ScreenResult::Continue,
+        }
+    }
+
+    fn render(&mut self, ecs: &mut Ecs) {
+        self.ui.render(ecs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::being::{gain_xp, load_progression_data, Being, Stat};
+    use crate::test_util::make_ecs;
+
+    #[test]
+    fn lines_show_growth() {
+        let mut ecs = make_ecs(5, 3);
+        let data = r#"{ "levels": [10], "growth": { "health": 5, "strength": 1 } }"#;
+        let value = gw_util::json::parse_string(data).unwrap();
+        load_progression_data(&mut ecs.write_global::<Progression>(), value).unwrap();
+
+        let world = ecs.current_world_mut();
+        let mut being = Being::new("HERO".to_string());
+        being.name = Some("hero".to_string());
+        let mut stats = Stats::new();
+        stats.set(Stat::HEALTH, 10);
+        let hero = world.spawn((being, stats));
+        gain_xp(world, hero, 10);
+
+        let lines = level_up_lines(world, hero, 2);
+        assert_eq!(lines[0], "hero reaches level 2!");
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2], "#[gray]HEALTH    #[]   15 #[green](+5)#[]");
+        assert_eq!(lines[3], "#[gray]STRENGTH  #[]    1 #[green](+1)#[]");
+    }
+}
@@ -0,0 +1,405 @@
+use super::Viewport;
+use crate::being::Being;
+use crate::fov::FOV;
+use crate::hero::Hero;
+use crate::item::Item;
+use crate::light::is_dark;
+use crate::map::{Cell, CellFlags, Map};
+use crate::memory::{seen_tiles, MapMemory};
+use crate::position::Position;
+use crate::sprite::Sprite;
+use crate::task::{start_travel, travel_path};
+use gw_app::color::named::{BLACK, WHITE};
+use gw_app::{draw, AppEvent, Ecs, Panel, Screen, ScreenResult, Value, VirtualKeyCode};
+use gw_ecs::prelude::World;
+use gw_util::point::Point;
+
+/// What the hero knows about the cell.
+/// Visible cells list the beings, items and tiles there, remembered cells only list the tiles.
+pub fn describe_cell(world: &World, point: Point) -> Vec<String> {
+    let map = world.read_resource::<Map>();
+    let idx = match map.get_wrapped_index(point.x, point.y) {
+        None => return Vec::new(),
+        Some(idx) => idx,
+    };
+
+    let (visible, known) = match world.try_read_resource::<FOV>() {
+        None => (true, true),
+        Some(fov) => (
+            fov.is_visible(point.x, point.y),
+            fov.is_revealed(point.x, point.y) || fov.is_mapped(point.x, point.y),
+        ),
+    };
+    if !known {
+        return vec!["You have not seen this place.".to_string()];
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    let mut descriptions: Vec<String> = Vec::new();
+    let mut add = |name: &str, description: Option<&String>| {
+        if !name.is_empty() {
+            names.push(name.to_string());
+        }
+        if let Some(text) = description.filter(|text| !text.is_empty()) {
+            descriptions.push(text.clone());
+        }
+    };
+
+    if visible && !is_dark(world, point) {
+        let hero = world.read_resource::<Hero>().entity;
+        let beings = world.read_component::<Being>();
+        for entity in map.iter_beings(idx) {
+            match beings.get(entity) {
+                None => {}
+                Some(_) if entity == hero => add("you", None),
+                Some(being) => add(being.name(), being.description.as_ref()),
+            }
+        }
+
+        let items = world.read_component::<Item>();
+        for entity in map.iter_items(idx) {
+            if let Some(item) = items.get(entity) {
+                add(item.name(), item.description.as_ref());
+            }
+        }
+    }
+
+    // Remembered cells are described as they were when last seen (if the world keeps a memory)
+    let tiles = match visible {
+        true => seen_tiles(&map, idx),
+        false => match world.try_read_resource::<MapMemory>() {
+            None => seen_tiles(&map, idx),
+            Some(memory) => memory
+                .get_tiles(point.x, point.y)
+                .cloned()
+                .unwrap_or_default(),
+        },
+    };
+    for tile in tiles.iter() {
+        add(&tile.flavor, Some(&tile.description));
+    }
+
+    let verb = match visible {
+        true => "see",
+        false => "remember",
+    };
+    let mut lines = match names.is_empty() {
+        true => vec![format!("You {} nothing.", verb)],
+        false => vec![format!("You {} {}.", verb, names.join(", "))],
+    };
+    lines.extend(descriptions);
+    lines
+}
+
+/// The sprite of the cell - remembered cells use the map memory (if there is one)
+fn cell_sprite(world: &World, point: Point) -> Option<Sprite> {
+    let map = world.read_resource::<Map>();
+    let idx = map.get_wrapped_index(point.x, point.y)?;
+    let visible = match world.try_read_resource::<FOV>() {
+        None => true,
+        Some(fov) => fov.is_visible(point.x, point.y),
+    };
+    if !visible {
+        let memory = world.try_read_resource::<MapMemory>()?;
+        return memory.get_sprite(point.x, point.y);
+    }
+    Some(map.get_cell(idx).unwrap().sprite())
+}
+
+pub struct LookScreenBuilder {
+    id: String,
+    font: String,
+    extents: (f32, f32, f32, f32),
+    panel_size: (u32, u32),
+    panel_extents: (f32, f32, f32, f32),
+}
+
+impl LookScreenBuilder {
+    fn new(id: &str) -> Self {
+        LookScreenBuilder {
+            id: id.to_string(),
+            font: "DEFAULT".to_string(),
+            extents: (0.0, 0.0, 1.0, 1.0),
+            panel_size: (60, 6),
+            panel_extents: (0.0, 0.8, 1.0, 1.0),
+        }
+    }
+
+    /// The font of the map (and description panel)
+    pub fn font(mut self, font: &str) -> Self {
+        self.font = font.to_string();
+        self
+    }
+
+    /// Where the map is drawn - use the same extents as the game's viewport
+    pub fn extents(mut self, left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        self.extents = (left, top, right, bottom);
+        self
+    }
+
+    pub fn panel_size(mut self, width: u32, height: u32) -> Self {
+        self.panel_size = (width, height);
+        self
+    }
+
+    pub fn panel_extents(mut self, left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        self.panel_extents = (left, top, right, bottom);
+        self
+    }
+
+    pub fn build(self) -> Box<LookScreen> {
+        Box::new(LookScreen::new(self))
+    }
+}
+
+/// Moves a cursor over the map (keys or mouse) and describes the cell under it.
+/// The path from the hero is highlighted - Enter travels there.
+pub struct LookScreen {
+    id: String,
+    viewport: Viewport,
+    panel: Panel,
+    cursor: Option<Point>,
+    path: Vec<Point>,
+}
+
+impl LookScreen {
+    pub fn builder(id: &str) -> LookScreenBuilder {
+        LookScreenBuilder::new(id)
+    }
+
+    fn new(builder: LookScreenBuilder) -> Self {
+        let (left, top, right, bottom) = builder.extents;
+        let viewport = Viewport::builder(&builder.id)
+            .font(&builder.font)
+            .extents(left, top, right, bottom)
+            .build();
+        let (left, top, right, bottom) = builder.panel_extents;
+        let panel = Panel::new(builder.panel_size.0, builder.panel_size.1, &builder.font)
+            .with_extents(left, top, right, bottom);
+
+        LookScreen {
+            id: builder.id,
+            viewport,
+            panel,
+            cursor: None,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn cursor(&self) -> Option<Point> {
+        self.cursor
+    }
+
+    fn hero_point(world: &World) -> Option<Point> {
+        let hero = world.read_resource::<Hero>().entity;
+        let positions = world.read_component::<Position>();
+        positions.get(hero).map(|pos| pos.point())
+    }
+
+    fn clear_cursor(&mut self, world: &mut World) {
+        let mut map = world.write_resource::<Map>();
+        for point in self.path.drain(..).chain(self.cursor.take()) {
+            if let Some(idx) = map.get_wrapped_index(point.x, point.y) {
+                map.clear_flag_with_redraw(idx, CellFlags::IS_CURSOR | CellFlags::IS_HIGHLIGHTED);
+            }
+        }
+    }
+
+    /// Moves the cursor and highlights the path from the hero
+    pub fn set_cursor(&mut self, world: &mut World, point: Point) {
+        let point = match world.read_resource::<Map>().try_wrap_xy(point.x, point.y) {
+            None => return,
+            Some((x, y)) => Point::new(x, y),
+        };
+        self.clear_cursor(world);
+
+        let path = match Self::hero_point(world) {
+            None => Vec::new(),
+            Some(start) => travel_path(world, start, point).unwrap_or_default(),
+        };
+
+        let mut map = world.write_resource::<Map>();
+        for step in path.iter().filter(|step| **step != point) {
+            let idx = map.get_wrapped_index(step.x, step.y).unwrap();
+            map.set_flag(idx, CellFlags::IS_HIGHLIGHTED);
+            map.set_needs_draw(idx);
+        }
+        let idx = map.get_wrapped_index(point.x, point.y).unwrap();
+        map.set_flag(idx, CellFlags::IS_CURSOR);
+        map.set_needs_draw(idx);
+
+        self.path = path;
+        self.cursor = Some(point);
+    }
+
+    fn move_cursor(&mut self, world: &mut World, dx: i32, dy: i32) {
+        if let Some(cursor) = self.cursor {
+            self.set_cursor(world, Point::new(cursor.x + dx, cursor.y + dy));
+        }
+    }
+
+    fn draw_panel(&mut self, world: &World) {
+        let buffer = self.panel.buffer_mut();
+        buffer.fill(Some(0), Some(WHITE.into()), Some(BLACK.into()));
+
+        let cursor = match self.cursor {
+            None => return,
+            Some(cursor) => cursor,
+        };
+        if let Some(sprite) = cell_sprite(world, cursor) {
+            buffer.draw(1, 1, sprite.glyph, sprite.fg, sprite.bg);
+        }
+
+        let mut y = 1;
+        for line in describe_cell(world, cursor) {
+            let (_, height) = draw::plain(buffer).fg(WHITE.into()).wrap(3, y, &line);
+            y += height;
+        }
+    }
+}
+
+impl Screen for LookScreen {
+    fn setup(&mut self, ecs: &mut Ecs) {
+        let world = ecs.current_world_mut();
+        if let Some(point) = Self::hero_point(world) {
+            self.set_cursor(world, point);
+        }
+    }
+
+    fn input(&mut self, ecs: &mut Ecs, ev: &AppEvent) -> ScreenResult {
+        if let Some(result) = self.viewport.input(ecs.current_world_mut(), ev) {
+            return result;
+        }
+
+        let world = ecs.current_world_mut();
+        if let AppEvent::KeyDown(key_down) = ev {
+            match key_down.key_code {
+                VirtualKeyCode::Escape => return ScreenResult::Pop,
+                VirtualKeyCode::Return => {
+                    if let Some(cursor) = self.cursor {
+                        start_travel(world, cursor);
+                    }
+                    return ScreenResult::Pop;
+                }
+                VirtualKeyCode::Up => self.move_cursor(world, 0, -1),
+                VirtualKeyCode::Down => self.move_cursor(world, 0, 1),
+                VirtualKeyCode::Left => self.move_cursor(world, -1, 0),
+                VirtualKeyCode::Right => self.move_cursor(world, 1, 0),
+                _ => {}
+            }
+        }
+        ScreenResult::Continue
+    }
+
+    fn message(&mut self, ecs: &mut Ecs, id: &str, value: Option<Value>) -> ScreenResult {
+        if id != format!("{}_MOVE", self.id) && id != format!("{}_CLICK", self.id) {
+            return ScreenResult::Continue;
+        }
+        let point: Option<Point> = value.and_then(|value| value.try_into().ok());
+        if let Some(point) = point {
+            self.set_cursor(ecs.current_world_mut(), point);
+        }
+        ScreenResult::Continue
+    }
+
+    fn render(&mut self, ecs: &mut Ecs) {
+        self.viewport.draw_level(ecs.current_world_mut());
+        self.draw_panel(ecs.current_world());
+        self.viewport.render(ecs);
+        self.panel.render(ecs);
+    }
+
+    fn teardown(&mut self, ecs: &mut Ecs) {
+        self.clear_cursor(ecs.current_world_mut());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{add_test_world, make_empty_ecs};
+    use crate::tile::{TileBuilder, Tiles};
+
+    fn make_ecs() -> Ecs {
+        let mut ecs = make_empty_ecs();
+        ecs.write_global::<Tiles>().insert(
+            TileBuilder::new("GRASS")
+                .flavor("some grass")
+                .description("It rustles in the wind.")
+                .build(),
+        );
+
+        let world = add_test_world(&mut ecs, 5, 1);
+        let grass = world.read_global::<Tiles>().get("GRASS").unwrap();
+        world.write_resource::<Map>().fill(grass);
+
+        let hero = world.spawn((Being::new("hero".to_string()), Position::new(0, 0)));
+        world.insert_resource(Hero::new(hero));
+        let mut goblin = Being::new("goblin".to_string());
+        goblin.description = Some("It is green.".to_string());
+        world.spawn((goblin, Position::new(2, 0)));
+        ecs
+    }
+
+    #[test]
+    fn describes_what_is_seen() {
+        let mut ecs = make_ecs();
+        let world = ecs.current_world_mut();
+
+        assert_eq!(
+            describe_cell(world, Point::new(2, 0)),
+            vec![
+                "You see goblin, some grass.",
+                "It is green.",
+                "It rustles in the wind."
+            ]
+        );
+
+        // Remembered cells only show the tiles
+        let mut fov = FOV::new(10);
+        fov.resize(5, 1);
+        fov.set_visible(2, 0);
+        fov.promote_flags();
+        world.insert_resource(fov);
+        assert_eq!(
+            describe_cell(world, Point::new(2, 0)),
+            vec!["You remember some grass.", "It rustles in the wind."]
+        );
+        assert_eq!(
+            describe_cell(world, Point::new(4, 0)),
+            vec!["You have not seen this place."]
+        );
+    }
+
+    #[test]
+    fn remembers_what_was_seen() {
+        let mut ecs = make_ecs();
+        let world = ecs.current_world_mut();
+
+        let mut memory = MapMemory::new(5, 1);
+        {
+            let map = world.read_resource::<Map>();
+            memory.set_tiles(2, 0, seen_tiles(&map, 2));
+        }
+        world.insert_resource_non_send(memory);
+
+        // The grass burns away while the hero is not looking
+        let floor = world.read_global::<Tiles>().get("FLOOR").unwrap();
+        world.write_resource::<Map>().reset_tiles(2, floor);
+
+        let mut fov = FOV::new(10);
+        fov.resize(5, 1);
+        fov.set_visible(2, 0);
+        fov.set_visible(3, 0);
+        fov.promote_flags();
+        world.insert_resource(fov);
+        assert_eq!(
+            describe_cell(world, Point::new(2, 0)),
+            vec!["You remember some grass.", "It rustles in the wind."]
+        );
+        assert_eq!(
+            describe_cell(world, Point::new(3, 0)),
+            vec!["You remember nothing."]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::log::Logger;
+use gw_app::{AppEvent, Ecs, Screen, ScreenResult, VirtualKeyCode};
+use gw_ui::ui::{page, Frame, Margined, Positioned, Text, UI};
+use gw_util::text::wrap_colored;
+
+/// The width of the turn column in the history
+const TURN_WIDTH: usize = 6;
+
+/// The full message history as lines of (color markup) text - oldest first.
+/// Each message starts with the turn it was logged on and is wrapped to the width.
+pub fn history_lines(logger: &mut Logger, width: usize) -> Vec<String> {
+    let text_width = width.saturating_sub(TURN_WIDTH).max(1);
+    let msgs: Vec<(u64, String)> = logger.iter().map(|m| (m.turn, m.markup())).collect();
+
+    let mut lines = Vec::new();
+    for (turn, markup) in msgs.iter().rev() {
+        for (i, line) in wrap_colored(text_width, markup).iter().enumerate() {
+            let prefix = match i {
+                0 => format!("{:>w$} ", turn, w = TURN_WIDTH - 1),
+                _ => " ".repeat(TURN_WIDTH),
+            };
+            lines.push(format!("#[gray]{}#[]{}", prefix, line));
+        }
+    }
+    lines
+}
+
+pub struct MessageLogScreenBuilder {
+    font: String,
+    page_size: (u32, u32),
+}
+
+impl MessageLogScreenBuilder {
+    fn new() -> Self {
+        MessageLogScreenBuilder {
+            font: "DEFAULT".to_string(),
+            page_size: (80, 50),
+        }
+    }
+
+    pub fn font(mut self, font: &str) -> Self {
+        self.font = font.to_string();
+        self
+    }
+
+    pub fn page_size(mut self, width: u32, height: u32) -> Self {
+        self.page_size = (width, height);
+        self
+    }
+
+    pub fn build(self) -> Box<MessageLogScreen> {
+        Box::new(MessageLogScreen::new(self))
+    }
+}
+
+/// The whole message history - scrolls with the arrows, page up/down and home/end.
+pub struct MessageLogScreen {
+    ui: UI,
+    size: (u32, u32),
+    lines: Vec<String>,
+    top: usize,
+}
+
+impl MessageLogScreen {
+    pub fn builder() -> MessageLogScreenBuilder {
+        MessageLogScreenBuilder::new()
+    }
+
+    fn new(builder: MessageLogScreenBuilder) -> Self {
+        let (width, height) = builder.page_size;
+        let size = (width.saturating_sub(4), height.saturating_sub(4));
+
+        let ui = page(builder.page_size, &builder.font, |body| {
+            Frame::new(body, |frame| {
+                frame.title("] Messages [").margin(1).size(width, height);
+
+                Text::new(frame, |txt| {
+                    txt.id("LOG").text("").size(size.0, size.1);
+                });
+            });
+        });
+
+        MessageLogScreen {
+            ui,
+            size,
+            lines: Vec::new(),
+            top: 0,
+        }
+    }
+
+    fn page_len(&self) -> usize {
+        self.size.1 as usize
+    }
+
+    fn scroll_to(&mut self, top: usize) {
+        let max_top = self.lines.len().saturating_sub(self.page_len());
+        self.top = top.min(max_top);
+
+        let end = (self.top + self.page_len()).min(self.lines.len());
+        let text = self.lines[self.top..end].join("\n");
+        if let Some(el) = self.ui.find_by_id("LOG") {
+            el.set_text(&text);
+        }
+    }
+
+    fn scroll_by(&mut self, delta: i32) {
+        let top = (self.top as i32 + delta).max(0) as usize;
+        self.scroll_to(top);
+    }
+}
+
+impl Screen for MessageLogScreen {
+    fn is_full_screen(&self) -> bool {
+        self.ui.is_full_screen()
+    }
+
+    fn setup(&mut self, ecs: &mut Ecs) {
+        let mut logger = ecs.write_global::<Logger>();
+        logger.ack_all();
+        self.lines = history_lines(&mut logger, self.size.0 as usize);
+        drop(logger);
+
+        // Start at the newest messages
+        self.scroll_to(self.lines.len());
+    }
+
+    fn input(&mut self, _ecs: &mut Ecs, ev: &AppEvent) -> ScreenResult {
+        let page = self.page_len() as i32;
+        if let AppEvent::KeyDown(key_down) = ev {
+            match key_down.key_code {
+                VirtualKeyCode::Escape | VirtualKeyCode::Return => return ScreenResult::Pop,
+                VirtualKeyCode::Up => self.scroll_by(-1),
+                VirtualKeyCode::Down => self.scroll_by(1),
+                VirtualKeyCode::PageUp => self.scroll_by(-page),
+                VirtualKeyCode::PageDown => self.scroll_by(page),
+                VirtualKeyCode::Home => self.scroll_to(0),
+                VirtualKeyCode::End => self.scroll_to(self.lines.len()),
+                _ => {}
+            }
+        }
+        ScreenResult::Continue
+    }
+
+    fn render(&mut self, ecs: &mut Ecs) {
+        self.ui.render(ecs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn history_has_turns_and_wraps() {
+        let mut logger = Logger::new();
+        logger.set_time(300);
+        logger.log("The rat bites you");
+        logger.log("The rat bites you");
+        logger.set_time(1200);
+        logger.log("You find a very long and shiny sword");
+
+        let lines = history_lines(&mut logger, 26);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "#[gray]    3 #[]#[]The rat bites you x2");
+        assert!(lines[1].starts_with("#[gray]   12 #[]"));
+        assert!(lines[2].starts_with("#[gray]      #[]"));
+    }
+}
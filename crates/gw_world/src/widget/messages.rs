@@ -0,0 +1,138 @@
+use crate::log::Logger;
+use gw_app::color::named::{BLACK, DIM_GRAY, WHITE, YELLOW};
+use gw_app::{draw, AppEvent, Ecs, Panel, ScreenResult};
+use gw_ecs::prelude::World;
+
+pub struct MessagePanelBuilder {
+    font: String,
+    size: (u32, u32),
+    extents: (f32, f32, f32, f32),
+}
+
+impl MessagePanelBuilder {
+    fn new() -> Self {
+        MessagePanelBuilder {
+            font: "DEFAULT".to_string(),
+            size: (60, 4),
+            extents: (0.0, 0.85, 1.0, 1.0),
+        }
+    }
+
+    pub fn font(mut self, font: &str) -> Self {
+        self.font = font.to_string();
+        self
+    }
+
+    /// The width (in characters) and the number of lines shown
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height.max(2));
+        self
+    }
+
+    pub fn extents(mut self, left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        self.extents = (left, top, right, bottom);
+        self
+    }
+
+    pub fn build(self) -> MessagePanel {
+        MessagePanel::new(self)
+    }
+}
+
+/// Shows the latest messages from the Logger.
+/// If more messages came in than fit, they are shown a page at a time with a "--more--" prompt.
+pub struct MessagePanel {
+    panel: Panel,
+    skip_char: bool,
+}
+
+impl MessagePanel {
+    pub fn builder() -> MessagePanelBuilder {
+        MessagePanelBuilder::new()
+    }
+
+    fn new(builder: MessagePanelBuilder) -> Self {
+        let (left, top, right, bottom) = builder.extents;
+        let panel = Panel::new(builder.size.0, builder.size.1, &builder.font)
+            .with_extents(left, top, right, bottom);
+        MessagePanel {
+            panel,
+            skip_char: false,
+        }
+    }
+
+    fn lines(&self) -> usize {
+        self.panel.height() as usize
+    }
+
+    /// Are there more new messages than fit?  The game should wait for the player to acknowledge them.
+    pub fn has_more(&self, world: &World) -> bool {
+        world.write_global::<Logger>().unacked() > self.lines()
+    }
+
+    /// A key press acknowledges the messages on the panel.
+    /// While "--more--" is showing the key is used up - otherwise it is passed on to the game.
+    pub fn input(&mut self, world: &mut World, ev: &AppEvent) -> Option<ScreenResult> {
+        match ev {
+            AppEvent::KeyDown(_) => {
+                let mut logger = world.write_global::<Logger>();
+                self.skip_char = logger.unacked() > self.lines();
+                if self.skip_char {
+                    logger.ack(self.lines() - 1);
+                    return Some(ScreenResult::Continue);
+                }
+                logger.ack_all();
+                None
+            }
+            // The char event that follows the key that closed a "--more--"
+            AppEvent::CharEvent(_) if self.skip_char => {
+                self.skip_char = false;
+                Some(ScreenResult::Continue)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn draw(&mut self, world: &World) {
+        let lines = self.lines();
+        let buffer = self.panel.buffer_mut();
+        buffer.fill(Some(0), Some(WHITE.into()), Some(BLACK.into()));
+        let width = buffer.width() as i32;
+
+        let mut logger = world.write_global::<Logger>();
+        let unacked = logger.unacked();
+
+        if unacked > lines {
+            // The oldest new messages first
+            let mut page: Vec<String> = logger.iter().take(unacked).map(|m| m.markup()).collect();
+            page.reverse();
+            for (y, text) in page.iter().take(lines - 1).enumerate() {
+                draw::colored(buffer)
+                    .fg(WHITE.into())
+                    .width(width)
+                    .print(0, y as i32, text);
+            }
+            draw::plain(buffer)
+                .fg(YELLOW.into())
+                .print(0, lines as i32 - 1, "--more--");
+            return;
+        }
+
+        // The newest message is at the bottom, the ones that were already seen are dimmed
+        for (i, msg) in logger.iter().take(lines).enumerate() {
+            let fg = match msg.acked {
+                true => DIM_GRAY,
+                false => WHITE,
+            };
+            draw::colored(buffer).fg(fg.into()).width(width).print(
+                0,
+                (lines - 1 - i) as i32,
+                &msg.markup(),
+            );
+        }
+    }
+
+    pub fn render(&mut self, ecs: &mut Ecs) {
+        self.panel.render(ecs);
+    }
+}
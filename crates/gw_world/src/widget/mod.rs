@@ -1,2 +1,14 @@
+mod level_up;
+pub use level_up::*;
+
+mod look;
+pub use look::*;
+
+mod message_log;
+pub use message_log::*;
+
+mod messages;
+pub use messages::*;
+
 mod viewport;
 pub use viewport::*;
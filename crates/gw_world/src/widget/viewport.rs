@@ -1,9 +1,13 @@
+use crate::animation::Animations;
+use crate::being::Being;
 use crate::camera::Camera;
 use crate::fov::FOV;
+use crate::hero::Hero;
 use crate::level::NeedsDraw;
+use crate::light::{apply_light, update_lights, LightMap};
 use crate::map::Cell;
 use crate::map::{CellFlags, Map};
-use crate::memory::MapMemory;
+use crate::memory::{seen_tiles, MapMemory};
 use crate::position::Position;
 use crate::sprite::Sprite;
 use gw_app::color::named::BLACK;
@@ -161,7 +165,11 @@ impl Viewport {
     }
 
     pub fn draw_level(&mut self, world: &mut World) {
-        {
+        if update_lights(world) {
+            self.needs_draw = true;
+        }
+
+        let offset = {
             if !world.has_resource::<Camera>() {
                 let map_size = world.read_resource::<Map>().size();
                 let camera = Camera::new(map_size.0, map_size.1);
@@ -173,6 +181,8 @@ impl Viewport {
             let needs_draw = world.read_resource::<NeedsDraw>();
             let mut memory = world.try_write_resource::<MapMemory>();
             let fov = world.try_read_resource::<FOV>();
+            let lights = world.try_read_resource::<LightMap>();
+            let lights = lights.as_deref();
 
             let offset = {
                 if self.con.size() != *camera.size() {
@@ -197,6 +207,7 @@ impl Viewport {
                         map.deref_mut(),
                         memory,
                         &vis,
+                        lights,
                         offset,
                         viewport_needs_draw,
                     );
@@ -208,6 +219,7 @@ impl Viewport {
                         map.deref_mut(),
                         memory,
                         &vis,
+                        lights,
                         offset,
                         viewport_needs_draw,
                     );
@@ -219,6 +231,7 @@ impl Viewport {
                         map.deref_mut(),
                         None,
                         &vis,
+                        lights,
                         offset,
                         viewport_needs_draw,
                     );
@@ -230,15 +243,19 @@ impl Viewport {
                         map.deref_mut(),
                         None,
                         &vis,
+                        lights,
                         offset,
                         viewport_needs_draw,
                     );
                 }
             };
-        }
+            offset
+        };
 
         draw_actors(self, world);
+        draw_animations(self, world, offset);
         clear_needs_draw(self, world);
+        tick_animations(world);
     }
 
     pub fn draw_map(
@@ -250,7 +267,7 @@ impl Viewport {
         force_draw: bool,
     ) {
         let needs_draw = force_draw || self.needs_draw;
-        draw_map(self, map, memory, vis, offset, needs_draw);
+        draw_map(self, map, memory, vis, None, offset, needs_draw);
     }
 
     pub fn render(&mut self, ecs: &mut Ecs) {
@@ -316,6 +333,7 @@ fn draw_map(
     map: &mut Map,
     mut memory: Option<ResMut<MapMemory>>,
     vis: &dyn VisSource,
+    lights: Option<&LightMap>,
     offset: (i32, i32),
     force_draw: bool,
 ) {
@@ -393,6 +411,7 @@ fn draw_map(
                                     tile_sprite.bg,
                                     tile_sprite.glyph,
                                 );
+                                memory.set_tiles(x, y, seen_tiles(map, idx));
                                 map.clear_needs_snapshot(idx);
                             }
                             (tile_sprite.glyph, tile_sprite.fg, tile_sprite.bg)
@@ -425,6 +444,12 @@ fn draw_map(
                             }
                             fg = RGBA::darken(&fg, 0.35); // Need to slightly dim as well
                         } else {
+                            if let Some(lights) = lights {
+                                let light = lights.get(idx);
+                                fg = apply_light(&fg, &light);
+                                bg = apply_light(&bg, &light);
+                            }
+
                             // for actor_id in map.actors_at_xy(x as i32, y as i32) {
                             //     if let Some(sprite) = global_world().get_sprite(actor_id) {
                             //         let sprite = sprite.borrow();
@@ -478,12 +503,15 @@ fn draw_map(
 }
 
 fn draw_actors(viewport: &mut Viewport, world: &mut World) {
-    let (map, camera, position, sprite) = <(
+    let (map, camera, position, sprite, beings) = <(
         ResRef<Map>,
         ResRef<Camera>,
         ReadComp<Position>,
         ReadComp<Sprite>,
+        ReadComp<Being>,
     )>::fetch(world);
+    let lights = world.try_read_resource::<LightMap>();
+    let hero = world.try_read_resource::<Hero>().map(|h| h.entity);
 
     // TODO - USE REGION
 
@@ -513,7 +541,7 @@ fn draw_actors(viewport: &mut Viewport, world: &mut World) {
     };
     let bounds = Rect::with_size(left, top, view_size.0, view_size.1);
 
-    for (pos, sprite) in (&position, &sprite).join() {
+    for (entity, pos, sprite) in (&world.entities(), &position, &sprite).join() {
         if !region.contains(pos.x, pos.y) {
             log("ACTOR NOT IN REGION");
             continue;
@@ -539,6 +567,24 @@ fn draw_actors(viewport: &mut Viewport, world: &mut World) {
 
             if let Some(idx) = map.get_wrapped_index(pos.x, pos.y) {
                 if map.has_flag(idx, CellFlags::DRAWN_THIS_FRAME) {
+                    let (sprite_fg, sprite_bg) = match lights.as_ref() {
+                        None => (sprite.fg, sprite.bg),
+                        Some(lights) => {
+                            // Beings in the dark can not be seen (except for the hero)
+                            if lights.is_dark(idx)
+                                && beings.contains(entity)
+                                && Some(entity) != hero
+                            {
+                                continue;
+                            }
+                            let light = lights.get(idx);
+                            (
+                                apply_light(&sprite.fg, &light),
+                                apply_light(&sprite.bg, &light),
+                            )
+                        }
+                    };
+
                     let fg = buf.get_fore(bufx, bufy).unwrap();
                     let bg = buf.get_back(bufx, bufy).unwrap();
 
@@ -546,8 +592,8 @@ fn draw_actors(viewport: &mut Viewport, world: &mut World) {
                         bufx,
                         bufy,
                         sprite.glyph,
-                        RGBA::alpha_mix(fg, &sprite.fg),
-                        RGBA::alpha_mix(bg, &sprite.bg),
+                        RGBA::alpha_mix(fg, &sprite_fg),
+                        RGBA::alpha_mix(bg, &sprite_bg),
                     );
                 }
             }
@@ -559,6 +605,45 @@ fn draw_actors(viewport: &mut Viewport, world: &mut World) {
     // self.needs_redraw = false;
 }
 
+/// Draws the bolts (arrows, etc...) over the map - one frame per draw
+fn draw_animations(viewport: &mut Viewport, world: &mut World, offset: (i32, i32)) {
+    let animations = match world.try_read_resource::<Animations>() {
+        None => return,
+        Some(animations) => animations,
+    };
+
+    let view_size = viewport.con.size();
+    let buf = viewport.con.buffer_mut();
+
+    for bolt in animations.iter() {
+        let pt = match bolt.point() {
+            None => continue,
+            Some(pt) => pt,
+        };
+        let x = pt.x - offset.0;
+        let y = pt.y - offset.1;
+        if x < 0 || y < 0 || x >= view_size.0 as i32 || y >= view_size.1 as i32 {
+            continue;
+        }
+        let bg = *buf.get_back(x, y).unwrap();
+        buf.draw(x, y, bolt.glyph, RGBA::alpha_mix(&bg, &bolt.fg), bg);
+    }
+}
+
+/// Moves the animations along and redraws the cells they leave
+fn tick_animations(world: &mut World) {
+    let dirty = match world.try_write_resource::<Animations>() {
+        None => return,
+        Some(mut animations) => animations.tick(),
+    };
+    let mut map = world.write_resource::<Map>();
+    for pt in dirty {
+        if let Some(idx) = map.get_wrapped_index(pt.x, pt.y) {
+            map.set_needs_draw(idx);
+        }
+    }
+}
+
 fn clear_needs_draw(viewport: &mut Viewport, world: &mut World) {
     let (mut map, mut camera, mut needs_draw) =
         <(ResMut<Map>, ResMut<Camera>, ResMut<NeedsDraw>)>::fetch(world);